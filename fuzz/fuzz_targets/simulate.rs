@@ -0,0 +1,162 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use process_scheduling_simulator::energy::{DvfsPolicy, FrequencyLevel, PowerModel};
+use process_scheduling_simulator::locking::{CriticalSection, LockProtocol};
+use process_scheduling_simulator::memory::{Admission, MemoryModel};
+use process_scheduling_simulator::multilevel::{
+    ClassQueue, JobClass, MultilevelQueues, QueueSharing,
+};
+use process_scheduling_simulator::process_scheduler::{
+    simulate, Aging, Algorithm, Job, SchedulerConfig, TieBreak, TieBreaking,
+};
+use process_scheduling_simulator::reference::compare_with_reference;
+
+// Times are kept to a byte so a run takes at most a few thousand ticks
+#[derive(Arbitrary, Debug)]
+struct FuzzJob {
+    id: u8,
+    name: String,
+    arrival_time: u8,
+    needed_cpu_cycle: u8,
+    // may name missing jobs or form cycles
+    depends_on: Vec<u8>,
+    // resource, offset and duration; may overlap or run past the job's cycles
+    critical_sections: Vec<(u8, u8, u8)>,
+    deadline: Option<u8>,
+    // may be more than the memory model has
+    memory: u8,
+    class: u8,
+    // left over from an earlier run, simulate must not trust them
+    remaining_cpu_cycle: u32,
+    completion_time: u32,
+    turnaround_time: u32,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    jobs: Vec<FuzzJob>,
+    algorithm: u8,
+    time_quantum: u8,
+    cores: u8,
+    context_switch_cost: u8,
+    seed: u64,
+    tie_break: u8,
+    preempted_first: bool,
+    aging: Option<(f64, u8)>,
+    locking: u8,
+    // frequencies in MHz and power draws, then sleep after, wake latency and policy
+    power: Option<(Vec<(u16, u8)>, u8, u8, bool)>,
+    // size, admission policy and swap cost
+    memory: Option<(u8, u8, Option<u8>)>,
+    // algorithm, time quantum and slice of each class, then time slicing or not
+    multilevel: Option<([(u8, u8, u8); 3], bool)>,
+}
+
+fuzz_target!(|input: Input| {
+    let jobs: Vec<Job> = input
+        .jobs
+        .into_iter()
+        .take(32)
+        .map(|job| Job {
+            id: job.id.into(),
+            job_name: job.name,
+            arrival_time: job.arrival_time.into(),
+            needed_cpu_cycle: job.needed_cpu_cycle.into(),
+            remaining_cpu_cycle: job.remaining_cpu_cycle,
+            completion_time: job.completion_time,
+            turnaround_time: job.turnaround_time,
+            depends_on: job.depends_on.into_iter().map(u32::from).collect(),
+            critical_sections: job
+                .critical_sections
+                .into_iter()
+                .map(|(resource, offset, duration)| CriticalSection {
+                    resource: format!("R{}", resource % 3),
+                    offset: offset.into(),
+                    duration: duration.into(),
+                })
+                .collect(),
+            deadline: job.deadline.map(u32::from),
+            memory: job.memory.into(),
+            class: JobClass::ALL[job.class as usize % JobClass::ALL.len()],
+        })
+        .collect();
+    let config = SchedulerConfig {
+        algorithm: Algorithm::ALL[input.algorithm as usize % Algorithm::ALL.len()],
+        // 0 is allowed, the simulator treats it as 1
+        time_quantum: input.time_quantum.into(),
+        cores: (input.cores % 8).into(),
+        context_switch_cost: (input.context_switch_cost % 8).into(),
+        seed: input.seed,
+        tie_breaking: TieBreaking {
+            rule: TieBreak::ALL[input.tie_break as usize % TieBreak::ALL.len()],
+            preempted_first: input.preempted_first,
+        },
+        aging: input.aging.map(|(rate, cap)| Aging {
+            rate,
+            cap: cap.into(),
+        }),
+        locking: LockProtocol::ALL[input.locking as usize % LockProtocol::ALL.len()],
+        power: input
+            .power
+            .map(|(levels, sleep_after, wake_latency, lowest_feasible)| PowerModel {
+                levels: levels
+                    .into_iter()
+                    .take(4)
+                    .map(|(mhz, power)| FrequencyLevel {
+                        mhz: (mhz % 4000).into(),
+                        power: power.into(),
+                    })
+                    .collect(),
+                sleep_after: sleep_after.into(),
+                wake_latency: (wake_latency % 8).into(),
+                policy: match lowest_feasible {
+                    true => DvfsPolicy::LowestFeasible,
+                    false => DvfsPolicy::RaceToIdle,
+                },
+                ..Default::default()
+            }),
+        memory: input
+            .memory
+            .map(|(size, admission, swap_cost)| MemoryModel {
+                size: size.into(),
+                admission: Admission::ALL[admission as usize % Admission::ALL.len()],
+                swap_cost: swap_cost.map(|cost| (cost % 8).into()),
+            }),
+        multilevel: input.multilevel.map(|(queues, slicing)| {
+            // 0 quanta and slices are allowed, the simulator copes with both
+            let [system, interactive, batch] = queues.map(|(algorithm, time_quantum, slice)| {
+                ClassQueue {
+                    algorithm: Algorithm::ALL[algorithm as usize % Algorithm::ALL.len()],
+                    time_quantum: time_quantum.into(),
+                    slice: (slice % 16).into(),
+                }
+            });
+            MultilevelQueues {
+                system,
+                interactive,
+                batch,
+                sharing: match slicing {
+                    true => QueueSharing::TimeSlicing,
+                    false => QueueSharing::StrictPriority,
+                },
+            }
+        }),
+    };
+
+    let schedule = simulate(&jobs, &config);
+    // the reference has no critical sections, power or memory model or multilevel queues,
+    // so only check that every job still finishes
+    if config.power.is_some()
+        || config.memory.is_some()
+        || config.multilevel.is_some()
+        || jobs.iter().any(|job| !job.critical_sections.is_empty())
+    {
+        assert_eq!(schedule.jobs.len(), jobs.len());
+        return;
+    }
+    if let Err(difference) = compare_with_reference(&jobs, &config, &schedule) {
+        panic!("{config:?}\n{difference}");
+    }
+});
@@ -1,7 +1,24 @@
-use crate::process_scheduler::{job_builder, *};
+use crate::charts;
+use crate::dependencies;
+use crate::energy::{self, CoreState, DvfsPolicy, FrequencyLevel, PowerModel};
+use crate::gantt::text_gantt;
+use crate::generator::{ArrivalProcess, BurstDistribution, WorkloadGenerator};
+use crate::history::{Edit, History};
+use crate::job_table::{self, Column};
+use crate::lifecycle::{self, ProcessState, StateChange};
+use crate::locking::{self, CriticalSection, LockProtocol};
+use crate::manual::{compare, ManualScheduler};
+use crate::memory::{self, Admission, MemoryModel};
+use crate::metrics::{job_metrics, run_metrics, JobMetrics, RunMetrics, Starvation};
+use crate::multilevel::{self, JobClass, MultilevelQueues, QueueSharing};
+use crate::optimal;
+use crate::process_scheduler::{self, job_builder, *};
+use crate::quiz::{self, Answer, AnswerSegment, Grade, Quiz, QuizSet};
+use crate::scenario::{textbook_scenarios, Scenario, ScenarioLibrary};
+use crate::workload::Workload;
 use egui::RichText;
 use egui_dropdown::DropDownBox;
-use std::hash::{Hash, Hasher};
+use std::collections::BTreeSet;
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -12,11 +29,143 @@ pub struct App {
     // TODO: Understand serialization
     #[serde(skip)] // This how you opt-out of serialization of a field
     value: f32,
+    #[serde(skip)]
     process_scheduling_algorithms: Vec<String>,
     time_quantum: u32,
+    cores: u32,
+    context_switch_cost: u32,
+    // no control of its own, kept so a loaded workload saves and runs with its seed
+    seed: u64,
+    tie_breaking: TieBreaking,
     buf: String,
+    #[serde(skip)]
     viewport_open: bool,
+    #[serde(skip)]
     open_sim_string: String,
+    generator: WorkloadGenerator,
+    scenarios: ScenarioLibrary,
+    scenario_name: String,
+    #[serde(skip)]
+    scenario_error: Option<String>,
+    #[serde(skip)]
+    history: History,
+    // last valid algorithm typed into the dropdown, so switching can be undone
+    #[serde(skip)]
+    recorded_algorithm: Option<Algorithm>,
+    #[serde(skip)]
+    selected: BTreeSet<usize>,
+    bulk_cpu_cycle: u32,
+    bulk_arrival_time: u32,
+    #[serde(skip)]
+    paste_text: String,
+    #[serde(skip)]
+    sorted_by: Option<(Column, bool)>,
+    #[serde(skip)]
+    table_error: Option<String>,
+    // "Be the Scheduler" run of the job table, if one was started
+    #[serde(skip)]
+    manual: Option<ManualScheduler>,
+    manual_preemptive: bool,
+    manual_ticks: u32,
+    #[serde(skip)]
+    manual_error: Option<String>,
+    quizzes: QuizSet,
+    quiz_index: usize,
+    quiz_job_count: u32,
+    quiz_algorithm: Algorithm,
+    #[serde(skip)]
+    quiz_answer: Answer,
+    #[serde(skip)]
+    quiz_grade: Option<Grade>,
+    #[serde(skip)]
+    quiz_hint: Option<String>,
+    // quiz set JSON being exported or pasted in for import
+    #[serde(skip)]
+    quiz_json: String,
+    #[serde(skip)]
+    quiz_error: Option<String>,
+    aging: Option<Aging>,
+    locking: LockProtocol,
+    power: Option<PowerModel>,
+    memory: Option<MemoryModel>,
+    multilevel: Option<MultilevelQueues>,
+    results: ResultsView,
+}
+
+/// Settings of the results window that are not part of the simulation.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct ResultsView {
+    // algorithms overlaid on the charts besides the simulated one
+    chart_algorithms: Vec<Algorithm>,
+    utilisation_window: u32,
+    starvation: Starvation,
+    // job shown under Process States, the first to finish if unset
+    lifecycle_job: Option<u32>,
+    // ticks played per second
+    playback_speed: f32,
+    #[serde(skip)]
+    playback_time: f32,
+    #[serde(skip)]
+    playing: bool,
+    // Distance from Optimal, kept until the jobs or config change
+    #[serde(skip)]
+    optimal: Option<OptimalDistance>,
+}
+
+/// The optima and every algorithm's gap from them for one workload.
+struct OptimalDistance {
+    jobs: Vec<Job>,
+    config: SchedulerConfig,
+    preemptive: optimal::Optimum,
+    non_preemptive: optimal::Optimum,
+    gaps: Vec<(Algorithm, f64, f64)>,
+    // average turnaround of the multilevel run and its gap from the preemptive optimum
+    multilevel: Option<(f64, f64)>,
+}
+
+impl ResultsView {
+    /// The distance from optimal of `jobs` under `config`, searched for again only when
+    /// either changed since the last call.
+    fn optimal(&mut self, jobs: &[Job], config: &SchedulerConfig) -> &OptimalDistance {
+        if let Some(cached) = &self.optimal {
+            if cached.jobs != jobs || cached.config != *config {
+                self.optimal = None;
+            }
+        }
+        self.optimal.get_or_insert_with(|| {
+            let preemptive = optimal::preemptive_optimum(jobs, config.cores);
+            // the queues preempt across classes, so only the preemptive optimum bounds them
+            let multilevel = config.multilevel.as_ref().map(|_| {
+                let average =
+                    run_metrics(&simulate(jobs, config), config.cores).average_turnaround_time;
+                (average, preemptive.gap(average))
+            });
+            OptimalDistance {
+                jobs: jobs.to_vec(),
+                config: config.clone(),
+                preemptive,
+                non_preemptive: optimal::non_preemptive_optimum(jobs, config.cores),
+                gaps: optimal::gaps(jobs, config),
+                multilevel,
+            }
+        })
+    }
+}
+
+impl Default for ResultsView {
+    fn default() -> Self {
+        Self {
+            chart_algorithms: Vec::new(),
+            utilisation_window: 10,
+            starvation: Starvation::default(),
+            lifecycle_job: None,
+            playback_speed: 2.0,
+            playback_time: 0.0,
+            playing: false,
+            optimal: None,
+        }
+    }
 }
 
 impl Default for App {
@@ -26,36 +175,772 @@ impl Default for App {
             job_count: 1,
             jobs: Vec::new(),
             value: 2.7,
-            process_scheduling_algorithms: vec![
-                "Random".into(),
-                "First Come First Serve (FCFS)".into(),
-                "Shortest Job Next (SJN)".into(),
-                "Shortest Remaining Time (SRT)".into(),
-                "Round Robin".into(),
-            ],
+            process_scheduling_algorithms: Algorithm::ALL
+                .iter()
+                .map(|algorithm| algorithm.name().to_string())
+                .collect(),
             time_quantum: 5,
+            cores: 1,
+            context_switch_cost: 0,
+            seed: 0,
+            tie_breaking: TieBreaking::default(),
             buf: "First Come First Serve (FCFS)".to_string(),
             viewport_open: false,
             open_sim_string: "Open Simulator".to_string(),
+            generator: WorkloadGenerator::default(),
+            scenarios: ScenarioLibrary::default(),
+            scenario_name: String::new(),
+            scenario_error: None,
+            history: History::default(),
+            recorded_algorithm: None,
+            selected: BTreeSet::new(),
+            bulk_cpu_cycle: 1,
+            bulk_arrival_time: 0,
+            paste_text: String::new(),
+            sorted_by: None,
+            table_error: None,
+            manual: None,
+            manual_preemptive: false,
+            manual_ticks: 1,
+            manual_error: None,
+            quizzes: QuizSet::default(),
+            quiz_index: 0,
+            quiz_job_count: 4,
+            quiz_algorithm: Algorithm::Fcfs,
+            quiz_answer: Answer::default(),
+            quiz_grade: None,
+            quiz_hint: None,
+            quiz_json: String::new(),
+            quiz_error: None,
+            aging: None,
+            locking: LockProtocol::None,
+            power: None,
+            memory: None,
+            multilevel: None,
+            results: ResultsView::default(),
         }
     }
 }
 
 impl App {
     /// Called once before the first frame.
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
-        // Code Below Enables Persistence
-        // if let Some(storage) = cc.storage {
-        //     return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
-        // }
+        if let Some(storage) = cc.storage {
+            let mut app: App = eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            // state saved before jobs had ids
+            ensure_unique_ids(&mut app.jobs);
+            return app;
+        }
 
         Default::default()
     }
+
+    /// The job table and algorithm settings as a workload.
+    fn workload(&self) -> Workload {
+        Workload {
+            jobs: self.jobs.clone(),
+            config: SchedulerConfig {
+                algorithm: Algorithm::from_name(&self.buf).unwrap_or_default(),
+                time_quantum: self.time_quantum,
+                cores: self.cores,
+                context_switch_cost: self.context_switch_cost,
+                seed: self.seed,
+                tie_breaking: self.tie_breaking,
+                aging: self.aging,
+                locking: self.locking,
+                power: self.power.clone(),
+                memory: self.memory.clone(),
+                multilevel: self.multilevel.clone(),
+            },
+        }
+    }
+
+    fn load_workload(&mut self, workload: &Workload) {
+        self.jobs = workload.jobs.clone();
+        self.job_count = self.jobs.len().max(1) as u32;
+        self.buf = workload.config.algorithm.name().to_string();
+        self.time_quantum = workload.config.time_quantum.max(1);
+        self.cores = workload.config.cores.max(1);
+        self.context_switch_cost = workload.config.context_switch_cost;
+        self.seed = workload.config.seed;
+        self.tie_breaking = workload.config.tie_breaking;
+        self.aging = workload.config.aging;
+        self.locking = workload.config.locking;
+        self.power = workload.config.power.clone();
+        self.memory = workload.config.memory.clone();
+        self.multilevel = workload.config.multilevel.clone();
+        self.recorded_algorithm = Some(workload.config.algorithm);
+    }
+
+    /// Adds an edit that was just made to the undo history.
+    fn record(&mut self, edit: Edit) {
+        self.history.push(edit, &self.workload());
+    }
+
+    /// Replaces the whole workload as one undoable step.
+    fn replace_workload(&mut self, description: String, workload: &Workload) {
+        let from = self.workload();
+        self.load_workload(workload);
+        self.record(Edit::Replace {
+            description,
+            from: Box::new(from),
+            to: Box::new(self.workload()),
+        });
+    }
+
+    /// Changes the jobs as one undoable step.
+    fn edit_jobs(&mut self, description: String, change: impl FnOnce(&mut Vec<Job>)) {
+        let mut jobs = self.jobs.clone();
+        change(&mut jobs);
+        let workload = Workload {
+            jobs,
+            ..self.workload()
+        };
+        self.replace_workload(description, &workload);
+    }
+
+    /// Pastes spreadsheet rows over the first selected job onwards, or after the last job.
+    fn paste(&mut self, text: &str) {
+        let rows = match job_table::parse_rows(text) {
+            Ok(rows) => rows,
+            Err(error) => {
+                self.table_error = Some(error);
+                return;
+            }
+        };
+        let at = self.selected.first().copied().unwrap_or(self.jobs.len());
+        self.edit_jobs(format!("Paste {} rows", rows.len()), |jobs| {
+            job_table::paste_rows(jobs, at, &rows)
+        });
+        self.table_error = None;
+        self.selected = (at..at + rows.len()).collect();
+    }
+
+    fn sort_by(&mut self, column: Column) {
+        let descending = self.sorted_by == Some((column, false));
+        self.edit_jobs(format!("Sort by {}", column.name()), |jobs| {
+            job_table::sort_jobs(jobs, column, descending);
+        });
+        self.sorted_by = Some((column, descending));
+        self.selected.clear();
+    }
+
+    /// Selection, insert, delete, bulk set and paste, above the job table.
+    fn table_tools_ui(&mut self, ui: &mut egui::Ui) {
+        // undo or a smaller job count can leave selected rows that are gone
+        let job_count = self.jobs.len();
+        self.selected.retain(|&i| i < job_count);
+        ui.horizontal(|ui| {
+            if ui.button("Select All").clicked() {
+                self.selected = (0..self.jobs.len()).collect();
+            }
+            if ui.button("Select None").clicked() {
+                self.selected.clear();
+            }
+            if ui
+                .button("Insert Job")
+                .on_hover_text("Above the first selected job, or at the end")
+                .clicked()
+            {
+                let at = self.selected.first().copied().unwrap_or(self.jobs.len());
+                self.edit_jobs("Insert job".to_string(), |jobs| {
+                    job_table::insert_job(jobs, at);
+                });
+                self.selected = BTreeSet::from([at]);
+            }
+            // at least one job has to stay
+            let can_delete = !self.selected.is_empty() && self.selected.len() < self.jobs.len();
+            if ui
+                .add_enabled(can_delete, egui::Button::new("Delete Selected"))
+                .clicked()
+            {
+                let selected = std::mem::take(&mut self.selected);
+                self.edit_jobs(format!("Delete {} jobs", selected.len()), |jobs| {
+                    job_table::delete_jobs(jobs, &selected);
+                });
+            }
+        });
+
+        ui.add_enabled_ui(!self.selected.is_empty(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Selected:");
+                ui.add(egui::DragValue::new(&mut self.bulk_cpu_cycle).range(1..=u16::MAX));
+                if ui.button("Set CPU Cycle").clicked() {
+                    let (selected, value) = (self.selected.clone(), self.bulk_cpu_cycle);
+                    self.edit_jobs(
+                        format!("Set CPU cycle of {} jobs to {value}", selected.len()),
+                        |jobs| {
+                            for &i in &selected {
+                                jobs[i].needed_cpu_cycle = value;
+                                jobs[i].remaining_cpu_cycle = value;
+                            }
+                        },
+                    );
+                }
+                ui.add(egui::DragValue::new(&mut self.bulk_arrival_time).range(0..=u16::MAX));
+                if ui.button("Set Arrival Time").clicked() {
+                    let (selected, value) = (self.selected.clone(), self.bulk_arrival_time);
+                    self.edit_jobs(
+                        format!("Set arrival time of {} jobs to {value}", selected.len()),
+                        |jobs| {
+                            for &i in &selected {
+                                jobs[i].arrival_time = value;
+                            }
+                        },
+                    );
+                }
+            });
+        });
+
+        ui.collapsing("Paste Rows", |ui| {
+            ui.label(
+                "Rows copied from a spreadsheet (tab or comma separated): job, CPU cycle, \
+                 arrival time, or a header row naming the columns. Ctrl+V outside a text field \
+                 pastes the clipboard the same way.",
+            );
+            ui.add(
+                egui::TextEdit::multiline(&mut self.paste_text)
+                    .desired_rows(4)
+                    .code_editor(),
+            );
+            if ui.button("Paste").clicked() {
+                let text = std::mem::take(&mut self.paste_text);
+                self.paste(&text);
+            }
+        });
+        if let Some(error) = &self.table_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+
+    fn randomize(&mut self) {
+        let workload = Workload {
+            jobs: self.generator.generate(self.jobs.clone()),
+            ..self.workload()
+        };
+        self.replace_workload(
+            format!("Randomize (seed {})", self.generator.seed),
+            &workload,
+        );
+    }
+
+    fn undo(&mut self) {
+        let mut workload = self.workload();
+        if self.history.undo(&mut workload) {
+            self.load_workload(&workload);
+        }
+    }
+
+    fn redo(&mut self) {
+        let mut workload = self.workload();
+        if self.history.redo(&mut workload) {
+            self.load_workload(&workload);
+        }
+    }
+
+    fn history_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!self.history.done().is_empty(), egui::Button::new("Undo"))
+                .on_hover_text("Ctrl+Z")
+                .clicked()
+            {
+                self.undo();
+            }
+            if ui
+                .add_enabled(!self.history.undone().is_empty(), egui::Button::new("Redo"))
+                .on_hover_text("Ctrl+Shift+Z")
+                .clicked()
+            {
+                self.redo();
+            }
+        });
+
+        // Oldest first, with the steps that can be redone greyed out after the current one
+        let current = self.history.done().len();
+        let steps: Vec<String> = std::iter::once("Start".to_string())
+            .chain(
+                self.history
+                    .done()
+                    .iter()
+                    .map(|step| step.description.clone()),
+            )
+            .chain(
+                self.history
+                    .undone()
+                    .iter()
+                    .rev()
+                    .map(|step| step.description.clone()),
+            )
+            .collect();
+        let mut go_to = None;
+        egui::ScrollArea::vertical()
+            .max_height(150.0)
+            .show(ui, |ui| {
+                for (position, description) in steps.into_iter().enumerate() {
+                    let text = match position > current {
+                        true => RichText::new(description).weak(),
+                        false => RichText::new(description),
+                    };
+                    if ui.selectable_label(position == current, text).clicked() {
+                        go_to = Some(position);
+                    }
+                }
+            });
+        if let Some(position) = go_to {
+            let mut workload = self.workload();
+            self.history.go_to(position, &mut workload);
+            self.load_workload(&workload);
+        }
+    }
+
+    /// Lets the user pick which job runs next on a single core, then compares the result.
+    fn manual_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let label = match self.manual {
+                Some(_) => "Restart",
+                None => "Start",
+            };
+            if ui
+                .button(label)
+                .on_hover_text("Schedule the job table by hand")
+                .clicked()
+            {
+                self.manual = Some(ManualScheduler::new(&self.jobs));
+                self.manual_error = None;
+            }
+            ui.checkbox(&mut self.manual_preemptive, "Preemptive");
+            if self.manual_preemptive {
+                ui.label("Run for:");
+                ui.add(
+                    egui::DragValue::new(&mut self.manual_ticks)
+                        .range(1..=u16::MAX)
+                        .speed(0.05),
+                );
+            }
+        });
+        let config = self.workload().config;
+        let Some(manual) = &mut self.manual else {
+            return;
+        };
+
+        if let Some(gantt) = text_gantt(&manual.schedule().segments, 1, manual.clock(), 60) {
+            for line in gantt.plain_lines(&manual.schedule().segments) {
+                ui.monospace(line);
+            }
+        }
+        if let Some(error) = &self.manual_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        if !manual.is_finished() {
+            ui.label(format!("Clock: {}", manual.clock()));
+            if let Some(arrival_time) = manual.next_arrival() {
+                ui.label(format!("Next arrival at {arrival_time}"));
+            }
+            let ticks = self.manual_preemptive.then_some(self.manual_ticks);
+            let mut picked = None;
+            egui::Grid::new("manual_ready_queue")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Ready Queue");
+                    ui.label("Arrival Time");
+                    ui.label("CPU Cycle");
+                    ui.label("Remaining");
+                    ui.end_row();
+                    for job in manual.ready() {
+                        let color = job_color(job.id);
+                        if ui
+                            .add(
+                                egui::Button::new(
+                                    RichText::new(format!("Run {}", job.job_name))
+                                        .color(egui::Color32::BLACK),
+                                )
+                                .fill(color),
+                            )
+                            .clicked()
+                        {
+                            picked = Some(job.id);
+                        }
+                        ui.label(job.arrival_time.to_string());
+                        ui.label(job.needed_cpu_cycle.to_string());
+                        ui.label(job.remaining_cpu_cycle.to_string());
+                        ui.end_row();
+                    }
+                });
+            if let Some(job_id) = picked {
+                self.manual_error = manual.run(job_id, ticks).err();
+            }
+            return;
+        }
+
+        // Every algorithm on the same jobs, the best average in each column in bold
+        let rows = compare(manual.jobs(), manual.schedule(), &config);
+        let averages = |metrics: &RunMetrics| {
+            [
+                metrics.average_turnaround_time,
+                metrics.average_waiting_time,
+                metrics.average_response_time,
+            ]
+        };
+        let mut best = [f64::INFINITY; 3];
+        for (_, metrics) in &rows {
+            for (best, value) in best.iter_mut().zip(averages(metrics)) {
+                *best = best.min(value);
+            }
+        }
+        // measured against what the user could reach: without preemption unless they
+        // used it, and without critical sections which manual mode leaves out
+        let user = rows[0].1.average_turnaround_time;
+        let segments = &manual.schedule().segments;
+        let preempted = segments.iter().enumerate().any(|(i, segment)| {
+            segments[i + 1..]
+                .iter()
+                .any(|later| later.job_id == segment.job_id)
+        });
+        let jobs: Vec<Job> = manual
+            .jobs()
+            .iter()
+            .map(|job| Job {
+                critical_sections: Vec::new(),
+                ..job.clone()
+            })
+            .collect();
+        let (kind, optimum) = match self.manual_preemptive || preempted {
+            true => ("preemptive", optimal::preemptive_optimum(&jobs, 1)),
+            false => ("non-preemptive", optimal::non_preemptive_optimum(&jobs, 1)),
+        };
+        let dependencies = jobs.iter().any(|job| !job.depends_on.is_empty());
+        ui.label(match (dependencies, optimum.exact) {
+            (true, _) => format!(
+                "Your average turnaround time is {user:.2}. Ignoring dependencies, the best \
+                {kind} schedule would average {:.2}.",
+                optimum.average_turnaround_time
+            ),
+            (false, true) => format!(
+                "Your average turnaround time is {user:.2}, {:.2} above the {kind} optimum.",
+                user - optimum.average_turnaround_time
+            ),
+            (false, false) => format!(
+                "Your average turnaround time is {user:.2}, the {kind} optimum is {}.",
+                optimum.describe()
+            ),
+        });
+        egui::Grid::new("manual_comparison")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Schedule");
+                ui.label("Average Turnaround");
+                ui.label("Average Waiting");
+                ui.label("Average Response");
+                ui.label("Context Switches");
+                ui.end_row();
+                for (name, metrics) in &rows {
+                    ui.label(name);
+                    for (value, best) in averages(metrics).into_iter().zip(best) {
+                        let text = RichText::new(format!("{value:.2}"));
+                        ui.label(match value <= best + 1e-9 {
+                            true => text.strong(),
+                            false => text,
+                        });
+                    }
+                    ui.label(metrics.context_switches.to_string());
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// Starts answering quiz `index` of the set from scratch.
+    fn open_quiz(&mut self, index: usize) {
+        self.quiz_index = index;
+        self.quiz_answer = self
+            .quizzes
+            .quizzes
+            .get(index)
+            .map(Answer::blank)
+            .unwrap_or_default();
+        self.quiz_grade = None;
+        self.quiz_hint = None;
+    }
+
+    /// Work out a hidden schedule by hand and have it graded.
+    fn quiz_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Jobs:");
+            ui.add(egui::DragValue::new(&mut self.quiz_job_count).range(1..=26));
+            egui::ComboBox::from_id_salt("quiz_algorithm")
+                .selected_text(self.quiz_algorithm.short_name().to_uppercase())
+                .show_ui(ui, |ui| {
+                    // Random has no rule to work out
+                    for algorithm in Algorithm::ALL {
+                        if algorithm != Algorithm::Random {
+                            ui.selectable_value(
+                                &mut self.quiz_algorithm,
+                                algorithm,
+                                algorithm.name(),
+                            );
+                        }
+                    }
+                });
+            if ui.button("New Quiz").clicked() {
+                let name = format!("Quiz {}", self.quizzes.quizzes.len() + 1);
+                let config = SchedulerConfig {
+                    algorithm: self.quiz_algorithm,
+                    time_quantum: self.time_quantum,
+                    ..Default::default()
+                };
+                self.quizzes
+                    .quizzes
+                    .push(Quiz::generate(&name, self.quiz_job_count, config));
+                self.open_quiz(self.quizzes.quizzes.len() - 1);
+            }
+        });
+
+        ui.collapsing("Export / Import", |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Export").clicked() {
+                    self.quiz_json = self.quizzes.to_json();
+                    ui.ctx().copy_text(self.quiz_json.clone());
+                }
+                if ui.button("Import").clicked() {
+                    match QuizSet::from_json(&self.quiz_json) {
+                        Ok(set) => {
+                            self.quizzes = set;
+                            self.quiz_error = None;
+                            self.open_quiz(0);
+                        }
+                        Err(error) => self.quiz_error = Some(error),
+                    }
+                }
+                if ui.button("Clear Set").clicked() {
+                    self.quizzes = QuizSet::default();
+                    self.open_quiz(0);
+                }
+            });
+            ui.add(
+                egui::TextEdit::multiline(&mut self.quiz_json)
+                    .hint_text("Quiz set JSON, copied to the clipboard on export")
+                    .desired_rows(3),
+            );
+        });
+        if let Some(error) = &self.quiz_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        let mut open = None;
+        ui.horizontal_wrapped(|ui| {
+            for (index, quiz) in self.quizzes.quizzes.iter().enumerate() {
+                if ui
+                    .selectable_label(index == self.quiz_index, &quiz.name)
+                    .clicked()
+                {
+                    open = Some(index);
+                }
+            }
+        });
+        if let Some(index) = open {
+            self.open_quiz(index);
+        }
+        let Some(quiz) = self.quizzes.quizzes.get(self.quiz_index).cloned() else {
+            return;
+        };
+        // answers from before the quiz changed, e.g. after an import
+        if self.quiz_answer.jobs.len() != quiz.workload.jobs.len() {
+            self.open_quiz(self.quiz_index);
+        }
+
+        let config = &quiz.workload.config;
+        ui.label(match config.algorithm {
+            Algorithm::RoundRobin => format!(
+                "Schedule these jobs with {}, time quantum {}.",
+                config.algorithm.name(),
+                config.time_quantum
+            ),
+            algorithm => format!("Schedule these jobs with {}.", algorithm.name()),
+        });
+        let name = |job_id: u32| {
+            quiz.workload
+                .jobs
+                .iter()
+                .find(|job| job.id == job_id)
+                .map_or("?", |job| job.job_name.as_str())
+        };
+
+        let first_wrong = self
+            .quiz_grade
+            .as_ref()
+            .and_then(|grade| grade.first_wrong_segment);
+        let mut remove = None;
+        egui::Grid::new("quiz_segments")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Segment");
+                ui.label("Job");
+                ui.label("Start");
+                ui.label("End");
+                ui.end_row();
+                for (row, segment) in self.quiz_answer.segments.iter_mut().enumerate() {
+                    let label = RichText::new((row + 1).to_string());
+                    ui.label(match first_wrong == Some(row) {
+                        true => label.color(egui::Color32::RED).strong(),
+                        false => label,
+                    });
+                    egui::ComboBox::from_id_salt(("quiz_segment_job", row))
+                        .selected_text(name(segment.job_id))
+                        .show_ui(ui, |ui| {
+                            for job in &quiz.workload.jobs {
+                                ui.selectable_value(&mut segment.job_id, job.id, &job.job_name);
+                            }
+                        });
+                    ui.add(egui::DragValue::new(&mut segment.start));
+                    ui.add(egui::DragValue::new(&mut segment.end));
+                    if ui.button("x").clicked() {
+                        remove = Some(row);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(row) = remove {
+            self.quiz_answer.segments.remove(row);
+        }
+        if first_wrong == Some(self.quiz_answer.segments.len()) {
+            ui.colored_label(egui::Color32::RED, "Segments are missing.");
+        }
+        if ui.button("Add Segment").clicked() {
+            let last = self.quiz_answer.segments.last().copied();
+            let start = last.map_or(0, |segment| segment.end);
+            self.quiz_answer.segments.push(AnswerSegment {
+                job_id: match last {
+                    Some(segment) => segment.job_id,
+                    None => quiz.workload.jobs.first().map_or(0, |job| job.id),
+                },
+                start,
+                end: start + 1,
+            });
+        }
+
+        egui::Grid::new("quiz_jobs").striped(true).show(ui, |ui| {
+            ui.label("Job");
+            ui.label("Arrival Time");
+            ui.label("CPU Cycle");
+            ui.label("Completion Time");
+            ui.label("Turnaround Time");
+            ui.end_row();
+            for (job, answer) in quiz.workload.jobs.iter().zip(&mut self.quiz_answer.jobs) {
+                ui.label(&job.job_name);
+                ui.label(job.arrival_time.to_string());
+                ui.label(job.needed_cpu_cycle.to_string());
+                ui.add(egui::DragValue::new(&mut answer.completion_time));
+                ui.add(egui::DragValue::new(&mut answer.turnaround_time));
+                ui.end_row();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Check").clicked() {
+                self.quiz_grade = Some(quiz::grade(&quiz, &self.quiz_answer));
+                self.quiz_hint = None;
+            }
+            if ui.button("Hint").clicked() {
+                self.quiz_hint = Some(
+                    quiz::hint(&quiz, &self.quiz_answer)
+                        .unwrap_or_else(|| "Nothing to fix, every answer is right.".to_string()),
+                );
+            }
+            if ui.button("Reset").clicked() {
+                self.open_quiz(self.quiz_index);
+            }
+        });
+        if let Some(grade) = &self.quiz_grade {
+            ui.label(format!(
+                "Score {:.0}%: {} of {} segments and {} of {} times right.",
+                grade.score * 100.0,
+                grade.correct_segments,
+                grade.solution_segments,
+                grade.correct_times,
+                grade.solution_times
+            ));
+        }
+        if let Some(hint) = &self.quiz_hint {
+            ui.label(hint);
+        }
+    }
+
+    fn scenarios_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            ui.text_edit_singleline(&mut self.scenario_name);
+            if ui.button("Save As").clicked() {
+                let result = self.scenarios.save_as(&self.scenario_name, self.workload());
+                self.scenario_error = result.err();
+            }
+        });
+        if let Some(error) = &self.scenario_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        enum Action {
+            Load(Scenario),
+            Duplicate(Scenario),
+            Rename(String),
+            Delete(String),
+        }
+        let mut action = None;
+        egui::Grid::new("scenarios").striped(true).show(ui, |ui| {
+            for scenario in &self.scenarios.scenarios {
+                ui.label(&scenario.name);
+                if ui.button("Load").clicked() {
+                    action = Some(Action::Load(scenario.clone()));
+                }
+                if ui.button("Duplicate").clicked() {
+                    action = Some(Action::Duplicate(scenario.clone()));
+                }
+                if ui
+                    .button("Rename")
+                    .on_hover_text("Rename to the name above")
+                    .clicked()
+                {
+                    action = Some(Action::Rename(scenario.name.clone()));
+                }
+                if ui.button("Delete").clicked() {
+                    action = Some(Action::Delete(scenario.name.clone()));
+                }
+                ui.end_row();
+            }
+            for scenario in textbook_scenarios() {
+                ui.label(RichText::new(&scenario.name).italics());
+                if ui.button("Load").clicked() {
+                    action = Some(Action::Load(scenario.clone()));
+                }
+                if ui.button("Duplicate").clicked() {
+                    action = Some(Action::Duplicate(scenario));
+                }
+                ui.end_row();
+            }
+        });
+
+        let Some(action) = action else {
+            return;
+        };
+        self.scenario_error = match action {
+            Action::Load(scenario) => {
+                self.replace_workload(format!("Load {}", scenario.name), &scenario.workload);
+                self.scenario_name = scenario.name;
+                None
+            }
+            Action::Duplicate(scenario) => {
+                self.scenario_name = self.scenarios.duplicate(&scenario);
+                None
+            }
+            Action::Rename(name) => self.scenarios.rename(&name, &self.scenario_name).err(),
+            Action::Delete(name) => self.scenarios.delete(&name).err(),
+        };
+    }
 }
 
 impl eframe::App for App {
@@ -66,6 +951,31 @@ impl eframe::App for App {
 
     /// Called each time the UI needs repainting, which may be many times per second.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Text fields keep their own undo while they have focus
+        if ctx.memory(|memory| memory.focused().is_none()) {
+            let shift_z = egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+                egui::Key::Z,
+            );
+            let z = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+            // Ctrl+Z also matches with shift held, so check for redo first
+            if ctx.input_mut(|input| input.consume_shortcut(&shift_z)) {
+                self.redo();
+            } else if ctx.input_mut(|input| input.consume_shortcut(&z)) {
+                self.undo();
+            }
+
+            let pasted = ctx.input(|input| {
+                input.events.iter().find_map(|event| match event {
+                    egui::Event::Paste(text) => Some(text.clone()),
+                    _ => None,
+                })
+            });
+            if let Some(text) = pasted {
+                self.paste(&text);
+            }
+        }
+
         // Put your widgets into a `SidePanel`, `TopBottomPanel`, `CentralPanel`, `Window` or `Area`.
         // For inspiration and more examples, go to https://emilk.github.io/egui
 
@@ -103,6 +1013,10 @@ impl eframe::App for App {
 
             ui.horizontal(|ui| {
                 ui.label("Process Scheduling Algorithm:");
+                // multilevel queues pick an algorithm per class instead
+                if self.multilevel.is_some() {
+                    ui.disable();
+                }
                 // ui.text_edit_singleline(&mut self.label);
                 ui.add(
                     DropDownBox::from_iter(
@@ -121,6 +1035,103 @@ impl eframe::App for App {
                                             // default is None internally, so TextEdit does whatever its default implements
                 );
             });
+            if let Some(algorithm) = Algorithm::from_name(&self.buf) {
+                if let Some(from) = self.recorded_algorithm.filter(|from| *from != algorithm) {
+                    self.record(Edit::Algorithm {
+                        from,
+                        to: algorithm,
+                    });
+                }
+                self.recorded_algorithm = Some(algorithm);
+            }
+            self.multilevel_ui(ui);
+
+            ui.horizontal(|ui| {
+                let from = self.tie_breaking;
+                ui.label("Tie-break:");
+                egui::ComboBox::from_id_salt("tie_break")
+                    .selected_text(self.tie_breaking.rule.name())
+                    .show_ui(ui, |ui| {
+                        for rule in TieBreak::ALL {
+                            ui.selectable_value(&mut self.tie_breaking.rule, rule, rule.name());
+                        }
+                    });
+                if matches!(
+                    Algorithm::from_name(&self.buf),
+                    Some(Algorithm::Srt | Algorithm::RoundRobin)
+                ) {
+                    ui.checkbox(
+                        &mut self.tie_breaking.preempted_first,
+                        "Preempted jobs queue ahead of new arrivals",
+                    );
+                }
+                if self.tie_breaking != from {
+                    self.record(Edit::TieBreaking {
+                        from,
+                        to: self.tie_breaking,
+                    });
+                }
+            });
+
+            if matches!(
+                Algorithm::from_name(&self.buf),
+                Some(Algorithm::Fcfs | Algorithm::Sjn | Algorithm::Srt)
+            ) {
+                ui.horizontal(|ui| {
+                    let from = self.aging;
+                    let mut enabled = self.aging.is_some();
+                    ui.checkbox(&mut enabled, "Aging");
+                    if enabled != self.aging.is_some() {
+                        self.aging = enabled.then(Aging::default);
+                    }
+                    if let Some(aging) = &mut self.aging {
+                        ui.label("Per Tick Waited:");
+                        ui.add(
+                            egui::DragValue::new(&mut aging.rate)
+                                .range(0.0..=100.0)
+                                .speed(0.05),
+                        );
+                        ui.label("Cap:");
+                        ui.add(egui::DragValue::new(&mut aging.cap).range(0..=u16::MAX));
+                    }
+                    if self.aging != from {
+                        self.record(Edit::Aging {
+                            from,
+                            to: self.aging,
+                        });
+                    }
+                });
+            }
+
+            if self
+                .jobs
+                .iter()
+                .any(|job| !job.critical_sections.is_empty())
+            {
+                ui.horizontal(|ui| {
+                    let from = self.locking;
+                    ui.label("Locking:");
+                    egui::ComboBox::from_id_salt("locking")
+                        .selected_text(self.locking.name())
+                        .show_ui(ui, |ui| {
+                            for protocol in LockProtocol::ALL {
+                                ui.selectable_value(&mut self.locking, protocol, protocol.name());
+                            }
+                        });
+                    if self.locking != from {
+                        self.record(Edit::Locking {
+                            from,
+                            to: self.locking,
+                        });
+                    }
+                });
+                if let Err(error) = locking::check_critical_sections(&self.jobs) {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+            }
+
+            self.memory_ui(ui);
+            self.power_ui(ui);
 
             ui.horizontal(|ui| {
                 if ui
@@ -130,7 +1141,7 @@ impl eframe::App for App {
                     )
                     .clicked()
                 {
-                    if self.viewport_open == true {
+                    if self.viewport_open {
                         self.viewport_open = false;
                         self.open_sim_string = "Open Simulator".to_string();
                     } else {
@@ -147,27 +1158,20 @@ impl eframe::App for App {
                     )
                     .clicked()
                 {
-                    self.jobs = randomize_jobs(self.jobs.clone())
+                    self.randomize();
+                }
+                ui.label("Seed:");
+                ui.add(egui::DragValue::new(&mut self.generator.seed));
+                if ui.button("New Seed").clicked() {
+                    self.generator.seed = rand::random();
+                    self.randomize();
                 }
 
                 // TODO: Allow for User Closing
                 // TODO: Force Close Window on Unsafe Operations
                 if self.viewport_open {
-                    let algorithm_num = match self.buf.as_str() {
-                        "Random" => 0,
-                        "First Come First Serve (FCFS)" => 1,
-                        "Shortest Job Next (SJN)" => 2,
-                        "Shortest Remaining Time (SRT)" => 3,
-                        "Round Robin" => 4,
-                        _ => -1, // Unknown, program will panic
-                    };
-                    if algorithm_num != -1 {
-                        self.spawn_new_window(
-                            ctx,
-                            self.buf.clone(),
-                            self.jobs.clone(),
-                            self.time_quantum.clone(),
-                        );
+                    if Algorithm::from_name(&self.buf).is_some() {
+                        self.spawn_new_window(ctx, self.workload().config, self.jobs.clone());
                     } else {
                         self.viewport_open = false;
                         self.buf = "First Come First Serve (FCFS)".to_string();
@@ -176,123 +1180,597 @@ impl eframe::App for App {
                 }
             });
 
+            ui.collapsing("Randomize Settings", |ui| {
+                generator_ui(ui, &mut self.generator)
+            });
+            ui.collapsing("Scenarios", |ui| self.scenarios_ui(ui));
+            ui.collapsing("History", |ui| self.history_ui(ui));
+            ui.collapsing("Be the Scheduler", |ui| self.manual_ui(ui));
+            ui.collapsing("Quiz", |ui| self.quiz_ui(ui));
+
             if self.jobs.len() as u32 != self.job_count {
-                self.jobs = job_builder(&self.jobs, self.job_count);
+                let jobs = job_builder(&self.jobs, self.job_count);
+                // the first frame fills the empty table, nothing to undo there
+                if !self.jobs.is_empty() {
+                    let from = std::mem::take(&mut self.jobs);
+                    self.jobs = jobs.clone();
+                    self.record(Edit::Resize { from, to: jobs });
+                } else {
+                    self.jobs = jobs;
+                }
             }
             ui.add_space(16.0);
             // TIME QUANTUM
             // TODO: MAKE SURE THIS IS CHECKED!
             if self.buf == self.process_scheduling_algorithms[4] {
                 ui.horizontal(|ui| {
-                    ui.label(format!("Time Quantum: "));
-                    ui.add(
-                        egui::DragValue::new(&mut self.time_quantum)
-                            .range(1..=u16::MAX)
-                            .speed(0.02),
-                    );
+                    ui.label("Time Quantum: ");
+                    let from = self.time_quantum;
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut self.time_quantum)
+                                .range(1..=u16::MAX)
+                                .speed(0.02),
+                        )
+                        .changed()
+                    {
+                        self.record(Edit::TimeQuantum {
+                            from,
+                            to: self.time_quantum,
+                        });
+                    }
                 });
             }
+            ui.horizontal(|ui| {
+                ui.label("Cores: ");
+                let from = self.cores;
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut self.cores)
+                            .range(1..=64)
+                            .speed(0.02),
+                    )
+                    .changed()
+                {
+                    self.record(Edit::Cores {
+                        from,
+                        to: self.cores,
+                    });
+                }
+                ui.label("Context Switch Cost: ");
+                let from = self.context_switch_cost;
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut self.context_switch_cost)
+                            .range(0..=u16::MAX)
+                            .speed(0.02),
+                    )
+                    .on_hover_text("Ticks a core spends switching from one job to another")
+                    .changed()
+                {
+                    self.record(Edit::ContextSwitchCost {
+                        from,
+                        to: self.context_switch_cost,
+                    });
+                }
+            });
 
-            egui::Grid::new("some_unique_id")
-                .striped(true)
-                .show(ui, |ui| {
-                    ui.label("Job Details");
-                    ui.label("CPU Cycle");
-                    ui.label("Arrival Time");
-                    ui.label("Move Up");
-                    ui.label("Move Down");
-                    ui.end_row();
+            self.table_tools_ui(ui);
+
+            ui.horizontal_top(|ui| {
+                egui::Grid::new("some_unique_id")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("Select");
+                        for column in Column::ALL {
+                            let heading = match column {
+                                Column::JobName => "Job Details",
+                                _ => column.name(),
+                            };
+                            let heading = match self.sorted_by {
+                                Some((sorted, false)) if sorted == column => format!("{heading} ^"),
+                                Some((sorted, true)) if sorted == column => format!("{heading} v"),
+                                _ => heading.to_string(),
+                            };
+                            if ui
+                                .add(egui::Button::new(heading).frame(false))
+                                .on_hover_text("Sort")
+                                .clicked()
+                            {
+                                self.sort_by(column);
+                            }
+                        }
+                        ui.label("Depends On");
+                        ui.label("Critical Sections");
+                        ui.label("Deadline");
+                        ui.label("Memory");
+                        ui.label("Class");
+                        ui.label("Move Up");
+                        ui.label("Move Down");
+                        ui.end_row();
+
+                        for i in 0..self.jobs.len() {
+                            let color = job_color(self.jobs[i].id);
 
-                    for i in 0..self.jobs.len() {
-                        let color = {
-                            let mut hasher = std::collections::hash_map::DefaultHasher::new();
-                            self.jobs[i].job_name.hash(&mut hasher);
-                            let hash = hasher.finish();
-                            let r = (hash & 0xFF) as u8;
-                            let g = ((hash >> 8) & 0xFF) as u8;
-                            let b = ((hash >> 16) & 0xFF) as u8;
-                            egui::Color32::from_rgb(r, g, b)
-                        };
-
-                        ui.label(
-                            RichText::new(format!("JOB {}", self.jobs[i].job_name))
-                                .background_color(color)
-                                .color(
-                                    if (0.299 * color.r() as f32
-                                        + 0.587 * color.g() as f32
-                                        + 0.114 * color.b() as f32)
-                                        > 128.0
-                                    {
-                                        egui::Color32::BLACK
-                                    } else {
-                                        egui::Color32::WHITE
-                                    },
+                            let mut selected = self.selected.contains(&i);
+                            if ui.checkbox(&mut selected, "").changed() {
+                                match selected {
+                                    true => self.selected.insert(i),
+                                    false => self.selected.remove(&i),
+                                };
+                            }
+
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new(format!("JOB {}", self.jobs[i].id))
+                                        .background_color(color)
+                                        .color(
+                                            if (0.299 * color.r() as f32
+                                                + 0.587 * color.g() as f32
+                                                + 0.114 * color.b() as f32)
+                                                > 128.0
+                                            {
+                                                egui::Color32::BLACK
+                                            } else {
+                                                egui::Color32::WHITE
+                                            },
+                                        )
+                                        .strong(),
+                                );
+                                let from = self.jobs[i].job_name.clone();
+                                if ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut self.jobs[i].job_name)
+                                            .desired_width(80.0),
+                                    )
+                                    .changed()
+                                {
+                                    self.record(Edit::Rename {
+                                        job: i,
+                                        from,
+                                        to: self.jobs[i].job_name.clone(),
+                                    });
+                                }
+                            });
+                            let from = self.jobs[i].needed_cpu_cycle;
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut self.jobs[i].needed_cpu_cycle)
+                                        .range(1..=u16::MAX),
                                 )
-                                .strong(),
-                        );
-                        ui.add(
-                            egui::DragValue::new(&mut self.jobs[i].needed_cpu_cycle)
-                                .range(1..=u16::MAX),
-                        );
-                        ui.add(
-                            egui::DragValue::new(&mut self.jobs[i].arrival_time)
-                                .range(0..=u16::MAX),
-                        );
-                        // move up [a][b] swap with previous
-                        if ui
-                            .add(egui::Button::new("^").fill(if i == 0 {
-                                egui::Color32::from_rgb(200, 200, 200)
-                            } else {
-                                egui::Color32::from_rgb(100, 149, 237)
-                            }))
-                            .clicked()
-                        {
-                            // Prevent action if job is first
-                            if i != 0 {
-                                let a = self.jobs[i - 1].clone();
-                                let b = self.jobs[i].clone();
+                                .changed()
+                            {
+                                self.record(Edit::CpuCycle {
+                                    job: i,
+                                    from,
+                                    to: self.jobs[i].needed_cpu_cycle,
+                                });
+                            }
+                            let from = self.jobs[i].arrival_time;
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut self.jobs[i].arrival_time)
+                                        .range(0..=u16::MAX),
+                                )
+                                .changed()
+                            {
+                                self.record(Edit::ArrivalTime {
+                                    job: i,
+                                    from,
+                                    to: self.jobs[i].arrival_time,
+                                });
+                            }
+                            self.dependencies_ui(ui, i);
+                            self.critical_sections_ui(ui, i);
+                            self.deadline_ui(ui, i);
+                            let from = self.jobs[i].memory;
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut self.jobs[i].memory)
+                                        .range(0..=u16::MAX),
+                                )
+                                .on_hover_text("Only used with a memory model")
+                                .changed()
+                            {
+                                self.record(Edit::Memory {
+                                    job: i,
+                                    from,
+                                    to: self.jobs[i].memory,
+                                });
+                            }
+                            self.class_ui(ui, i);
+                            // move up [a][b] swap with previous
+                            if ui
+                                .add(egui::Button::new("^").fill(if i == 0 {
+                                    egui::Color32::from_rgb(200, 200, 200)
+                                } else {
+                                    egui::Color32::from_rgb(100, 149, 237)
+                                }))
+                                .clicked()
+                            {
+                                // Prevent action if job is first
+                                if i != 0 {
+                                    let a = self.jobs[i - 1].clone();
+                                    let b = self.jobs[i].clone();
 
-                                self.jobs[i] = a;
-                                self.jobs[i - 1] = b;
+                                    self.jobs[i] = a;
+                                    self.jobs[i - 1] = b;
+                                    self.record(Edit::Swap { a: i - 1, b: i });
+                                }
                             }
-                        }
-                        // move up [b][a] swap with next
-                        if ui
-                            .add(egui::Button::new("v").fill(if i == self.jobs.len() - 1 {
-                                egui::Color32::from_rgb(200, 200, 200)
-                            } else {
-                                egui::Color32::from_rgb(100, 149, 237)
-                            }))
-                            .clicked()
-                        {
-                            // Prevent action if job is last
-                            if i != self.jobs.len() - 1 {
-                                let a = self.jobs[i + 1].clone();
-                                let b = self.jobs[i].clone();
+                            // move up [b][a] swap with next
+                            if ui
+                                .add(egui::Button::new("v").fill(if i == self.jobs.len() - 1 {
+                                    egui::Color32::from_rgb(200, 200, 200)
+                                } else {
+                                    egui::Color32::from_rgb(100, 149, 237)
+                                }))
+                                .clicked()
+                            {
+                                // Prevent action if job is last
+                                if i != self.jobs.len() - 1 {
+                                    let a = self.jobs[i + 1].clone();
+                                    let b = self.jobs[i].clone();
 
-                                self.jobs[i] = a;
-                                self.jobs[i + 1] = b;
+                                    self.jobs[i] = a;
+                                    self.jobs[i + 1] = b;
+                                    self.record(Edit::Swap { a: i, b: i + 1 });
+                                }
                             }
+                            ui.end_row();
                         }
-                        ui.end_row();
-                    }
-                });
+                    });
+                if self.jobs.iter().any(|job| !job.depends_on.is_empty()) {
+                    ui.separator();
+                    ui.vertical(|ui| dependency_graph_ui(ui, &self.jobs));
+                }
+            });
 
             ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
                 github_link(ui);
             });
         });
+
+        // A drag or a typed value is one step in the history once it is let go of
+        if !ctx.input(|input| input.pointer.any_down())
+            && ctx.memory(|memory| memory.focused().is_none())
+        {
+            self.history.seal();
+        }
     }
 }
 
 impl App {
-    fn spawn_new_window(
-        &mut self,
-        ctx: &egui::Context,
-        algorithm: String,
-        jobs: Vec<Job>,
-        time_quantum: u32,
-    ) {
+    /// Picks the jobs job `i` waits for. Jobs that already wait for job `i` cannot be
+    /// picked, which keeps the dependencies free of cycles.
+    fn dependencies_ui(&mut self, ui: &mut egui::Ui, i: usize) {
+        let names: Vec<&str> = self
+            .jobs
+            .iter()
+            .filter(|job| self.jobs[i].depends_on.contains(&job.id))
+            .map(|job| job.job_name.as_str())
+            .collect();
+        let label = match names.is_empty() {
+            true => "-".to_string(),
+            false => names.join(", "),
+        };
+        ui.menu_button(label, |ui| {
+            for other in 0..self.jobs.len() {
+                if other == i {
+                    continue;
+                }
+                let id = self.jobs[other].id;
+                let mut depends = self.jobs[i].depends_on.contains(&id);
+                let allowed = depends || dependencies::can_depend_on(&self.jobs, i, other);
+                let checkbox = egui::Checkbox::new(&mut depends, &self.jobs[other].job_name);
+                let response = ui
+                    .add_enabled(allowed, checkbox)
+                    .on_disabled_hover_text(format!(
+                        "{} already waits for {}",
+                        self.jobs[other].job_name, self.jobs[i].job_name
+                    ));
+                if response.changed() {
+                    let from = self.jobs[i].depends_on.clone();
+                    match depends {
+                        true => self.jobs[i].depends_on.push(id),
+                        false => self.jobs[i].depends_on.retain(|&on| on != id),
+                    }
+                    self.record(Edit::Dependencies {
+                        job: i,
+                        from,
+                        to: self.jobs[i].depends_on.clone(),
+                    });
+                }
+            }
+        });
+    }
+
+    /// Edits the resources job `i` holds and when, in CPU cycles the job has run.
+    fn critical_sections_ui(&mut self, ui: &mut egui::Ui, i: usize) {
+        let sections: Vec<String> = self.jobs[i]
+            .critical_sections
+            .iter()
+            .map(|section| format!("{} {}–{}", section.resource, section.offset, section.end()))
+            .collect();
+        let label = match sections.is_empty() {
+            true => "-".to_string(),
+            false => sections.join(", "),
+        };
+        ui.menu_button(label, |ui| {
+            let from = self.jobs[i].critical_sections.clone();
+            let needed_cpu_cycle = self.jobs[i].needed_cpu_cycle;
+            let mut removed = None;
+            egui::Grid::new(("critical_sections", i)).show(ui, |ui| {
+                ui.label("Resource");
+                ui.label("From Cycle");
+                ui.label("For");
+                ui.end_row();
+                for (index, section) in self.jobs[i].critical_sections.iter_mut().enumerate() {
+                    ui.add(egui::TextEdit::singleline(&mut section.resource).desired_width(60.0));
+                    ui.add(egui::DragValue::new(&mut section.offset).range(0..=needed_cpu_cycle));
+                    ui.add(egui::DragValue::new(&mut section.duration).range(1..=needed_cpu_cycle));
+                    if ui.button("x").clicked() {
+                        removed = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(index) = removed {
+                self.jobs[i].critical_sections.remove(index);
+            }
+            if ui.button("Add").clicked() {
+                let offset = self.jobs[i]
+                    .critical_sections
+                    .iter()
+                    .map(CriticalSection::end)
+                    .max()
+                    .unwrap_or(0);
+                self.jobs[i].critical_sections.push(CriticalSection {
+                    resource: "R".to_string(),
+                    offset: offset.min(needed_cpu_cycle.saturating_sub(1)),
+                    duration: 1,
+                });
+            }
+            if self.jobs[i].critical_sections != from {
+                self.record(Edit::CriticalSections {
+                    job: i,
+                    from,
+                    to: self.jobs[i].critical_sections.clone(),
+                });
+            }
+        });
+    }
+
+    fn deadline_ui(&mut self, ui: &mut egui::Ui, i: usize) {
+        let from = self.jobs[i].deadline;
+        ui.horizontal(|ui| {
+            let mut due = from.is_some();
+            ui.checkbox(&mut due, "")
+                .on_hover_text("Only used to pick the lowest feasible CPU frequency");
+            let job = &mut self.jobs[i];
+            let due_by = job.arrival_time + 2 * job.needed_cpu_cycle;
+            job.deadline = due.then(|| job.deadline.unwrap_or(due_by));
+            let deadline = &mut job.deadline;
+            if let Some(deadline) = deadline {
+                ui.add(egui::DragValue::new(deadline).range(0..=u16::MAX));
+            }
+        });
+        if self.jobs[i].deadline != from {
+            self.record(Edit::Deadline {
+                job: i,
+                from,
+                to: self.jobs[i].deadline,
+            });
+        }
+    }
+
+    fn class_ui(&mut self, ui: &mut egui::Ui, i: usize) {
+        let from = self.jobs[i].class;
+        egui::ComboBox::from_id_salt(("class", i))
+            .selected_text(from.name())
+            .show_ui(ui, |ui| {
+                for class in JobClass::ALL {
+                    ui.selectable_value(&mut self.jobs[i].class, class, class.name());
+                }
+            })
+            .response
+            .on_hover_text("Only used with multilevel queues");
+        if self.jobs[i].class != from {
+            self.record(Edit::Class {
+                job: i,
+                from,
+                to: self.jobs[i].class,
+            });
+        }
+    }
+
+    /// Turns multilevel queues on or off and edits each class's algorithm and how the
+    /// queues share the CPU.
+    fn multilevel_ui(&mut self, ui: &mut egui::Ui) {
+        let from = self.multilevel.clone();
+        ui.horizontal(|ui| {
+            let mut enabled = self.multilevel.is_some();
+            ui.checkbox(&mut enabled, "Multilevel Queues");
+            if enabled != self.multilevel.is_some() {
+                self.multilevel = enabled.then(MultilevelQueues::default);
+            }
+            if let Some(multilevel) = &mut self.multilevel {
+                ui.label("Between Queues:");
+                egui::ComboBox::from_id_salt("queue_sharing")
+                    .selected_text(multilevel.sharing.name())
+                    .show_ui(ui, |ui| {
+                        for sharing in QueueSharing::ALL {
+                            ui.selectable_value(&mut multilevel.sharing, sharing, sharing.name());
+                        }
+                    });
+            }
+        });
+        if let Some(multilevel) = &mut self.multilevel {
+            let slicing = multilevel.sharing == QueueSharing::TimeSlicing;
+            egui::Grid::new("class_queues").show(ui, |ui| {
+                for class in JobClass::ALL {
+                    let queue = multilevel.queue_mut(class);
+                    ui.label(class.name());
+                    egui::ComboBox::from_id_salt(("class_algorithm", class))
+                        .selected_text(queue.algorithm.name())
+                        .show_ui(ui, |ui| {
+                            for algorithm in Algorithm::ALL {
+                                ui.selectable_value(
+                                    &mut queue.algorithm,
+                                    algorithm,
+                                    algorithm.name(),
+                                );
+                            }
+                        });
+                    ui.horizontal(|ui| {
+                        if queue.algorithm == Algorithm::RoundRobin {
+                            ui.label("Quantum:");
+                            ui.add(
+                                egui::DragValue::new(&mut queue.time_quantum).range(1..=u16::MAX),
+                            );
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        if slicing {
+                            ui.label("Slice:");
+                            ui.add(egui::DragValue::new(&mut queue.slice).range(0..=u16::MAX));
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+            if let Err(error) = multilevel.check() {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        }
+        if self.multilevel != from {
+            self.record(Edit::Multilevel {
+                from,
+                to: self.multilevel.clone(),
+            });
+        }
+    }
+
+    /// Turns the memory model on or off and edits its size, admission policy and swapping.
+    fn memory_ui(&mut self, ui: &mut egui::Ui) {
+        let from = self.memory.clone();
+        ui.horizontal(|ui| {
+            let mut enabled = self.memory.is_some();
+            ui.checkbox(&mut enabled, "Memory");
+            if enabled != self.memory.is_some() {
+                self.memory = enabled.then(MemoryModel::default);
+            }
+            if let Some(memory) = &mut self.memory {
+                ui.label("Size:");
+                ui.add(egui::DragValue::new(&mut memory.size).range(1..=u16::MAX));
+                ui.label("Admission:");
+                egui::ComboBox::from_id_salt("admission")
+                    .selected_text(memory.admission.name())
+                    .show_ui(ui, |ui| {
+                        for admission in Admission::ALL {
+                            ui.selectable_value(&mut memory.admission, admission, admission.name());
+                        }
+                    });
+                let mut swapping = memory.swap_cost.is_some();
+                ui.checkbox(&mut swapping, "Swapping");
+                memory.swap_cost = swapping.then(|| memory.swap_cost.unwrap_or(1));
+                if let Some(cost) = &mut memory.swap_cost {
+                    ui.label("Cost:");
+                    ui.add(egui::DragValue::new(cost).range(0..=u16::MAX));
+                }
+            }
+        });
+        if let Some(memory) = &self.memory {
+            if let Err(error) = memory::check_memory(&self.jobs, memory) {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        }
+        if self.memory != from {
+            self.record(Edit::MemoryModel {
+                from,
+                to: self.memory.clone(),
+            });
+        }
+    }
+
+    /// Turns the energy model on or off and edits its frequency levels and power draws.
+    fn power_ui(&mut self, ui: &mut egui::Ui) {
+        let from = self.power.clone();
+        ui.horizontal(|ui| {
+            let mut enabled = self.power.is_some();
+            ui.checkbox(&mut enabled, "Energy");
+            if enabled != self.power.is_some() {
+                self.power = enabled.then(PowerModel::default);
+            }
+            if let Some(power) = &mut self.power {
+                ui.label("Policy:");
+                egui::ComboBox::from_id_salt("dvfs_policy")
+                    .selected_text(power.policy.name())
+                    .show_ui(ui, |ui| {
+                        for policy in DvfsPolicy::ALL {
+                            ui.selectable_value(&mut power.policy, policy, policy.name());
+                        }
+                    });
+            }
+        });
+        if let Some(power) = &mut self.power {
+            ui.horizontal(|ui| {
+                ui.label("Idle W:");
+                ui.add(
+                    egui::DragValue::new(&mut power.idle_power)
+                        .range(0.0..=1000.0)
+                        .speed(0.01),
+                );
+                ui.label("Sleep W:");
+                ui.add(
+                    egui::DragValue::new(&mut power.sleep_power)
+                        .range(0.0..=1000.0)
+                        .speed(0.01),
+                );
+                ui.label("Sleep After:");
+                ui.add(egui::DragValue::new(&mut power.sleep_after).range(1..=u16::MAX));
+                ui.label("Wake Latency:");
+                ui.add(egui::DragValue::new(&mut power.wake_latency).range(0..=u16::MAX));
+            });
+            let mut removed = None;
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Levels (MHz, W):");
+                for (index, level) in power.levels.iter_mut().enumerate() {
+                    ui.add(egui::DragValue::new(&mut level.mhz).range(1..=100_000));
+                    ui.add(
+                        egui::DragValue::new(&mut level.power)
+                            .range(0.0..=1000.0)
+                            .speed(0.01),
+                    );
+                    if ui.small_button("x").clicked() {
+                        removed = Some(index);
+                    }
+                    ui.separator();
+                }
+                if ui.button("Add").clicked() {
+                    let fastest = power.fastest();
+                    power.levels.push(FrequencyLevel {
+                        mhz: fastest.mhz + 600,
+                        power: fastest.power * 2.0,
+                    });
+                }
+            });
+            if let Some(index) = removed {
+                power.levels.remove(index);
+            }
+            if let Err(error) = power.check() {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        }
+        if self.power != from {
+            self.record(Edit::Power {
+                from,
+                to: self.power.clone(),
+            });
+        }
+    }
+
+    fn spawn_new_window(&mut self, ctx: &egui::Context, config: SchedulerConfig, jobs: Vec<Job>) {
         // return value adjusts "viewport_open"
         let ctx_clone = ctx.clone();
         ctx.show_viewport_immediate(
@@ -305,12 +1783,7 @@ impl App {
                 );
                 // Define the UI for the new viewport here
                 egui::CentralPanel::default().show(&ctx_clone, |ui| {
-                    timeline_builder_screen(
-                        ui,
-                        algorithm.clone(),
-                        jobs.clone(),
-                        time_quantum.clone(),
-                    );
+                    timeline_builder_screen(ui, &config, &jobs, &mut self.results);
                 });
 
                 // I want to die. multiple days of trying to understand egui docmentation
@@ -327,110 +1800,208 @@ impl App {
 // FIXME: Updates only on mouse hover on second window
 fn timeline_builder_screen(
     ui: &mut egui::Ui,
-    algorithm: String,
-    jobs: Vec<Job>,
-    time_quantum: u32,
+    config: &SchedulerConfig,
+    jobs: &[Job],
+    results: &mut ResultsView,
 ) {
     // TODO: ALLOW TO ONLY RUN ONCE
-    let (mut returned_jobs, timeline) =
-        process_scheduler(algorithm.clone(), jobs.clone(), time_quantum.clone());
+    let schedule = simulate(jobs, config);
+    let starved = results.starvation.starved_jobs(&schedule);
+    let mut returned_jobs = schedule.jobs.clone();
+    let timeline: Vec<(String, u32, u32)> = schedule
+        .segments
+        .iter()
+        .map(|segment| (segment.job_name.clone(), segment.start, segment.end))
+        .collect();
 
-    let mut job_segments = Vec::new();
+    // on several cores the last segment need not be the one ending last
+    let total_time = schedule
+        .segments
+        .iter()
+        .map(|segment| segment.end as f32)
+        .reduce(f32::max)
+        .unwrap_or(1.0); // Default value when timeline is empty
 
-    ui.horizontal(|ui| {
-        for i in 0..timeline.len() {
-            let (job_name, start_time, end_time) = &timeline[i];
-            job_segments.push((job_name.clone(), *start_time as f32, *end_time as f32));
+    // one lane per core
+    for core in 0..config.cores.max(1) {
+        if config.cores > 1 {
+            ui.label(format!("Core {core}"));
         }
+        let mut job_segments = Vec::new();
 
-        // println!("{:?}", job_segments);
-        let painter = ui.painter();
-        let total_time = if let Some(last) = timeline.last() {
-            last.2 as f32
-        } else {
-            1.0 // Default value when timeline is empty
-        };
-        let width = ui.available_width();
-        let height = 50.0;
-        let rect = egui::Rect::from_min_size(ui.cursor().min, egui::vec2(width, height));
-        painter.rect_filled(rect, 0.0, egui::Color32::LIGHT_GRAY);
-
-        for i in 0..job_segments.len() {
-            // let mut previous_start_time: f32 = 0.0;
-            let mut previous_end_time: f32 = 0.0;
-            let next_start_time: f32;
-            // let mut next_end_time: f32 = 0.0;
-
-            let (job_name, start_time, end_time) = &job_segments[i];
-            if i != 0 {
-                (_, _, previous_end_time) = job_segments[i - 1];
-            }
-            if i + 1 != job_segments.len() {
-                (_, next_start_time, _) = job_segments[i + 1];
-            } else {
-                next_start_time = -1.0;
-            }
-
-            let color = {
-                let mut hasher = std::collections::hash_map::DefaultHasher::new();
-                job_name.hash(&mut hasher);
-                let hash = hasher.finish();
-                let r = (hash & 0xFF) as u8;
-                let g = ((hash >> 8) & 0xFF) as u8;
-                let b = ((hash >> 16) & 0xFF) as u8;
-                egui::Color32::from_rgb(r, g, b)
-            };
-            let x_start = rect.left() + (start_time / total_time) * rect.width();
-            let x_end = rect.left() + (end_time / total_time) * rect.width();
-            let job_rect = egui::Rect::from_min_max(
-                egui::pos2(x_start, rect.top()),
-                egui::pos2(x_end, rect.bottom()),
-            );
-            painter.rect_filled(job_rect, 0.0, color);
-            painter.text(
-                job_rect.center(),
-                egui::Align2::CENTER_CENTER,
-                job_name,
-                egui::FontId::default(),
-                egui::Color32::BLACK,
-            );
+        ui.horizontal(|ui| {
+            for segment in schedule
+                .segments
+                .iter()
+                .filter(|segment| segment.core == core)
+            {
+                job_segments.push((
+                    segment.job_id,
+                    segment.job_name.clone(),
+                    segment.start as f32,
+                    segment.end as f32,
+                ));
+            }
+
+            // println!("{:?}", job_segments);
+            let painter = ui.painter();
+            let width = ui.available_width();
+            let height = 50.0;
+            let rect = egui::Rect::from_min_size(ui.cursor().min, egui::vec2(width, height));
+            painter.rect_filled(rect, 0.0, egui::Color32::LIGHT_GRAY);
+
+            for i in 0..job_segments.len() {
+                // let mut previous_start_time: f32 = 0.0;
+                let mut previous_end_time: f32 = 0.0;
+                let next_start_time: f32;
+                // let mut next_end_time: f32 = 0.0;
+
+                let (job_id, job_name, start_time, end_time) = &job_segments[i];
+                if i != 0 {
+                    (_, _, _, previous_end_time) = job_segments[i - 1];
+                }
+                if i + 1 != job_segments.len() {
+                    (_, _, next_start_time, _) = job_segments[i + 1];
+                } else {
+                    next_start_time = -1.0;
+                }
 
-            // Paint Start
-            if previous_end_time <= *start_time {
+                let color = job_color(*job_id);
+                let x_start = rect.left() + (start_time / total_time) * rect.width();
+                let x_end = rect.left() + (end_time / total_time) * rect.width();
+                let job_rect = egui::Rect::from_min_max(
+                    egui::pos2(x_start, rect.top()),
+                    egui::pos2(x_end, rect.bottom()),
+                );
+                painter.rect_filled(job_rect, 0.0, color);
+                // shade the part of the run spent holding a resource
+                for hold in schedule.holds.iter().filter(|hold| hold.job_id == *job_id) {
+                    let start = start_time.max(hold.start as f32);
+                    let end = end_time.min(hold.end as f32);
+                    if start < end {
+                        let held = egui::Rect::from_min_max(
+                            egui::pos2(
+                                rect.left() + (start / total_time) * rect.width(),
+                                rect.top() + height * 0.65,
+                            ),
+                            egui::pos2(
+                                rect.left() + (end / total_time) * rect.width(),
+                                rect.bottom(),
+                            ),
+                        );
+                        painter.rect_filled(held, 0.0, egui::Color32::from_black_alpha(90));
+                        painter.text(
+                            held.center(),
+                            egui::Align2::CENTER_CENTER,
+                            &hold.resource,
+                            egui::FontId::proportional(10.0),
+                            egui::Color32::WHITE,
+                        );
+                    }
+                }
+                if starved.contains(job_id) {
+                    painter.rect_stroke(job_rect.shrink(1.0), 0.0, (2.0, egui::Color32::RED));
+                }
                 painter.text(
-                    job_rect.left_bottom() + egui::vec2(0.0, 20.0),
-                    egui::Align2::LEFT_BOTTOM,
-                    start_time,
+                    job_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    job_name,
                     egui::FontId::default(),
                     egui::Color32::BLACK,
                 );
-            }
 
-            if *end_time < next_start_time || next_start_time == -1.0 {
-                // Paint End
-                painter.text(
-                    job_rect.right_bottom() + egui::vec2(0.0, 20.0),
-                    egui::Align2::RIGHT_BOTTOM,
-                    end_time,
-                    egui::FontId::default(),
-                    egui::Color32::BLACK,
+                // Paint Start
+                if previous_end_time <= *start_time {
+                    painter.text(
+                        job_rect.left_bottom() + egui::vec2(0.0, 20.0),
+                        egui::Align2::LEFT_BOTTOM,
+                        start_time,
+                        egui::FontId::default(),
+                        egui::Color32::BLACK,
+                    );
+                }
+
+                if *end_time < next_start_time || next_start_time == -1.0 {
+                    // Paint End
+                    painter.text(
+                        job_rect.right_bottom() + egui::vec2(0.0, 20.0),
+                        egui::Align2::RIGHT_BOTTOM,
+                        end_time,
+                        egui::FontId::default(),
+                        egui::Color32::BLACK,
+                    );
+                }
+
+                // Draw a line to separate the jobs
+                painter.line_segment(
+                    [
+                        egui::pos2(x_end, rect.top()),
+                        egui::pos2(x_end, rect.bottom()),
+                    ],
+                    (0.5, egui::Color32::BLACK),
                 );
             }
+        });
+        ui.add_space(80.0);
+    }
+    // ui.label(format!("",));
+    match &config.multilevel {
+        Some(multilevel) => {
+            class_lanes_ui(ui, &schedule, config.cores);
+            ui.label(multilevel.describe());
+        }
+        None => {
+            ui.label(config.algorithm.name());
+        }
+    }
+    ui.label(config.tie_breaking.describe(config.algorithm));
+    if let Some(aging) = config.aging.filter(|_| {
+        matches!(
+            config.algorithm,
+            Algorithm::Fcfs | Algorithm::Sjn | Algorithm::Srt
+        )
+    }) {
+        ui.label(aging.describe());
+    }
+    let locking = jobs.iter().any(|job| !job.critical_sections.is_empty());
+    if locking {
+        ui.label(config.locking.name())
+            .on_hover_text("Darker parts of the chart are spent holding the resource named there.");
+    }
+    ui.label(format!("{:?}", timeline));
 
-            // Draw a line to separate the jobs
-            painter.line_segment(
-                [
-                    egui::pos2(x_end, rect.top()),
-                    egui::pos2(x_end, rect.bottom()),
-                ],
-                (0.5, egui::Color32::BLACK),
+    ui.horizontal(|ui| {
+        let starvation = &mut results.starvation;
+        ui.label("Starved when waiting more than");
+        let mut limited = starvation.max_wait.is_some();
+        ui.checkbox(&mut limited, "");
+        starvation.max_wait = limited.then_some(starvation.max_wait.unwrap_or(20));
+        if let Some(max_wait) = &mut starvation.max_wait {
+            ui.add(egui::DragValue::new(max_wait).range(0..=u16::MAX));
+        }
+        ui.label("ticks, or");
+        let mut limited = starvation.burst_multiple.is_some();
+        ui.checkbox(&mut limited, "");
+        starvation.burst_multiple = limited.then_some(starvation.burst_multiple.unwrap_or(5.0));
+        if let Some(multiple) = &mut starvation.burst_multiple {
+            ui.add(
+                egui::DragValue::new(multiple)
+                    .range(0.0..=1000.0)
+                    .speed(0.1),
             );
         }
+        ui.label("× its CPU cycles");
     });
-    ui.add_space(80.0);
-    // ui.label(format!("",));
-    ui.label(format!("{}", algorithm));
-    ui.label(format!("{:?}", timeline));
+    if !starved.is_empty() {
+        ui.colored_label(
+            egui::Color32::RED,
+            format!("{} job(s) starved, outlined in red", starved.len()),
+        );
+    }
+    let waiting_times: Vec<(u32, u32, u32)> = job_metrics(&schedule)
+        .iter()
+        .map(|job| (job.job_id, job.waiting_time, job.blocked_time))
+        .collect();
 
     let mut total_turnaround_time: f64 = 0.0;
 
@@ -446,20 +2017,52 @@ fn timeline_builder_screen(
             ui.label(format!("{:.2}", average_turnaround_time));
             ui.end_row();
 
+            let makespan = returned_jobs.iter().map(|job| job.completion_time).max();
+            ui.label("Makespan: ");
+            ui.label(makespan.unwrap_or(0).to_string());
+            ui.end_row();
+            if let Some(report) = config
+                .power
+                .as_ref()
+                .and_then(|power| energy::energy_report(&schedule, power))
+            {
+                ui.label("Frequency: ");
+                ui.label(format!("{} MHz", report.frequency.mhz));
+                ui.end_row();
+                ui.label("Energy: ");
+                ui.label(format!("{:.2} mJ", report.energy))
+                    .on_hover_text("Taking a tick to be a millisecond");
+                ui.end_row();
+                ui.label("Average Power: ");
+                ui.label(format!("{:.3} W", report.average_power));
+                ui.end_row();
+                let missed = energy::missed_deadlines(&schedule);
+                if jobs.iter().any(|job| job.deadline.is_some()) {
+                    ui.label("Missed Deadlines: ");
+                    ui.label(missed.len().to_string());
+                    ui.end_row();
+                }
+            }
+            if jobs.iter().any(|job| !job.depends_on.is_empty()) {
+                if let Ok(path) = dependencies::critical_path(jobs) {
+                    ui.label("Critical Path: ");
+                    ui.label(path.describe(jobs)).on_hover_text(
+                        "The longest chain of dependent jobs. No schedule can finish sooner.",
+                    );
+                    ui.end_row();
+                }
+            }
+
             ui.label("Job Name");
             ui.label("Completion Time");
             ui.label("Turn Around");
+            ui.label("Waiting");
+            if locking {
+                ui.label("Blocked");
+            }
             ui.end_row();
             for job in &mut returned_jobs {
-                let color = {
-                    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-                    job.job_name.hash(&mut hasher);
-                    let hash = hasher.finish();
-                    let r = (hash & 0xFF) as u8;
-                    let g = ((hash >> 8) & 0xFF) as u8;
-                    let b = ((hash >> 16) & 0xFF) as u8;
-                    egui::Color32::from_rgb(r, g, b)
-                };
+                let color = job_color(job.id);
                 // ui.label(format!("Job {}", job.job_name));
                 ui.label(
                     RichText::new(format!("JOB {}", job.job_name))
@@ -479,17 +2082,731 @@ fn timeline_builder_screen(
                 );
                 ui.label(format!("{}", job.completion_time));
                 ui.label(format!("{}", job.turnaround_time));
+                if let Some((_, waiting_time, blocked_time)) =
+                    waiting_times.iter().find(|(id, _, _)| *id == job.id)
+                {
+                    let waiting_time = RichText::new(waiting_time.to_string());
+                    if starved.contains(&job.id) {
+                        ui.label(waiting_time.color(egui::Color32::RED).strong())
+                            .on_hover_text("Starved");
+                    } else {
+                        ui.label(waiting_time);
+                    }
+                    if locking {
+                        ui.label(blocked_time.to_string())
+                            .on_hover_text("Ticks spent waiting for a resource another job held");
+                    }
+                }
                 total_turnaround_time += job.turnaround_time as f64;
                 ui.end_row();
             }
         });
 
+    ui.collapsing("Distance from Optimal", |ui| {
+        let distance = results.optimal(jobs, config);
+        ui.label(format!(
+            "Best average turnaround: {} preemptive, {} non-preemptive",
+            distance.preemptive.describe(),
+            distance.non_preemptive.describe()
+        ))
+        .on_hover_text(
+            "Context switches are taken to be free and dependencies and critical sections are left out. \"At least\" marks a lower bound \
+            where the exact optimum is too expensive to find.",
+        );
+        egui::Grid::new("optimal_gaps")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Algorithm");
+                ui.label("Average Turnaround");
+                ui.label("Gap");
+                ui.end_row();
+                // the run on screen in bold
+                let mut row = |name: String, average: f64, gap: f64, shown: bool| {
+                    let text = |text: String| match shown {
+                        true => RichText::new(text).strong(),
+                        false => RichText::new(text),
+                    };
+                    ui.label(text(name));
+                    ui.label(text(format!("{average:.2}")));
+                    ui.label(text(format!("{:+.1}%", gap * 100.0)));
+                    ui.end_row();
+                };
+                for &(algorithm, average, gap) in &distance.gaps {
+                    let shown = config.multilevel.is_none() && algorithm == config.algorithm;
+                    row(algorithm.name().to_string(), average, gap, shown);
+                }
+                if let Some((average, gap)) = distance.multilevel {
+                    row("Multilevel queues".to_string(), average, gap, true);
+                }
+            });
+    });
+
+    ui.collapsing("Process States", |ui| lifecycle_ui(ui, &schedule, results));
+
+    if let Some(memory) = &config.memory {
+        ui.collapsing("Memory", |ui| {
+            memory_ui(ui, &returned_jobs, memory, &schedule)
+        });
+    }
+
+    if let Some(power) = &config.power {
+        ui.collapsing("Energy", |ui| energy_ui(ui, jobs, config, power, &schedule));
+    }
+
+    ui.collapsing("Charts", |ui| {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Compare with:");
+            for algorithm in Algorithm::ALL {
+                // with multilevel queues the config's algorithm is not the run on screen
+                if config.multilevel.is_none() && algorithm == config.algorithm {
+                    continue;
+                }
+                let mut shown = results.chart_algorithms.contains(&algorithm);
+                if ui.checkbox(&mut shown, algorithm.name()).changed() {
+                    if shown {
+                        results.chart_algorithms.push(algorithm);
+                    } else {
+                        results
+                            .chart_algorithms
+                            .retain(|&chosen| chosen != algorithm);
+                    }
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Utilisation Window:");
+            ui.add(egui::DragValue::new(&mut results.utilisation_window).range(1..=u16::MAX));
+        });
+
+        let mut runs = vec![match &config.multilevel {
+            Some(multilevel) => (multilevel.describe(), MULTILEVEL_COLOR, schedule.clone()),
+            None => (
+                config.algorithm.name().to_string(),
+                run_color(config.algorithm),
+                schedule.clone(),
+            ),
+        }];
+        for algorithm in Algorithm::ALL {
+            let shown = config.multilevel.is_none() && algorithm == config.algorithm;
+            if !shown && results.chart_algorithms.contains(&algorithm) {
+                let config = SchedulerConfig {
+                    algorithm,
+                    multilevel: None,
+                    ..config.clone()
+                };
+                runs.push((
+                    algorithm.name().to_string(),
+                    run_color(algorithm),
+                    simulate(jobs, &config),
+                ));
+            }
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            charts_ui(ui, jobs, &runs, config.cores, results.utilisation_window);
+        });
+    });
+
     ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
         powered_by_egui_and_eframe(ui);
         egui::warn_if_debug_build(ui);
     });
 }
 
+/// The Gantt chart again with a lane per job class, and per core when there are several,
+/// and the CPU time each class got.
+fn class_lanes_ui(ui: &mut egui::Ui, schedule: &Schedule, cores: u32) {
+    const LANE: f32 = 20.0;
+    let makespan = schedule
+        .jobs
+        .iter()
+        .map(|job| job.completion_time)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let class_of = |job_id: u32| {
+        schedule
+            .jobs
+            .iter()
+            .find(|job| job.id == job_id)
+            .map_or(JobClass::default(), |job| job.class)
+    };
+    let width = ui.available_width().min(800.0);
+    for class in multilevel::classes_used(&schedule.jobs) {
+        for core in 0..cores.max(1) {
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(width, LANE), egui::Sense::hover());
+            let painter = ui.painter_at(rect);
+            let plot = egui::Rect::from_min_max(rect.min + egui::vec2(110.0, 0.0), rect.max);
+            let x = |time: u32| plot.left() + time as f32 / makespan as f32 * plot.width();
+            painter.rect_stroke(plot, 0.0, (0.5, egui::Color32::GRAY));
+            for segment in schedule
+                .segments
+                .iter()
+                .filter(|segment| segment.core == core && class_of(segment.job_id) == class)
+            {
+                let span = egui::Rect::from_min_max(
+                    egui::pos2(x(segment.start), plot.top()),
+                    egui::pos2(x(segment.end), plot.bottom()),
+                );
+                painter.rect_filled(span, 0.0, job_color(segment.job_id));
+                painter.text(
+                    span.center(),
+                    egui::Align2::CENTER_CENTER,
+                    &segment.job_name,
+                    egui::FontId::proportional(11.0),
+                    egui::Color32::BLACK,
+                );
+            }
+            let label = match cores > 1 {
+                true => format!("{} core {core}", class.name()),
+                false => class.name().to_string(),
+            };
+            painter.text(
+                rect.left_center(),
+                egui::Align2::LEFT_CENTER,
+                label,
+                egui::FontId::proportional(12.0),
+                ui.visuals().text_color(),
+            );
+        }
+    }
+    let ticks = multilevel::class_ticks(schedule);
+    let total: u32 = ticks.iter().map(|(_, ticks)| ticks).sum();
+    let shares: Vec<String> = ticks
+        .iter()
+        .map(|(class, ticks)| {
+            format!(
+                "{} {ticks} ({:.0}%)",
+                class.name(),
+                *ticks as f64 / total.max(1) as f64 * 100.0
+            )
+        })
+        .collect();
+    ui.label(format!("CPU time: {}", shares.join(", ")));
+}
+
+/// Memory in use over the run, and how long each job waited to be admitted.
+fn memory_ui(ui: &mut egui::Ui, jobs: &[Job], memory: &MemoryModel, schedule: &Schedule) {
+    let used = memory::memory_used(schedule);
+    line_chart(
+        ui,
+        "Memory Used",
+        &[
+            (
+                "Used".to_string(),
+                egui::Color32::from_rgb(100, 149, 237),
+                used.iter().copied().map(f64::from).collect(),
+            ),
+            (
+                "Size".to_string(),
+                egui::Color32::GRAY,
+                vec![f64::from(memory.size); used.len()],
+            ),
+        ],
+        Some(f64::from(memory.size)),
+    );
+    if memory.swap_cost.is_some() {
+        ui.label(format!("Swaps: {}", schedule.swaps));
+    }
+    egui::Grid::new("admission_delays")
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Job Name");
+            ui.label("Memory");
+            ui.label("Admission Delay");
+            ui.end_row();
+            for job in jobs {
+                ui.label(&job.job_name);
+                ui.label(job.memory.to_string());
+                ui.label(memory::admission_delay(schedule, job).to_string())
+                    .on_hover_text("Ticks from arrival until the job was let into memory");
+                ui.end_row();
+            }
+        });
+}
+
+fn core_state_color(state: CoreState) -> egui::Color32 {
+    match state {
+        CoreState::Switching | CoreState::Waking => egui::Color32::from_rgb(230, 150, 60),
+        CoreState::Idle => egui::Color32::from_gray(200),
+        CoreState::Asleep => egui::Color32::from_rgb(70, 90, 160),
+    }
+}
+
+/// What each core did over the run and the energy the run would take at every level.
+fn energy_ui(
+    ui: &mut egui::Ui,
+    jobs: &[Job],
+    config: &SchedulerConfig,
+    power: &PowerModel,
+    schedule: &Schedule,
+) {
+    let makespan = schedule
+        .jobs
+        .iter()
+        .map(|job| job.completion_time)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    const LANE: f32 = 18.0;
+    let width = ui.available_width().min(800.0);
+    for core in 0..config.cores.max(1) {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(width, LANE), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let x = |time: u32| rect.left() + time as f32 / makespan as f32 * rect.width();
+        let span_rect = |start: u32, end: u32| {
+            egui::Rect::from_min_max(
+                egui::pos2(x(start), rect.top()),
+                egui::pos2(x(end), rect.bottom()),
+            )
+        };
+        painter.rect_stroke(rect, 0.0, (0.5, egui::Color32::GRAY));
+        for segment in schedule
+            .segments
+            .iter()
+            .filter(|segment| segment.core == core)
+        {
+            painter.rect_filled(
+                span_rect(segment.start, segment.end),
+                0.0,
+                job_color(segment.job_id),
+            );
+        }
+        for span in schedule.core_states.iter().filter(|span| span.core == core) {
+            painter.rect_filled(
+                span_rect(span.start, span.end),
+                0.0,
+                core_state_color(span.state),
+            );
+        }
+        painter.text(
+            rect.left_center() + egui::vec2(4.0, 0.0),
+            egui::Align2::LEFT_CENTER,
+            format!("core {core}"),
+            egui::FontId::proportional(11.0),
+            egui::Color32::BLACK,
+        );
+    }
+    ui.horizontal(|ui| {
+        for state in [CoreState::Switching, CoreState::Idle, CoreState::Asleep] {
+            ui.label(RichText::new("■").color(core_state_color(state)));
+            ui.label(match state {
+                CoreState::Switching => "Switching or waking",
+                _ => state.name(),
+            });
+        }
+    });
+
+    egui::Grid::new("energy_levels")
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Frequency");
+            ui.label("Energy");
+            ui.label("Average Power");
+            ui.label("Makespan");
+            ui.label("Missed Deadlines");
+            ui.end_row();
+            for level in power.sorted_levels() {
+                let run = process_scheduler::simulate_at_frequency(jobs, config, level);
+                let Some(report) = energy::energy_report(&run, power) else {
+                    continue;
+                };
+                let text = |text: String| match Some(level) == schedule.frequency {
+                    true => RichText::new(text).strong(),
+                    false => RichText::new(text),
+                };
+                let makespan = run.jobs.iter().map(|job| job.completion_time).max();
+                ui.label(text(format!("{} MHz", level.mhz)));
+                ui.label(text(format!("{:.2} mJ", report.energy)));
+                ui.label(text(format!("{:.3} W", report.average_power)));
+                ui.label(text(makespan.unwrap_or(0).to_string()));
+                ui.label(text(energy::missed_deadlines(&run).len().to_string()));
+                ui.end_row();
+            }
+        });
+}
+
+/// Arrival process and burst distribution used by "Randomize Details".
+fn generator_ui(ui: &mut egui::Ui, generator: &mut WorkloadGenerator) {
+    let arrival_processes = [
+        ("Uniform", ArrivalProcess::Uniform { max_arrival: 9 }),
+        ("Poisson", ArrivalProcess::Poisson { rate: 0.5 }),
+        (
+            "Bursty",
+            ArrivalProcess::Bursty {
+                group_size: 3,
+                mean_gap: 10.0,
+            },
+        ),
+    ];
+    let burst_distributions = [
+        ("Uniform", BurstDistribution::Uniform { min: 1, max: 9 }),
+        ("Exponential", BurstDistribution::Exponential { mean: 5.0 }),
+        (
+            "Bimodal",
+            BurstDistribution::Bimodal {
+                short: 2,
+                long: 20,
+                long_fraction: 0.2,
+            },
+        ),
+        (
+            "Pareto",
+            BurstDistribution::Pareto {
+                scale: 1.0,
+                shape: 1.5,
+            },
+        ),
+        (
+            "Log-normal",
+            BurstDistribution::LogNormal {
+                mu: 1.5,
+                sigma: 0.75,
+            },
+        ),
+    ];
+
+    ui.horizontal(|ui| {
+        ui.label("Arrivals:");
+        let selected = arrival_processes
+            .iter()
+            .find(|(_, process)| {
+                std::mem::discriminant(process) == std::mem::discriminant(&generator.arrivals)
+            })
+            .map_or("", |(name, _)| *name);
+        egui::ComboBox::from_id_salt("arrival_process")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                for (name, process) in &arrival_processes {
+                    if ui.selectable_label(*name == selected, *name).clicked() && *name != selected
+                    {
+                        generator.arrivals = process.clone();
+                    }
+                }
+            });
+        match &mut generator.arrivals {
+            ArrivalProcess::Uniform { max_arrival } => {
+                ui.label("Latest Arrival:");
+                ui.add(egui::DragValue::new(max_arrival).range(0..=u16::MAX));
+            }
+            ArrivalProcess::Poisson { rate } => {
+                ui.label("Jobs per Tick:");
+                ui.add(egui::DragValue::new(rate).range(0.01..=100.0).speed(0.01));
+            }
+            ArrivalProcess::Bursty {
+                group_size,
+                mean_gap,
+            } => {
+                ui.label("Jobs per Burst:");
+                ui.add(egui::DragValue::new(group_size).range(1..=u16::MAX));
+                ui.label("Mean Gap:");
+                ui.add(
+                    egui::DragValue::new(mean_gap)
+                        .range(0.0..=10000.0)
+                        .speed(0.1),
+                );
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("CPU Cycles:");
+        let selected = burst_distributions
+            .iter()
+            .find(|(_, distribution)| {
+                std::mem::discriminant(distribution) == std::mem::discriminant(&generator.bursts)
+            })
+            .map_or("", |(name, _)| *name);
+        egui::ComboBox::from_id_salt("burst_distribution")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                for (name, distribution) in &burst_distributions {
+                    if ui.selectable_label(*name == selected, *name).clicked() && *name != selected
+                    {
+                        generator.bursts = distribution.clone();
+                    }
+                }
+            });
+        match &mut generator.bursts {
+            BurstDistribution::Uniform { min, max } => {
+                ui.label("Min:");
+                ui.add(egui::DragValue::new(min).range(1..=u16::MAX));
+                ui.label("Max:");
+                ui.add(egui::DragValue::new(max).range(*min..=u16::MAX as u32));
+            }
+            BurstDistribution::Exponential { mean } => {
+                ui.label("Mean:");
+                ui.add(egui::DragValue::new(mean).range(1.0..=10000.0).speed(0.1));
+            }
+            BurstDistribution::Bimodal {
+                short,
+                long,
+                long_fraction,
+            } => {
+                ui.label("Short:");
+                ui.add(egui::DragValue::new(short).range(1..=u16::MAX));
+                ui.label("Long:");
+                ui.add(egui::DragValue::new(long).range(1..=u16::MAX));
+                ui.label("Share of Long:");
+                ui.add(
+                    egui::DragValue::new(long_fraction)
+                        .range(0.0..=1.0)
+                        .speed(0.01),
+                );
+            }
+            BurstDistribution::Pareto { scale, shape } => {
+                ui.label("Minimum:");
+                ui.add(egui::DragValue::new(scale).range(1.0..=10000.0).speed(0.1));
+                ui.label("Shape:");
+                ui.add(egui::DragValue::new(shape).range(0.1..=10.0).speed(0.01));
+            }
+            BurstDistribution::LogNormal { mu, sigma } => {
+                ui.label("μ:");
+                ui.add(egui::DragValue::new(mu).range(0.0..=10.0).speed(0.01));
+                ui.label("σ:");
+                ui.add(egui::DragValue::new(sigma).range(0.0..=5.0).speed(0.01));
+            }
+        }
+    });
+}
+
+/// Colour of an algorithm's run in the results window's charts.
+fn run_color(algorithm: Algorithm) -> egui::Color32 {
+    const COLORS: [egui::Color32; 5] = [
+        egui::Color32::from_rgb(100, 149, 237),
+        egui::Color32::from_rgb(237, 125, 49),
+        egui::Color32::from_rgb(80, 170, 90),
+        egui::Color32::from_rgb(200, 70, 80),
+        egui::Color32::from_rgb(150, 100, 200),
+    ];
+    let index = Algorithm::ALL
+        .iter()
+        .position(|&other| other == algorithm)
+        .unwrap_or(0);
+    COLORS[index % COLORS.len()]
+}
+
+/// Colour of a multilevel queues run, apart from every algorithm's.
+const MULTILEVEL_COLOR: egui::Color32 = egui::Color32::from_rgb(190, 160, 40);
+
+// a chart's name, colour and values, one value per job or per tick
+type Series = (String, egui::Color32, Vec<f64>);
+
+/// Per job bar charts and over time line charts of `runs`, all of the same `jobs`.
+fn charts_ui(
+    ui: &mut egui::Ui,
+    jobs: &[Job],
+    runs: &[(String, egui::Color32, Schedule)],
+    cores: u32,
+    utilisation_window: u32,
+) {
+    ui.horizontal_wrapped(|ui| {
+        for (name, color, _) in runs {
+            ui.colored_label(*color, RichText::new(name).strong());
+        }
+    });
+
+    let names: Vec<String> = jobs.iter().map(|job| job.job_name.clone()).collect();
+    let per_job = |value: fn(&JobMetrics) -> u32| -> Vec<Series> {
+        runs.iter()
+            .map(|(name, color, schedule)| {
+                let metrics = job_metrics(schedule);
+                let values = jobs
+                    .iter()
+                    .map(|job| {
+                        metrics
+                            .iter()
+                            .find(|metrics| metrics.job_id == job.id)
+                            .map_or(0.0, |metrics| value(metrics) as f64)
+                    })
+                    .collect();
+                (name.clone(), *color, values)
+            })
+            .collect()
+    };
+    bar_chart(ui, "Waiting Time", &names, &per_job(|job| job.waiting_time));
+    bar_chart(
+        ui,
+        "Turnaround Time",
+        &names,
+        &per_job(|job| job.turnaround_time),
+    );
+    bar_chart(
+        ui,
+        "Response Time",
+        &names,
+        &per_job(|job| job.response_time),
+    );
+
+    let over_time = |values: &dyn Fn(&Schedule) -> Vec<f64>| -> Vec<Series> {
+        runs.iter()
+            .map(|(name, color, schedule)| (name.clone(), *color, values(schedule)))
+            .collect()
+    };
+    let counts = |counts: Vec<u32>| counts.into_iter().map(f64::from).collect();
+    line_chart(
+        ui,
+        "Ready Queue Length",
+        &over_time(&|schedule| counts(charts::queue_length(schedule))),
+        None,
+    );
+    line_chart(
+        ui,
+        &format!("CPU Utilisation (last {utilisation_window} ticks)"),
+        &over_time(&|schedule| charts::utilisation(schedule, cores, utilisation_window)),
+        Some(1.0),
+    );
+    line_chart(
+        ui,
+        "Completed Jobs",
+        &over_time(&|schedule| counts(charts::completions(schedule))),
+        None,
+    );
+}
+
+const CHART_HEIGHT: f32 = 120.0;
+
+/// Allocates a chart with its title, frame and y axis, returning the area to plot in.
+fn chart_frame(
+    ui: &mut egui::Ui,
+    title: &str,
+    y_max: f64,
+) -> (egui::Response, egui::Painter, egui::Rect) {
+    ui.label(RichText::new(title).strong());
+    let (response, painter) = ui.allocate_painter(
+        egui::vec2(ui.available_width(), CHART_HEIGHT),
+        egui::Sense::hover(),
+    );
+    let plot = egui::Rect::from_min_max(
+        response.rect.min + egui::vec2(36.0, 6.0),
+        response.rect.max - egui::vec2(6.0, 18.0),
+    );
+    let text_color = ui.visuals().text_color();
+    painter.rect_stroke(plot, 0.0, (0.5, egui::Color32::GRAY));
+    for (value, y) in [(y_max, plot.top()), (0.0, plot.bottom())] {
+        painter.text(
+            egui::pos2(plot.left() - 4.0, y),
+            egui::Align2::RIGHT_CENTER,
+            format!("{}", (value * 100.0).round() / 100.0),
+            egui::FontId::proportional(11.0),
+            text_color,
+        );
+    }
+    (response, painter, plot)
+}
+
+/// The largest value of any series, at least `1` so empty charts still have a scale.
+fn series_max(series: &[Series]) -> f64 {
+    series
+        .iter()
+        .flat_map(|(_, _, values)| values.iter().copied())
+        .fold(1.0, f64::max)
+}
+
+/// Bars grouped by `categories`, one bar per series in each group.
+fn bar_chart(ui: &mut egui::Ui, title: &str, categories: &[String], series: &[Series]) {
+    let y_max = series_max(series);
+    let (response, painter, plot) = chart_frame(ui, title, y_max);
+    if categories.is_empty() || series.is_empty() {
+        return;
+    }
+    let group_width = plot.width() / categories.len() as f32;
+    let bar_width = group_width * 0.8 / series.len() as f32;
+    for (group, category) in categories.iter().enumerate() {
+        let left = plot.left() + group as f32 * group_width + group_width * 0.1;
+        for (bar, (_, color, values)) in series.iter().enumerate() {
+            let value = values.get(group).copied().unwrap_or(0.0);
+            let x = left + bar as f32 * bar_width;
+            let top = plot.bottom() - (value / y_max) as f32 * plot.height();
+            painter.rect_filled(
+                egui::Rect::from_min_max(
+                    egui::pos2(x, top),
+                    egui::pos2(x + bar_width, plot.bottom()),
+                ),
+                0.0,
+                *color,
+            );
+        }
+        painter.text(
+            egui::pos2(left + group_width * 0.4, plot.bottom() + 2.0),
+            egui::Align2::CENTER_TOP,
+            category,
+            egui::FontId::proportional(11.0),
+            ui.visuals().text_color(),
+        );
+    }
+
+    if let Some(pointer) = response
+        .hover_pos()
+        .filter(|pointer| plot.contains(*pointer))
+    {
+        let group = (((pointer.x - plot.left()) / group_width) as usize).min(categories.len() - 1);
+        let mut text = categories[group].clone();
+        for (name, _, values) in series {
+            let value = values.get(group).copied().unwrap_or(0.0);
+            text.push_str(&format!("\n{name}: {value}"));
+        }
+        response.on_hover_text(text);
+    }
+}
+
+/// Series over time as step lines, averaged down to about one point per pixel.
+fn line_chart(ui: &mut egui::Ui, title: &str, series: &[Series], y_max: Option<f64>) {
+    let y_max = y_max.unwrap_or_else(|| series_max(series));
+    let (response, painter, plot) = chart_frame(ui, title, y_max);
+    let ticks = series
+        .iter()
+        .map(|(_, _, values)| values.len())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    painter.text(
+        plot.right_bottom() + egui::vec2(0.0, 2.0),
+        egui::Align2::RIGHT_TOP,
+        ticks,
+        egui::FontId::proportional(11.0),
+        ui.visuals().text_color(),
+    );
+    let x = |tick: usize| plot.left() + tick as f32 / ticks as f32 * plot.width();
+    for (_, color, values) in series {
+        let buckets = values.len().min(plot.width().max(1.0) as usize);
+        let points = (0..buckets)
+            .flat_map(|bucket| {
+                let range = bucket * values.len() / buckets..(bucket + 1) * values.len() / buckets;
+                let value = values[range.clone()].iter().sum::<f64>() / range.len() as f64;
+                let y = plot.bottom() - (value / y_max) as f32 * plot.height();
+                [egui::pos2(x(range.start), y), egui::pos2(x(range.end), y)]
+            })
+            .collect();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, *color)));
+    }
+
+    if let Some(pointer) = response
+        .hover_pos()
+        .filter(|pointer| plot.contains(*pointer))
+    {
+        let tick = ((pointer.x - plot.left()) / plot.width() * ticks as f32) as usize;
+        painter.line_segment(
+            [
+                egui::pos2(pointer.x, plot.top()),
+                egui::pos2(pointer.x, plot.bottom()),
+            ],
+            (0.5, egui::Color32::GRAY),
+        );
+        let mut text = format!("Tick {tick}");
+        for (name, _, values) in series {
+            if let Some(value) = values.get(tick) {
+                text.push_str(&format!("\n{name}: {}", (value * 100.0).round() / 100.0));
+            }
+        }
+        response.on_hover_text(text);
+    }
+}
+
+fn job_color(id: u32) -> egui::Color32 {
+    let [r, g, b] = process_scheduler::job_color(id);
+    egui::Color32::from_rgb(r, g, b)
+}
+
 fn powered_by_egui_and_eframe(ui: &mut egui::Ui) {
     ui.horizontal(|ui| {
         ui.spacing_mut().item_spacing.x = 0.0;
@@ -514,3 +2831,346 @@ fn github_link(ui: &mut egui::Ui) {
         );
     });
 }
+
+fn state_color(state: ProcessState) -> egui::Color32 {
+    match state {
+        ProcessState::New => egui::Color32::from_rgb(170, 170, 170),
+        ProcessState::Ready => egui::Color32::from_rgb(240, 190, 60),
+        ProcessState::Running => egui::Color32::from_rgb(90, 190, 90),
+        ProcessState::Waiting => egui::Color32::from_rgb(110, 140, 230),
+        ProcessState::Terminated => egui::Color32::from_rgb(90, 90, 90),
+        ProcessState::Suspended => egui::Color32::from_rgb(170, 110, 200),
+    }
+}
+
+/// The states one job goes through, as a swimlane over time and as the textbook
+/// diagram, with playback moving a cursor through both.
+fn lifecycle_ui(ui: &mut egui::Ui, schedule: &Schedule, results: &mut ResultsView) {
+    let mut jobs: Vec<&Job> = schedule.jobs.iter().collect();
+    jobs.sort_by_key(|job| job.id);
+    let Some(first) = schedule.jobs.first() else {
+        ui.label("No jobs");
+        return;
+    };
+    let job = jobs
+        .iter()
+        .find(|job| Some(job.id) == results.lifecycle_job)
+        .unwrap_or(&first);
+    let job_id = job.id;
+    let makespan = schedule
+        .jobs
+        .iter()
+        .map(|job| job.completion_time)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_label("Job")
+            .selected_text(&job.job_name)
+            .show_ui(ui, |ui| {
+                for job in &jobs {
+                    ui.selectable_value(&mut results.lifecycle_job, Some(job.id), &job.job_name);
+                }
+            });
+        let play = if results.playing { "Pause" } else { "Play" };
+        if ui.button(play).clicked() {
+            results.playing = !results.playing;
+            if results.playback_time >= makespan as f32 {
+                results.playback_time = 0.0;
+            }
+        }
+        ui.add(egui::Slider::new(&mut results.playback_time, 0.0..=makespan as f32).text("Tick"));
+        ui.label("Ticks per second:");
+        ui.add(
+            egui::DragValue::new(&mut results.playback_speed)
+                .range(0.1..=100.0)
+                .speed(0.1),
+        );
+    });
+    if results.playing {
+        results.playback_time += ui.input(|input| input.stable_dt) * results.playback_speed;
+        if results.playback_time >= makespan as f32 {
+            results.playback_time = makespan as f32;
+            results.playing = false;
+        }
+        ui.ctx().request_repaint();
+    }
+    let time = results.playback_time.clamp(0.0, makespan as f32);
+
+    let changes = &schedule.state_changes;
+    let (state, change) = lifecycle::state_at(changes, job_id, time as u32);
+    ui.label(match change {
+        Some(change) => format!(
+            "Tick {}: {} since {}, {}",
+            time as u32,
+            state.name(),
+            change.time,
+            change.from.transition_name(change.to)
+        ),
+        None => format!("Tick {}: {}", time as u32, state.name()),
+    });
+    // Suspended only comes up when jobs are swapped out
+    let swapping = changes
+        .iter()
+        .any(|change| change.to == ProcessState::Suspended);
+    let states = match swapping {
+        true => &ProcessState::ALL[..],
+        false => &ProcessState::ALL[..5],
+    };
+    swimlane(ui, changes, states, job_id, makespan, time);
+    ui.add_space(8.0);
+    state_diagram(ui, states, state, change, time);
+}
+
+/// One lane per state, filled where the job was in it, with the playback cursor at `time`.
+fn swimlane(
+    ui: &mut egui::Ui,
+    changes: &[StateChange],
+    states: &[ProcessState],
+    job_id: u32,
+    makespan: u32,
+    time: f32,
+) {
+    const LANE_HEIGHT: f32 = 18.0;
+    let (response, painter) = ui.allocate_painter(
+        egui::vec2(
+            ui.available_width(),
+            LANE_HEIGHT * states.len() as f32 + 16.0,
+        ),
+        egui::Sense::hover(),
+    );
+    let plot = egui::Rect::from_min_max(
+        response.rect.min + egui::vec2(80.0, 0.0),
+        response.rect.max - egui::vec2(6.0, 16.0),
+    );
+    let text_color = ui.visuals().text_color();
+    let x = |time: f32| plot.left() + time / makespan as f32 * plot.width();
+    let lane = |state: ProcessState| {
+        let top = plot.top() + state as usize as f32 * LANE_HEIGHT;
+        egui::Rangef::new(top + 2.0, top + LANE_HEIGHT - 2.0)
+    };
+
+    for &state in states {
+        painter.text(
+            egui::pos2(response.rect.left(), lane(state).center()),
+            egui::Align2::LEFT_CENTER,
+            state.name(),
+            egui::FontId::proportional(12.0),
+            text_color,
+        );
+    }
+    painter.rect_stroke(plot, 0.0, (0.5, egui::Color32::GRAY));
+    let spans = lifecycle::state_spans(changes, job_id, makespan);
+    for &(state, start, end) in &spans {
+        let rect = egui::Rect::from_x_y_ranges(x(start as f32)..=x(end as f32), lane(state));
+        painter.rect_filled(rect, 2.0, state_color(state));
+    }
+    for (value, align) in [
+        (0, egui::Align2::LEFT_TOP),
+        (makespan, egui::Align2::RIGHT_TOP),
+    ] {
+        painter.text(
+            egui::pos2(x(value as f32), plot.bottom() + 2.0),
+            align,
+            value,
+            egui::FontId::proportional(11.0),
+            text_color,
+        );
+    }
+    painter.line_segment(
+        [
+            egui::pos2(x(time), plot.top()),
+            egui::pos2(x(time), plot.bottom()),
+        ],
+        (2.0, egui::Color32::RED),
+    );
+
+    if let Some(pointer) = response
+        .hover_pos()
+        .filter(|pointer| plot.contains(*pointer))
+    {
+        let tick = ((pointer.x - plot.left()) / plot.width() * makespan as f32) as u32;
+        if let Some((state, start, end)) = spans
+            .iter()
+            .find(|&&(_, start, end)| start <= tick && tick < end)
+        {
+            response.on_hover_text(format!("{} from {start} to {end}", state.name()));
+        }
+    }
+}
+
+/// The five-state diagram, with Suspended when `states` has it, and the job's current
+/// state filled in. For the tick after a change a dot travels along the arrow it took.
+fn state_diagram(
+    ui: &mut egui::Ui,
+    states: &[ProcessState],
+    state: ProcessState,
+    change: Option<&StateChange>,
+    time: f32,
+) {
+    const RADIUS: f32 = 32.0;
+    let (response, painter) = ui.allocate_painter(
+        egui::vec2(ui.available_width().min(480.0), 220.0),
+        egui::Sense::hover(),
+    );
+    let rect = response.rect.shrink(RADIUS);
+    let position = |state: ProcessState| {
+        let (x, y) = match state {
+            ProcessState::New => (0.0, 0.0),
+            ProcessState::Ready => (0.3, 0.45),
+            ProcessState::Running => (0.7, 0.45),
+            ProcessState::Waiting => (0.5, 1.0),
+            ProcessState::Terminated => (1.0, 0.0),
+            ProcessState::Suspended => (0.05, 1.0),
+        };
+        rect.min + egui::vec2(x * rect.width(), y * rect.height())
+    };
+    let text_color = ui.visuals().text_color();
+    let transitions: Vec<(ProcessState, ProcessState)> = lifecycle::TRANSITIONS
+        .into_iter()
+        .chain(lifecycle::SWAP_TRANSITIONS)
+        .filter(|(from, to)| states.contains(from) && states.contains(to))
+        .collect();
+    // arrows both ways between two states are drawn side by side
+    let arrow_ends = |from: ProcessState, to: ProcessState| {
+        let (start, end) = (position(from), position(to));
+        let direction = (end - start).normalized();
+        let side = match transitions.contains(&(to, from)) {
+            true => direction.rot90() * 6.0,
+            false => egui::Vec2::ZERO,
+        };
+        (
+            start + direction * RADIUS + side,
+            end - direction * RADIUS + side,
+        )
+    };
+    let taken = change
+        .filter(|change| time - (change.time as f32) < 1.0)
+        .map(|change| (change.from, change.to));
+
+    for &(from, to) in &transitions {
+        let (start, end) = arrow_ends(from, to);
+        let stroke = match taken == Some((from, to)) {
+            true => egui::Stroke::new(3.0, egui::Color32::RED),
+            false => egui::Stroke::new(1.0, egui::Color32::GRAY),
+        };
+        painter.arrow(start, end - start, stroke);
+        let side = (end - start).normalized().rot90() * 10.0;
+        painter.text(
+            start + (end - start) * 0.5 + side,
+            egui::Align2::CENTER_CENTER,
+            from.transition_name(to),
+            egui::FontId::proportional(11.0),
+            text_color,
+        );
+    }
+    for &node in states {
+        let fill = match node == state {
+            true => state_color(node),
+            false => ui.visuals().extreme_bg_color,
+        };
+        painter.circle(position(node), RADIUS, fill, (1.0, text_color));
+        painter.text(
+            position(node),
+            egui::Align2::CENTER_CENTER,
+            node.name(),
+            egui::FontId::proportional(12.0),
+            text_color,
+        );
+    }
+    if let (Some((from, to)), Some(change)) = (taken, change) {
+        let (start, end) = arrow_ends(from, to);
+        let progress = (time - change.time as f32).clamp(0.0, 1.0);
+        painter.circle_filled(start + (end - start) * progress, 5.0, egui::Color32::RED);
+    }
+}
+
+/// Jobs with dependencies drawn left to right, each in the column after the last job
+/// it waits for, with the critical path in red.
+fn dependency_graph_ui(ui: &mut egui::Ui, jobs: &[Job]) {
+    const NODE: egui::Vec2 = egui::vec2(56.0, 22.0);
+    const SPACING: egui::Vec2 = egui::vec2(84.0, 34.0);
+    ui.label(RichText::new("Dependencies").strong());
+    let order = match dependencies::topological_order(jobs) {
+        Ok(order) => order,
+        Err(error) => {
+            ui.colored_label(egui::Color32::RED, error);
+            return;
+        }
+    };
+    let predecessors = dependencies::predecessors(jobs);
+    let mut shown = vec![false; jobs.len()];
+    for (job, predecessors) in predecessors.iter().enumerate() {
+        if !predecessors.is_empty() {
+            shown[job] = true;
+            for &predecessor in predecessors {
+                shown[predecessor] = true;
+            }
+        }
+    }
+    let mut column = vec![0; jobs.len()];
+    for &job in &order {
+        column[job] = predecessors[job]
+            .iter()
+            .map(|&predecessor| column[predecessor] + 1)
+            .max()
+            .unwrap_or(0);
+    }
+    let mut rows: Vec<usize> = Vec::new();
+    let mut row = vec![0; jobs.len()];
+    for job in (0..jobs.len()).filter(|&job| shown[job]) {
+        if rows.len() <= column[job] {
+            rows.resize(column[job] + 1, 0);
+        }
+        row[job] = rows[column[job]];
+        rows[column[job]] += 1;
+    }
+
+    let size = egui::vec2(
+        rows.len() as f32 * SPACING.x,
+        rows.iter().copied().max().unwrap_or(0) as f32 * SPACING.y,
+    );
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+    let center = |job: usize| {
+        response.rect.min
+            + egui::vec2(
+                (column[job] as f32 + 0.5) * SPACING.x,
+                (row[job] as f32 + 0.5) * SPACING.y,
+            )
+    };
+    let critical = dependencies::critical_path(jobs).unwrap_or_default();
+    let on_critical_path = |a: usize, b: usize| {
+        critical
+            .jobs
+            .windows(2)
+            .any(|pair| pair == [jobs[a].id, jobs[b].id])
+    };
+    for (job, predecessors) in predecessors.iter().enumerate() {
+        for &predecessor in predecessors {
+            let (start, end) = (center(predecessor), center(job));
+            let direction = (end - start).normalized();
+            // leave the arrow at the edge of the node it points to
+            let reach = (NODE.x / 2.0 / direction.x.abs().max(0.01))
+                .min(NODE.y / 2.0 / direction.y.abs().max(0.01));
+            let inset = direction * reach;
+            let stroke = match on_critical_path(predecessor, job) {
+                true => egui::Stroke::new(2.0, egui::Color32::RED),
+                false => egui::Stroke::new(1.0, egui::Color32::GRAY),
+            };
+            painter.arrow(start + inset, end - start - inset * 2.0, stroke);
+        }
+    }
+    for job in (0..jobs.len()).filter(|&job| shown[job]) {
+        let rect = egui::Rect::from_center_size(center(job), NODE);
+        painter.rect_filled(rect, 4.0, job_color(jobs[job].id));
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            format!("{} ({})", jobs[job].job_name, jobs[job].needed_cpu_cycle),
+            egui::FontId::proportional(12.0),
+            egui::Color32::BLACK,
+        );
+    }
+    ui.label(critical.describe(jobs));
+}
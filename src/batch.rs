@@ -0,0 +1,343 @@
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::generator::WorkloadGenerator;
+use crate::metrics::{run_metrics, RunMetrics};
+use crate::process_scheduler::{job_builder, simulate, Algorithm, Job, SchedulerConfig};
+
+/// Parameters to sweep over. Every combination is run on every workload.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sweep {
+    pub algorithms: Vec<Algorithm>,
+    /// Only varied for Round Robin, the other algorithms ignore the quantum.
+    pub time_quanta: Vec<u32>,
+    pub cores: Vec<u32>,
+    pub context_switch_costs: Vec<u32>,
+    pub workloads: u32,
+    pub jobs_per_workload: u32,
+    /// Arrival process and burst distribution of the workloads, its seed is ignored.
+    pub generator: WorkloadGenerator,
+    /// Workload `i` is generated from `seed + i`.
+    pub seed: u64,
+    pub threads: usize,
+}
+
+impl Default for Sweep {
+    fn default() -> Self {
+        Self {
+            algorithms: vec![
+                Algorithm::Fcfs,
+                Algorithm::Sjn,
+                Algorithm::Srt,
+                Algorithm::RoundRobin,
+            ],
+            time_quanta: vec![1, 2, 4, 8],
+            cores: vec![1],
+            context_switch_costs: vec![0],
+            workloads: 30,
+            jobs_per_workload: 10,
+            generator: WorkloadGenerator::default(),
+            seed: 0,
+            threads: std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+        }
+    }
+}
+
+impl Sweep {
+    /// Every scheduler configuration in the sweep.
+    pub fn configs(&self) -> Vec<SchedulerConfig> {
+        let mut configs = Vec::new();
+        for &algorithm in &self.algorithms {
+            let time_quanta = if algorithm == Algorithm::RoundRobin {
+                &self.time_quanta[..]
+            } else {
+                &self.time_quanta[..self.time_quanta.len().min(1)]
+            };
+            for &time_quantum in time_quanta {
+                for &cores in &self.cores {
+                    for &context_switch_cost in &self.context_switch_costs {
+                        configs.push(SchedulerConfig {
+                            algorithm,
+                            time_quantum,
+                            cores,
+                            context_switch_cost,
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+        }
+        configs
+    }
+
+    pub fn workload_seed(&self, workload: u32) -> u64 {
+        self.seed.wrapping_add(workload as u64)
+    }
+
+    /// The random workload every configuration is run against for `workload`.
+    pub fn workload(&self, workload: u32) -> Vec<Job> {
+        let generator = WorkloadGenerator {
+            seed: self.workload_seed(workload),
+            ..self.generator.clone()
+        };
+        generator.generate(job_builder(&[], self.jobs_per_workload))
+    }
+}
+
+/// One configuration run on one workload.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Run {
+    pub workload: u32,
+    pub seed: u64,
+    pub config: SchedulerConfig,
+    pub metrics: RunMetrics,
+}
+
+/// Mean and 95% confidence interval of one metric over all workloads of a configuration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary {
+    pub config: SchedulerConfig,
+    pub metric: &'static str,
+    pub runs: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    /// Half width of the 95% confidence interval of the mean.
+    pub ci95: f64,
+}
+
+/// Runs every configuration on every workload, spread over `sweep.threads` threads.
+/// Runs are returned grouped by workload in the order of [`Sweep::configs`], whatever the thread count.
+pub fn run_sweep(sweep: &Sweep) -> Vec<Run> {
+    let configs = sweep.configs();
+    let tasks: Vec<(u32, &SchedulerConfig)> = (0..sweep.workloads)
+        .flat_map(|workload| configs.iter().map(move |config| (workload, config)))
+        .collect();
+
+    let run_task = |(workload, config): (u32, &SchedulerConfig)| {
+        let seed = sweep.workload_seed(workload);
+        let schedule = simulate(
+            &sweep.workload(workload),
+            &SchedulerConfig {
+                seed,
+                ..config.clone()
+            },
+        );
+        Run {
+            workload,
+            seed,
+            config: config.clone(),
+            metrics: run_metrics(&schedule, config.cores),
+        }
+    };
+
+    if sweep.threads <= 1 {
+        return tasks.into_iter().map(run_task).collect();
+    }
+
+    let next_task = AtomicUsize::new(0);
+    let mut runs: Vec<(usize, Run)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..sweep.threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let task = next_task.fetch_add(1, Ordering::Relaxed);
+                        if task >= tasks.len() {
+                            break done;
+                        }
+                        done.push((task, run_task(tasks[task])));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("batch worker panicked"))
+            .collect()
+    });
+    runs.sort_by_key(|(task, _)| *task);
+    runs.into_iter().map(|(_, run)| run).collect()
+}
+
+/// Aggregates runs per configuration and metric.
+pub fn summarize(runs: &[Run]) -> Vec<Summary> {
+    let mut configs: Vec<&SchedulerConfig> = Vec::new();
+    for run in runs {
+        if !configs.contains(&&run.config) {
+            configs.push(&run.config);
+        }
+    }
+
+    let mut summaries = Vec::new();
+    for config in configs {
+        let metrics: Vec<&RunMetrics> = runs
+            .iter()
+            .filter(|run| &run.config == config)
+            .map(|run| &run.metrics)
+            .collect();
+        for (index, (metric, _)) in RunMetrics::default().values().into_iter().enumerate() {
+            let values: Vec<f64> = metrics.iter().map(|m| m.values()[index].1).collect();
+            let (mean, std_dev, ci95) = mean_and_ci95(&values);
+            summaries.push(Summary {
+                config: config.clone(),
+                metric,
+                runs: values.len(),
+                mean,
+                std_dev,
+                ci95,
+            });
+        }
+    }
+    summaries
+}
+
+/// Mean, sample standard deviation and the half width of the 95% confidence interval.
+fn mean_and_ci95(values: &[f64]) -> (f64, f64, f64) {
+    let n = values.len();
+    if n == 0 {
+        return (0.0, 0.0, 0.0);
+    }
+    let mean = values.iter().sum::<f64>() / n as f64;
+    if n == 1 {
+        return (mean, 0.0, 0.0);
+    }
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / (n - 1) as f64;
+    let std_dev = variance.sqrt();
+    (
+        mean,
+        std_dev,
+        t_critical_95(n - 1) * std_dev / (n as f64).sqrt(),
+    )
+}
+
+/// Two sided 95% critical value of Student's t distribution.
+fn t_critical_95(degrees_of_freedom: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+    TABLE
+        .get(degrees_of_freedom.wrapping_sub(1))
+        .copied()
+        .unwrap_or(1.960)
+}
+
+fn config_columns(config: &SchedulerConfig) -> String {
+    let time_quantum = if config.algorithm == Algorithm::RoundRobin {
+        config.time_quantum.to_string()
+    } else {
+        String::new()
+    };
+    format!(
+        "{},{},{},{}",
+        config.algorithm.short_name(),
+        time_quantum,
+        config.cores,
+        config.context_switch_cost
+    )
+}
+
+/// One row per run with every metric as a column.
+pub fn write_runs_csv(writer: &mut impl Write, runs: &[Run]) -> io::Result<()> {
+    let metric_names: Vec<&str> = RunMetrics::default()
+        .values()
+        .iter()
+        .map(|(name, _)| *name)
+        .collect();
+    writeln!(
+        writer,
+        "workload,seed,algorithm,time_quantum,cores,context_switch_cost,{}",
+        metric_names.join(",")
+    )?;
+    for run in runs {
+        let values: Vec<String> = run
+            .metrics
+            .values()
+            .iter()
+            .map(|(_, value)| value.to_string())
+            .collect();
+        writeln!(
+            writer,
+            "{},{},{},{}",
+            run.workload,
+            run.seed,
+            config_columns(&run.config),
+            values.join(",")
+        )?;
+    }
+    Ok(())
+}
+
+/// One row per configuration and metric.
+pub fn write_summary_csv(writer: &mut impl Write, summaries: &[Summary]) -> io::Result<()> {
+    writeln!(
+        writer,
+        "algorithm,time_quantum,cores,context_switch_cost,metric,runs,mean,std_dev,ci95_low,ci95_high"
+    )?;
+    for summary in summaries {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{}",
+            config_columns(&summary.config),
+            summary.metric,
+            summary.runs,
+            summary.mean,
+            summary.std_dev,
+            summary.mean - summary.ci95,
+            summary.mean + summary.ci95
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantum_is_only_swept_for_rr() {
+        let sweep = Sweep {
+            algorithms: vec![Algorithm::Fcfs, Algorithm::RoundRobin],
+            time_quanta: vec![1, 2, 3],
+            cores: vec![1, 2],
+            ..Default::default()
+        };
+        let configs = sweep.configs();
+        assert_eq!(configs.len(), 2 + 3 * 2);
+        assert!(configs[..2]
+            .iter()
+            .all(|config| config.algorithm == Algorithm::Fcfs));
+    }
+
+    #[test]
+    fn threads_do_not_change_results() {
+        let sweep = Sweep {
+            workloads: 5,
+            seed: 7,
+            threads: 1,
+            ..Default::default()
+        };
+        let runs = run_sweep(&sweep);
+        assert_eq!(runs.len(), 5 * sweep.configs().len());
+        assert_eq!(
+            runs,
+            run_sweep(&Sweep {
+                threads: 4,
+                ..sweep
+            })
+        );
+    }
+
+    #[test]
+    fn confidence_interval() {
+        let (mean, std_dev, ci95) = mean_and_ci95(&[2.0, 4.0, 6.0]);
+        assert_eq!(mean, 4.0);
+        assert_eq!(std_dev, 2.0);
+        assert!((ci95 - 4.303 * 2.0 / 3f64.sqrt()).abs() < 1e-9);
+    }
+}
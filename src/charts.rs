@@ -0,0 +1,127 @@
+use crate::lifecycle::{state_spans, ProcessState};
+use crate::process_scheduler::Schedule;
+
+/// Completion time of the last job, the length of every series below.
+fn makespan(schedule: &Schedule) -> usize {
+    schedule
+        .jobs
+        .iter()
+        .map(|job| job.completion_time)
+        .max()
+        .unwrap_or(0) as usize
+}
+
+/// Cores running a job during each tick.
+pub fn busy_cores(schedule: &Schedule) -> Vec<u32> {
+    let mut busy = vec![0; makespan(schedule)];
+    for segment in &schedule.segments {
+        for tick in &mut busy[segment.start as usize..segment.end as usize] {
+            *tick += 1;
+        }
+    }
+    busy
+}
+
+/// Jobs in the ready queue during each tick, by their Ready spans in the state changes.
+/// Jobs held back by dependencies, blocked on a resource or swapped out are not counted.
+pub fn queue_length(schedule: &Schedule) -> Vec<u32> {
+    let makespan = makespan(schedule);
+    let mut waiting = vec![0u32; makespan];
+    for job in &schedule.jobs {
+        for (state, start, end) in state_spans(&schedule.state_changes, job.id, makespan as u32) {
+            if state == ProcessState::Ready {
+                for tick in &mut waiting[start as usize..end as usize] {
+                    *tick += 1;
+                }
+            }
+        }
+    }
+    waiting
+}
+
+/// Share of core time spent running jobs over the last `window` ticks up to each tick,
+/// or over all ticks so far during the first `window`.
+pub fn utilisation(schedule: &Schedule, cores: u32, window: u32) -> Vec<f64> {
+    let busy = busy_cores(schedule);
+    let window = window.max(1) as usize;
+    let mut sum = 0;
+    (0..busy.len())
+        .map(|tick| {
+            sum += busy[tick];
+            if tick >= window {
+                sum -= busy[tick - window];
+            }
+            sum as f64 / (window.min(tick + 1) as f64 * cores.max(1) as f64)
+        })
+        .collect()
+}
+
+/// Jobs finished by the end of each tick.
+pub fn completions(schedule: &Schedule) -> Vec<u32> {
+    let mut finished = vec![0; makespan(schedule)];
+    for job in &schedule.jobs {
+        if let Some(tick) = (job.completion_time as usize).checked_sub(1) {
+            finished[tick] += 1;
+        }
+    }
+    let mut total = 0;
+    for tick in &mut finished {
+        total += *tick;
+        *tick = total;
+    }
+    finished
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_scheduler::{simulate, Algorithm, Job, SchedulerConfig};
+
+    fn schedule() -> Schedule {
+        // A runs 0-4, B waits from 1 to 4 and runs 4-6, nothing to do until C arrives at 8
+        let jobs = [(0, 4), (1, 2), (8, 1)].map(|(arrival_time, needed_cpu_cycle)| Job {
+            arrival_time,
+            needed_cpu_cycle,
+            ..Default::default()
+        });
+        simulate(
+            &jobs,
+            &SchedulerConfig {
+                algorithm: Algorithm::Fcfs,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn queue_and_completions() {
+        let schedule = schedule();
+        assert_eq!(busy_cores(&schedule), [1, 1, 1, 1, 1, 1, 0, 0, 1]);
+        assert_eq!(queue_length(&schedule), [0, 1, 1, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(completions(&schedule), [0, 0, 0, 1, 1, 2, 2, 2, 3]);
+    }
+
+    #[test]
+    fn dependents_are_not_queued() {
+        // B arrives at 1 but waits for A until 4 and runs straight away, C arrives at 2
+        // and queues until B is done at 6
+        let mut jobs = crate::process_scheduler::job_builder(&[], 3);
+        for (job, (arrival_time, needed_cpu_cycle)) in jobs.iter_mut().zip([(0, 4), (1, 2), (2, 1)])
+        {
+            job.arrival_time = arrival_time;
+            job.needed_cpu_cycle = needed_cpu_cycle;
+        }
+        jobs[1].depends_on = vec![jobs[0].id];
+        let schedule = simulate(&jobs, &SchedulerConfig::default());
+        assert_eq!(queue_length(&schedule), [0, 0, 1, 1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn sliding_utilisation() {
+        let utilisation = utilisation(&schedule(), 1, 4);
+        assert_eq!(utilisation[..4], [1.0; 4]);
+        assert_eq!(utilisation[6..], [0.75, 0.5, 0.5]);
+        // two cores, one of them idle
+        assert_eq!(super::utilisation(&schedule(), 2, 4)[0], 0.5);
+    }
+}
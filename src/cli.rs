@@ -0,0 +1,752 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::batch::{run_sweep, summarize, write_runs_csv, write_summary_csv, Sweep};
+use crate::dependencies::critical_path;
+use crate::energy::{energy_report, missed_deadlines, DvfsPolicy, PowerModel};
+use crate::gantt::text_gantt;
+use crate::generator::WorkloadGenerator;
+use crate::golden;
+use crate::grader::{self, Claim};
+use crate::locking::LockProtocol;
+use crate::memory::{admission_delay, check_memory, memory_used, Admission, MemoryModel};
+use crate::metrics::{job_metrics, run_metrics, Starvation};
+use crate::multilevel::{class_ticks, JobClass, MultilevelQueues, QueueSharing};
+use crate::optimal;
+use crate::process_scheduler::{self, job_builder, Aging, Algorithm, SchedulerConfig, TieBreak};
+use crate::quiz::{Quiz, QuizSet};
+use crate::swf::{self, SwfOptions};
+use crate::trace::{self, TraceOptions};
+use crate::workload::Workload;
+
+const USAGE: &str = "\
+Usage: process_scheduling_simulator <command> [options]
+Without a command the simulator window is opened.
+
+Commands:
+  batch     Sweep scheduler parameters over seeded random workloads
+      --algorithms <list>       fcfs,sjn,srt,rr,random (default fcfs,sjn,srt,rr)
+      --quantum <list>          Round Robin time quanta (default 1,2,4,8)
+      --cores <list>            number of cores (default 1)
+      --context-switch <list>   context switch costs in ticks (default 0)
+      --workloads <n>           random workloads per configuration (default 30)
+      --jobs <n>                jobs per workload (default 10)
+      --seed <n>                seed of the first workload (default 0)
+      --arrivals <spec>         arrival process, see generate (default uniform:9)
+      --bursts <spec>           burst distribution, see generate (default uniform:1,9)
+      --threads <n>             worker threads (default: all cores)
+      --out <file>              per run metrics (default runs.csv)
+      --summary <file>          means and 95% confidence intervals (default summary.csv)
+  generate  Write a seeded random workload file
+      --jobs <n>                number of jobs (default 10)
+      --seed <n>                (default 0)
+      --arrivals <spec>         uniform:MAX, poisson:RATE or bursty:GROUP_SIZE,MEAN_GAP
+      --bursts <spec>           uniform:MIN,MAX, exponential:MEAN, bimodal:SHORT,LONG,LONG_FRACTION,
+                                pareto:SCALE,SHAPE or lognormal:MU,SIGMA
+      --algorithm <name>        algorithm stored with the jobs (default fcfs)
+      --quantum <n>             time quantum stored with the jobs (default 5)
+      --out <file>              (default: print to stdout)
+  golden <dir>
+            Run every workload file in a directory under every algorithm and compare the
+            timelines and metrics with the .golden file next to it
+      --update <true|false>     rewrite the golden files instead (default false)
+  grade <file>
+            Check a claimed schedule for a workload file and print a graded report
+      --claim <file>            timeline and/or per job metrics as JSON or CSV, may be given
+                                more than once
+      --algorithm <name>        override the algorithm stored in the workload
+      --quantum <n>             override the time quantum
+      --format <text|json>      (default text)
+  import-swf <file>
+            Convert a Standard Workload Format trace into a workload file
+      --time-unit <n>           seconds per tick (default 1)
+      --max-jobs <n>            keep only the first n jobs
+      --users <list>            keep only jobs of these user ids
+      --queues <list>           keep only jobs from these queues
+      --scale-by-processors <true|false>
+                                multiply run times by the processors used (default false)
+      --algorithm <name>        algorithm stored with the jobs (default fcfs)
+      --quantum <n>             time quantum stored with the jobs (default 5)
+      --out <file>              (default: print to stdout)
+  import-trace <file>
+            Convert `perf sched script` or ftrace sched_switch/sched_wakeup output into a workload file
+      --time-unit-us <n>        microseconds per tick (default 1000)
+      --per-burst <true|false>  one job per CPU burst instead of per task (default false)
+      --comms <list>            keep only tasks with these names
+      --max-jobs <n>            keep only the first n jobs to arrive
+      --algorithm <name>        algorithm stored with the jobs (default fcfs)
+      --quantum <n>             time quantum stored with the jobs (default 5)
+      --compare <true|false>    print the observed schedule next to the simulated one
+                                instead of the workload (default false)
+      --width <n>               width of the compared charts (default 100)
+      --out <file>              (default: print to stdout)
+  quiz      Write a set of random quizzes to hand out for the Quiz section
+      --count <n>               number of quizzes (default 5)
+      --jobs <n>                jobs per quiz (default 5)
+      --algorithm <name>        algorithm the quizzes ask for (default fcfs)
+      --quantum <n>             Round Robin time quantum (default 5)
+      --out <file>              (default: print to stdout)
+  simulate <file>
+            Run a workload file and print the timeline and per job results
+      --algorithm <name>        override the algorithm stored in the file
+      --quantum <n>             override the time quantum
+      --cores <n>               override the number of cores
+      --context-switch <n>      override the context switch cost
+      --tie-break <rule>        fifo, lifo, arrival, id or name (default fifo)
+      --preempted-first <true|false>
+                                queue preempted jobs ahead of jobs arriving in the same tick
+                                (default false)
+      --aging-rate <x>          turn on aging, lowering a waiting job's rank by x per tick
+      --aging-cap <n>           most aging can lower a job's rank (default 10)
+      --locking <protocol>      none, inheritance or ceiling, for jobs with critical sections
+                                (default none)
+      --dvfs <policy>           turn on the energy model, picking the CPU frequency by
+                                race-to-idle or lowest-feasible (meeting job deadlines)
+      --memory-size <n>         turn on the memory model with n units for jobs
+      --admission <policy>      fcfs, first-fit or best-fit, turning on the memory model
+                                (default fcfs)
+      --swap-cost <n>           swap ready jobs out to admit waiting ones, taking n ticks
+                                each way
+      --multilevel <sharing>    turn on multilevel queues by job class in place of the
+                                algorithm, shared by strict priority or time slicing
+      --slices <s,i,b>          ticks the system, interactive and batch queues get in each
+                                round of time slicing (default 2,8,2)
+      --starve-wait <n>         flag jobs waiting longer than n ticks as starved
+      --starve-multiple <x>     flag jobs waiting longer than x times their CPU cycles
+                                as starved (default 5)
+  tui       Edit and simulate a workload in the terminal
+      [file]                    workload file to open
+  help      Show this message";
+
+/// Runs the subcommand in `args` (without the program name).
+pub fn run(args: &[String]) -> Result<(), String> {
+    match args.first().map(String::as_str) {
+        Some("batch") => batch(&args[1..]),
+        Some("generate") => generate(&args[1..]),
+        Some("golden") => golden(&args[1..]),
+        Some("grade") => grade(&args[1..]),
+        Some("import-swf") => import_swf(&args[1..]),
+        Some("import-trace") => import_trace(&args[1..]),
+        Some("quiz") => quiz(&args[1..]),
+        Some("simulate") => simulate(&args[1..]),
+        Some("tui") => match &args[1..] {
+            [] => crate::tui::run(None),
+            [path] => crate::tui::run(Some(path.into())),
+            _ => Err(USAGE.to_string()),
+        },
+        Some("help" | "--help" | "-h") => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        Some(command) => Err(format!("unknown command `{command}`\n\n{USAGE}")),
+        None => Err(USAGE.to_string()),
+    }
+}
+
+/// `--name value` pairs in the order given.
+type Options<'a> = Vec<(&'a str, &'a str)>;
+
+fn options(args: &[String]) -> Result<Options<'_>, String> {
+    let mut options = Vec::new();
+    let mut args = args.iter();
+    while let Some(name) = args.next() {
+        let Some(name) = name.strip_prefix("--") else {
+            return Err(format!("expected an option, found `{name}`"));
+        };
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for --{name}"))?;
+        options.push((name, value.as_str()));
+    }
+    Ok(options)
+}
+
+/// Splits off the file every command working on a file starts with.
+fn file_and_options(args: &[String]) -> Result<(&Path, Options<'_>), String> {
+    match args.split_first() {
+        Some((file, rest)) if !file.starts_with("--") => Ok((Path::new(file), options(rest)?)),
+        _ => Err(format!("missing file\n\n{USAGE}")),
+    }
+}
+
+fn parse<T: FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid value `{value}` for --{name}"))
+}
+
+fn parse_list<T: FromStr>(name: &str, value: &str) -> Result<Vec<T>, String> {
+    value.split(',').map(|item| parse(name, item)).collect()
+}
+
+fn algorithm(name: &str) -> Result<Algorithm, String> {
+    Algorithm::from_name(name.trim()).ok_or_else(|| format!("unknown algorithm `{name}`"))
+}
+
+fn batch(args: &[String]) -> Result<(), String> {
+    let mut sweep = Sweep::default();
+    let mut out = "runs.csv".to_string();
+    let mut summary = "summary.csv".to_string();
+    for (name, value) in options(args)? {
+        match name {
+            "algorithms" => {
+                sweep.algorithms = value.split(',').map(algorithm).collect::<Result<_, _>>()?
+            }
+            "quantum" => sweep.time_quanta = parse_list(name, value)?,
+            "cores" => sweep.cores = parse_list(name, value)?,
+            "context-switch" => sweep.context_switch_costs = parse_list(name, value)?,
+            "workloads" => sweep.workloads = parse(name, value)?,
+            "jobs" => sweep.jobs_per_workload = parse(name, value)?,
+            "seed" => sweep.seed = parse(name, value)?,
+            "arrivals" => sweep.generator.arrivals = value.parse()?,
+            "bursts" => sweep.generator.bursts = value.parse()?,
+            "threads" => sweep.threads = parse(name, value)?,
+            "out" => out = value.to_string(),
+            "summary" => summary = value.to_string(),
+            _ => return Err(format!("unknown option --{name}\n\n{USAGE}")),
+        }
+    }
+    if sweep.time_quanta.contains(&0) || sweep.cores.contains(&0) {
+        return Err("time quanta and core counts must be at least 1".to_string());
+    }
+
+    let runs = run_sweep(&sweep);
+    let summaries = summarize(&runs);
+
+    let create = |path: &str| {
+        File::create(path)
+            .map(BufWriter::new)
+            .map_err(|error| format!("cannot create {path}: {error}"))
+    };
+    write_runs_csv(&mut create(&out)?, &runs).map_err(|error| error.to_string())?;
+    write_summary_csv(&mut create(&summary)?, &summaries).map_err(|error| error.to_string())?;
+
+    println!(
+        "{} configurations x {} workloads = {} runs",
+        sweep.configs().len(),
+        sweep.workloads,
+        runs.len()
+    );
+    println!("Wrote {out} and {summary}");
+    Ok(())
+}
+
+fn generate(args: &[String]) -> Result<(), String> {
+    let mut generator = WorkloadGenerator::default();
+    let mut job_count = 10;
+    let mut config = SchedulerConfig::default();
+    let mut out = None;
+    for (name, value) in options(args)? {
+        match name {
+            "jobs" => job_count = parse(name, value)?,
+            "seed" => generator.seed = parse(name, value)?,
+            "arrivals" => generator.arrivals = value.parse()?,
+            "bursts" => generator.bursts = value.parse()?,
+            "algorithm" => config.algorithm = algorithm(value)?,
+            "quantum" => config.time_quantum = parse(name, value)?,
+            "out" => out = Some(value),
+            _ => return Err(format!("unknown option --{name}\n\n{USAGE}")),
+        }
+    }
+
+    let workload = Workload {
+        jobs: generator.generate(job_builder(&[], job_count)),
+        config,
+    };
+    match out {
+        Some(path) => workload.save(Path::new(path)),
+        None => {
+            println!("{}", workload.to_json());
+            Ok(())
+        }
+    }
+}
+
+fn golden(args: &[String]) -> Result<(), String> {
+    let (dir, options) = file_and_options(args)?;
+    let mut update = false;
+    for (name, value) in options {
+        match name {
+            "update" => update = parse(name, value)?,
+            _ => return Err(format!("unknown option --{name}\n\n{USAGE}")),
+        }
+    }
+
+    if update {
+        for path in golden::update(dir)? {
+            println!("updated {}", path.display());
+        }
+        return Ok(());
+    }
+    let failures = golden::check(dir)?;
+    if failures.is_empty() {
+        println!("all golden files match");
+        Ok(())
+    } else {
+        Err(failures.join("\n"))
+    }
+}
+
+fn quiz(args: &[String]) -> Result<(), String> {
+    let mut count = 5;
+    let mut job_count = 5;
+    let mut config = SchedulerConfig::default();
+    let mut out = None;
+    for (name, value) in options(args)? {
+        match name {
+            "count" => count = parse(name, value)?,
+            "jobs" => job_count = parse(name, value)?,
+            "algorithm" => config.algorithm = algorithm(value)?,
+            "quantum" => config.time_quantum = parse(name, value)?,
+            "out" => out = Some(value),
+            _ => return Err(format!("unknown option --{name}\n\n{USAGE}")),
+        }
+    }
+
+    let set = QuizSet {
+        quizzes: (1..=count)
+            .map(|number: u32| Quiz::generate(&format!("Quiz {number}"), job_count, config.clone()))
+            .collect(),
+    };
+    match out {
+        Some(path) => set.save(Path::new(path)),
+        None => {
+            println!("{}", set.to_json());
+            Ok(())
+        }
+    }
+}
+
+fn grade(args: &[String]) -> Result<(), String> {
+    let (file, options) = file_and_options(args)?;
+    let mut workload = Workload::load(file)?;
+    let mut claim = Claim::default();
+    let mut json = false;
+    for (name, value) in options {
+        match name {
+            "claim" => {
+                let text = std::fs::read_to_string(value)
+                    .map_err(|error| format!("cannot read {value}: {error}"))?;
+                let part = Claim::parse(&text)?;
+                claim.segments.extend(part.segments);
+                claim.jobs.extend(part.jobs);
+            }
+            "algorithm" => workload.config.algorithm = algorithm(value)?,
+            "quantum" => workload.config.time_quantum = parse(name, value)?,
+            "format" => {
+                json = match value {
+                    "json" => true,
+                    "text" => false,
+                    _ => return Err(format!("invalid value `{value}` for --{name}")),
+                }
+            }
+            _ => return Err(format!("unknown option --{name}\n\n{USAGE}")),
+        }
+    }
+
+    let report = grader::grade(&workload, &claim)?;
+    if json {
+        let json = serde_json::to_string_pretty(&report).expect("reports always serialize");
+        println!("{json}");
+        return Ok(());
+    }
+    let mark = |correct| if correct { "ok" } else { "wrong" };
+    println!(
+        "{} score {:.1}%",
+        workload.config.algorithm.name(),
+        report.score * 100.0
+    );
+    for issue in &report.issues {
+        println!("  {issue}");
+    }
+    if !report.segments.is_empty() {
+        println!();
+        for segment in &report.segments {
+            println!(
+                "{:>8} {:>8}  {:<12} {}",
+                segment.start,
+                segment.end,
+                segment.job,
+                mark(segment.correct)
+            );
+        }
+        for segment in &report.missing_segments {
+            println!(
+                "{:>8} {:>8}  {:<12} missing",
+                segment.start, segment.end, segment.job
+            );
+        }
+    }
+    if !report.jobs.is_empty() {
+        println!();
+        for job in &report.jobs {
+            for metric in &job.metrics {
+                print!(
+                    "{:<12} {:<16} {:>6}  {}",
+                    job.job,
+                    metric.metric,
+                    metric.claimed,
+                    mark(metric.correct)
+                );
+                if !metric.correct {
+                    print!(", expected {}", metric.expected);
+                }
+                println!();
+            }
+        }
+    }
+    Ok(())
+}
+
+fn import_swf(args: &[String]) -> Result<(), String> {
+    let (file, options) = file_and_options(args)?;
+    let mut swf_options = SwfOptions::default();
+    let mut config = SchedulerConfig::default();
+    let mut out = None;
+    for (name, value) in options {
+        match name {
+            "time-unit" => swf_options.time_unit = parse(name, value)?,
+            "max-jobs" => swf_options.max_jobs = Some(parse(name, value)?),
+            "users" => swf_options.users = parse_list(name, value)?,
+            "queues" => swf_options.queues = parse_list(name, value)?,
+            "scale-by-processors" => swf_options.scale_by_processors = parse(name, value)?,
+            "algorithm" => config.algorithm = algorithm(value)?,
+            "quantum" => config.time_quantum = parse(name, value)?,
+            "out" => out = Some(value),
+            _ => return Err(format!("unknown option --{name}\n\n{USAGE}")),
+        }
+    }
+
+    let text = std::fs::read_to_string(file)
+        .map_err(|error| format!("cannot read {}: {error}", file.display()))?;
+    let workload = Workload {
+        jobs: swf::import_swf(&text, &swf_options)?,
+        config,
+    };
+    match out {
+        Some(path) => {
+            workload.save(Path::new(path))?;
+            eprintln!("Imported {} jobs into {path}", workload.jobs.len());
+            Ok(())
+        }
+        None => {
+            println!("{}", workload.to_json());
+            Ok(())
+        }
+    }
+}
+
+fn import_trace(args: &[String]) -> Result<(), String> {
+    let (file, options) = file_and_options(args)?;
+    let mut trace_options = TraceOptions::default();
+    let mut config = SchedulerConfig::default();
+    let mut compare = false;
+    let mut width = 100;
+    let mut out = None;
+    for (name, value) in options {
+        match name {
+            "time-unit-us" => trace_options.time_unit_us = parse(name, value)?,
+            "per-burst" => trace_options.per_burst = parse(name, value)?,
+            "comms" => trace_options.comms = value.split(',').map(str::to_string).collect(),
+            "max-jobs" => trace_options.max_jobs = Some(parse(name, value)?),
+            "algorithm" => config.algorithm = algorithm(value)?,
+            "quantum" => config.time_quantum = parse(name, value)?,
+            "compare" => compare = parse(name, value)?,
+            "width" => width = parse(name, value)?,
+            "out" => out = Some(value),
+            _ => return Err(format!("unknown option --{name}\n\n{USAGE}")),
+        }
+    }
+
+    let text = std::fs::read_to_string(file)
+        .map_err(|error| format!("cannot read {}: {error}", file.display()))?;
+    let imported = trace::import_trace(&text, &trace_options)?;
+    // replay on as many cores as the traced machine used
+    config.cores = imported.cpus;
+    let workload = Workload {
+        jobs: imported.jobs,
+        config,
+    };
+    if let Some(path) = out {
+        workload.save(Path::new(path))?;
+        eprintln!("Imported {} jobs into {path}", workload.jobs.len());
+    }
+
+    if compare {
+        let simulated = process_scheduler::simulate(&workload.jobs, &workload.config);
+        let makespan = |schedule: &process_scheduler::Schedule| {
+            schedule
+                .segments
+                .iter()
+                .map(|segment| segment.end)
+                .max()
+                .unwrap_or(0)
+        };
+        let makespan = makespan(&imported.observed).max(makespan(&simulated));
+        for (title, schedule) in [
+            ("Observed".to_string(), &imported.observed),
+            (workload.config.algorithm.name().to_string(), &simulated),
+        ] {
+            println!("{title}");
+            if let Some(gantt) = text_gantt(&schedule.segments, imported.cpus, makespan, width) {
+                for line in gantt.plain_lines(&schedule.segments) {
+                    println!("{line}");
+                }
+            }
+            let metrics = run_metrics(schedule, imported.cpus);
+            println!(
+                "Average turnaround {:.2}, waiting {:.2}, response {:.2}, makespan {}, {} context switches",
+                metrics.average_turnaround_time,
+                metrics.average_waiting_time,
+                metrics.average_response_time,
+                metrics.makespan,
+                metrics.context_switches
+            );
+            println!();
+        }
+    } else if out.is_none() {
+        println!("{}", workload.to_json());
+    }
+    Ok(())
+}
+
+fn simulate(args: &[String]) -> Result<(), String> {
+    let (file, options) = file_and_options(args)?;
+    let mut workload = Workload::load(file)?;
+    let mut starvation = Starvation::default();
+    for (name, value) in options {
+        match name {
+            "algorithm" => workload.config.algorithm = algorithm(value)?,
+            "quantum" => workload.config.time_quantum = parse(name, value)?,
+            "cores" => workload.config.cores = parse(name, value)?,
+            "context-switch" => workload.config.context_switch_cost = parse(name, value)?,
+            "tie-break" => {
+                workload.config.tie_breaking.rule = TieBreak::from_name(value.trim())
+                    .ok_or_else(|| format!("unknown tie-break rule `{value}`"))?
+            }
+            "preempted-first" => workload.config.tie_breaking.preempted_first = parse(name, value)?,
+            "aging-rate" => {
+                workload
+                    .config
+                    .aging
+                    .get_or_insert_with(Aging::default)
+                    .rate = parse(name, value)?
+            }
+            "aging-cap" => {
+                workload.config.aging.get_or_insert_with(Aging::default).cap = parse(name, value)?
+            }
+            "locking" => {
+                workload.config.locking = LockProtocol::from_name(value.trim())
+                    .ok_or_else(|| format!("unknown locking protocol `{value}`"))?
+            }
+            "dvfs" => {
+                workload
+                    .config
+                    .power
+                    .get_or_insert_with(PowerModel::default)
+                    .policy = DvfsPolicy::from_name(value.trim())
+                    .ok_or_else(|| format!("unknown DVFS policy `{value}`"))?
+            }
+            "memory-size" => {
+                workload
+                    .config
+                    .memory
+                    .get_or_insert_with(MemoryModel::default)
+                    .size = parse(name, value)?
+            }
+            "admission" => {
+                workload
+                    .config
+                    .memory
+                    .get_or_insert_with(MemoryModel::default)
+                    .admission = Admission::from_name(value.trim())
+                    .ok_or_else(|| format!("unknown admission policy `{value}`"))?
+            }
+            "swap-cost" => {
+                workload
+                    .config
+                    .memory
+                    .get_or_insert_with(MemoryModel::default)
+                    .swap_cost = Some(parse(name, value)?)
+            }
+            "multilevel" => {
+                workload
+                    .config
+                    .multilevel
+                    .get_or_insert_with(MultilevelQueues::default)
+                    .sharing = QueueSharing::from_name(value.trim())
+                    .ok_or_else(|| format!("unknown queue sharing `{value}`"))?
+            }
+            "slices" => {
+                let slices: Vec<u32> = parse_list(name, value)?;
+                if slices.len() != JobClass::ALL.len() {
+                    return Err(format!("--slices takes one slice per class, got `{value}`"));
+                }
+                let multilevel = workload
+                    .config
+                    .multilevel
+                    .get_or_insert_with(MultilevelQueues::default);
+                for (class, slice) in JobClass::ALL.into_iter().zip(slices) {
+                    multilevel.queue_mut(class).slice = slice;
+                }
+            }
+            "starve-wait" => starvation.max_wait = Some(parse(name, value)?),
+            "starve-multiple" => starvation.burst_multiple = Some(parse(name, value)?),
+            _ => return Err(format!("unknown option --{name}\n\n{USAGE}")),
+        }
+    }
+    if let Some(memory) = &workload.config.memory {
+        check_memory(&workload.jobs, memory)?;
+    }
+    if let Some(multilevel) = &workload.config.multilevel {
+        multilevel.check()?;
+    }
+    let locking = workload
+        .jobs
+        .iter()
+        .any(|job| !job.critical_sections.is_empty());
+
+    let schedule = process_scheduler::simulate(&workload.jobs, &workload.config);
+    match &workload.config.multilevel {
+        Some(multilevel) => println!("{}", multilevel.describe()),
+        None => println!("{}", workload.config.algorithm.name()),
+    }
+    println!(
+        "{}",
+        workload
+            .config
+            .tie_breaking
+            .describe(workload.config.algorithm)
+    );
+    if let Some(aging) = workload.config.aging {
+        println!("{}", aging.describe());
+    }
+    if locking {
+        println!("{}", workload.config.locking.name());
+    }
+    if let Some(memory) = &workload.config.memory {
+        println!("{}", memory.describe());
+    }
+    for segment in &schedule.segments {
+        println!(
+            "core {} {:>8} {:>8}  {}",
+            segment.core, segment.start, segment.end, segment.job_name
+        );
+    }
+    for hold in &schedule.holds {
+        let name = workload
+            .jobs
+            .iter()
+            .find(|job| job.id == hold.job_id)
+            .map_or("", |job| job.job_name.as_str());
+        println!(
+            "{:<6} {:>8} {:>8}  {name} holds {}",
+            "", hold.start, hold.end, hold.resource
+        );
+    }
+    println!();
+    println!(
+        "{:<12} {:>10} {:>10} {:>10} {:>10}",
+        "Job Name", "Completion", "Turnaround", "Waiting", "Response"
+    );
+    let admission_delays: Vec<(u32, u32)> = match workload.config.memory {
+        Some(_) => schedule
+            .jobs
+            .iter()
+            .map(|job| (job.id, admission_delay(&schedule, job)))
+            .collect(),
+        None => Vec::new(),
+    };
+    for job in job_metrics(&schedule) {
+        println!(
+            "{:<12} {:>10} {:>10} {:>10} {:>10}{}{}{}",
+            job.job_name,
+            job.completion_time,
+            job.turnaround_time,
+            job.waiting_time,
+            job.response_time,
+            if job.blocked_time > 0 {
+                format!("  blocked {}", job.blocked_time)
+            } else {
+                String::new()
+            },
+            match admission_delays.iter().find(|(id, _)| *id == job.job_id) {
+                Some((_, delay)) if *delay > 0 => format!("  admitted after {delay}"),
+                _ => String::new(),
+            },
+            if starvation.is_starved(&job) {
+                "  starved"
+            } else {
+                ""
+            }
+        );
+    }
+    let metrics = run_metrics(&schedule, workload.config.cores);
+    println!(
+        "{:<12} {:>10} {:>10.2} {:>10.2} {:>10.2}",
+        "Average",
+        "",
+        metrics.average_turnaround_time,
+        metrics.average_waiting_time,
+        metrics.average_response_time
+    );
+    println!(
+        "Makespan {}, CPU utilisation {:.1}%, {} context switches",
+        metrics.makespan,
+        metrics.cpu_utilisation * 100.0,
+        metrics.context_switches
+    );
+    if workload.jobs.iter().any(|job| !job.depends_on.is_empty()) {
+        println!(
+            "{}",
+            critical_path(&workload.jobs)?.describe(&workload.jobs)
+        );
+    }
+    if workload.config.multilevel.is_some() {
+        let ticks: Vec<String> = class_ticks(&schedule)
+            .iter()
+            .map(|(class, ticks)| format!("{} {ticks}", class.short_name()))
+            .collect();
+        println!("CPU ticks by class: {}", ticks.join(", "));
+    }
+    if let Some(memory) = &workload.config.memory {
+        let peak = memory_used(&schedule).into_iter().max().unwrap_or(0);
+        match memory.swap_cost {
+            Some(_) => println!(
+                "Peak memory {peak} of {}, {} swaps",
+                memory.size, schedule.swaps
+            ),
+            None => println!("Peak memory {peak} of {}", memory.size),
+        }
+    }
+    if let Some(power) = &workload.config.power {
+        if let Some(report) = energy_report(&schedule, power) {
+            println!("{}, {}", power.policy.name(), report.describe());
+        }
+        let missed = missed_deadlines(&schedule);
+        if !missed.is_empty() {
+            let names: Vec<&str> = schedule
+                .jobs
+                .iter()
+                .filter(|job| missed.contains(&job.id))
+                .map(|job| job.job_name.as_str())
+                .collect();
+            println!("Missed deadlines: {}", names.join(", "));
+        }
+    }
+    // a higher class preempts a lower one
+    let preemptive = workload.config.algorithm.preemptive() || workload.config.multilevel.is_some();
+    let optimum = match preemptive {
+        true => optimal::preemptive_optimum(&workload.jobs, workload.config.cores),
+        false => optimal::non_preemptive_optimum(&workload.jobs, workload.config.cores),
+    };
+    println!(
+        "Best average turnaround {}, {:+.1}% away",
+        optimum.describe(),
+        optimum.gap(metrics.average_turnaround_time) * 100.0
+    );
+    Ok(())
+}
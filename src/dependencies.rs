@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use crate::process_scheduler::Job;
+
+/// Positions in `jobs` of the jobs each job depends on. Ids no job has are left out,
+/// so deleting a job frees the jobs that were waiting for it.
+pub(crate) fn predecessors(jobs: &[Job]) -> Vec<Vec<usize>> {
+    let positions: HashMap<u32, usize> = jobs
+        .iter()
+        .enumerate()
+        .map(|(position, job)| (job.id, position))
+        .collect();
+    jobs.iter()
+        .map(|job| {
+            let mut predecessors: Vec<usize> = job
+                .depends_on
+                .iter()
+                .filter_map(|id| positions.get(id).copied())
+                .collect();
+            predecessors.sort_unstable();
+            predecessors.dedup();
+            predecessors
+        })
+        .collect()
+}
+
+/// Positions in `jobs` ordered so that every job comes after the jobs it depends on,
+/// or an error naming the jobs of a cycle.
+pub fn topological_order(jobs: &[Job]) -> Result<Vec<usize>, String> {
+    let predecessors = predecessors(jobs);
+    let mut waiting_on: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+    let mut successors = vec![Vec::new(); jobs.len()];
+    for (job, predecessors) in predecessors.iter().enumerate() {
+        for &predecessor in predecessors {
+            successors[predecessor].push(job);
+        }
+    }
+
+    let mut order: Vec<usize> = (0..jobs.len())
+        .filter(|&job| waiting_on[job] == 0)
+        .collect();
+    let mut next = 0;
+    while next < order.len() {
+        for &successor in &successors[order[next]] {
+            waiting_on[successor] -= 1;
+            if waiting_on[successor] == 0 {
+                order.push(successor);
+            }
+        }
+        next += 1;
+    }
+    if order.len() == jobs.len() {
+        return Ok(order);
+    }
+
+    // every job left over still waits on another left-over job, so following those
+    // back from any of them must come round to a job seen before
+    let mut path = vec![(0..jobs.len())
+        .find(|&job| waiting_on[job] > 0)
+        .expect("a job is left over")];
+    loop {
+        let last = *path.last().expect("path is not empty");
+        let previous = predecessors[last]
+            .iter()
+            .copied()
+            .find(|&predecessor| waiting_on[predecessor] > 0)
+            .expect("a left-over job waits on another");
+        if let Some(start) = path.iter().position(|&job| job == previous) {
+            let mut cycle: Vec<&str> = path[start..]
+                .iter()
+                .rev()
+                .map(|&job| jobs[job].job_name.as_str())
+                .collect();
+            cycle.push(cycle[0]);
+            return Err(format!(
+                "job dependencies form a cycle: {}",
+                cycle.join(" → ")
+            ));
+        }
+        path.push(previous);
+    }
+}
+
+/// The chain of dependent jobs that takes longest to get through.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CriticalPath {
+    /// When the last job of the chain could finish at the earliest, with a core for every
+    /// job and no time lost switching. No schedule can have a shorter makespan.
+    pub length: u32,
+    /// Ids of the jobs along it, first to last.
+    pub jobs: Vec<u32>,
+}
+
+impl CriticalPath {
+    pub fn describe(&self, jobs: &[Job]) -> String {
+        let names: Vec<&str> = self
+            .jobs
+            .iter()
+            .filter_map(|id| jobs.iter().find(|job| job.id == *id))
+            .map(|job| job.job_name.as_str())
+            .collect();
+        format!("Critical path {} ({})", self.length, names.join(" → "))
+    }
+}
+
+/// Each job finishes no earlier than its arrival or its last predecessor's finish, plus
+/// its own CPU cycles; the critical path ends at the job finishing last.
+pub fn critical_path(jobs: &[Job]) -> Result<CriticalPath, String> {
+    let order = topological_order(jobs)?;
+    let predecessors = predecessors(jobs);
+    let mut finish = vec![0; jobs.len()];
+    // the predecessor that held each job up longest
+    let mut longest: Vec<Option<usize>> = vec![None; jobs.len()];
+    for &job in &order {
+        let mut start = jobs[job].arrival_time;
+        for &predecessor in &predecessors[job] {
+            if finish[predecessor] > start {
+                start = finish[predecessor];
+                longest[job] = Some(predecessor);
+            }
+        }
+        finish[job] = start + jobs[job].needed_cpu_cycle;
+    }
+
+    let Some(last) = (0..jobs.len()).max_by_key(|&job| (finish[job], std::cmp::Reverse(job)))
+    else {
+        return Ok(CriticalPath::default());
+    };
+    let mut path = vec![jobs[last].id];
+    let mut job = last;
+    while let Some(predecessor) = longest[job] {
+        path.push(jobs[predecessor].id);
+        job = predecessor;
+    }
+    path.reverse();
+    Ok(CriticalPath {
+        length: finish[last],
+        jobs: path,
+    })
+}
+
+/// Whether `job` could be made to depend on `on` without forming a cycle, i.e. `on`
+/// is another job that does not already depend on `job`, directly or not.
+pub fn can_depend_on(jobs: &[Job], job: usize, on: usize) -> bool {
+    let predecessors = predecessors(jobs);
+    let mut stack = vec![on];
+    let mut seen = vec![false; jobs.len()];
+    while let Some(next) = stack.pop() {
+        if next == job {
+            return false;
+        }
+        if !std::mem::replace(&mut seen[next], true) {
+            stack.extend(&predecessors[next]);
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jobs(specs: &[(&str, u32, u32, &[u32])]) -> Vec<Job> {
+        specs
+            .iter()
+            .zip(1..)
+            .map(
+                |(&(name, arrival_time, needed_cpu_cycle, depends_on), id)| Job {
+                    id,
+                    job_name: name.to_string(),
+                    arrival_time,
+                    needed_cpu_cycle,
+                    depends_on: depends_on.to_vec(),
+                    ..Default::default()
+                },
+            )
+            .collect()
+    }
+
+    #[test]
+    fn critical_path_through_a_diamond() {
+        // A fans out to B and C, which both feed D; C is the slower branch
+        let jobs = jobs(&[
+            ("D", 0, 1, &[2, 3]),
+            ("B", 0, 2, &[4]),
+            ("C", 0, 5, &[4]),
+            ("A", 1, 3, &[]),
+        ]);
+        assert_eq!(topological_order(&jobs), Ok(vec![3, 1, 2, 0]));
+        let path = critical_path(&jobs).unwrap();
+        assert_eq!(
+            path,
+            CriticalPath {
+                length: 10,
+                jobs: vec![4, 3, 1]
+            }
+        );
+        assert_eq!(path.describe(&jobs), "Critical path 10 (A → C → D)");
+
+        // B already waits for C's predecessor A, but not for C
+        assert!(can_depend_on(&jobs, 1, 2));
+        assert!(!can_depend_on(&jobs, 3, 1));
+        assert!(!can_depend_on(&jobs, 0, 0));
+    }
+
+    #[test]
+    fn cycles_are_named() {
+        let jobs = jobs(&[("A", 0, 1, &[]), ("B", 0, 1, &[3]), ("C", 0, 1, &[2, 1])]);
+        assert_eq!(
+            topological_order(&jobs),
+            Err("job dependencies form a cycle: C → B → C".to_string())
+        );
+        let alone = self::jobs(&[("A", 0, 1, &[1, 9])]);
+        assert_eq!(
+            critical_path(&alone),
+            Err("job dependencies form a cycle: A → A".to_string())
+        );
+    }
+}
@@ -0,0 +1,394 @@
+use crate::locking::CriticalSection;
+use crate::process_scheduler::{Job, Schedule};
+
+/// A speed the cores can run at and the power a core draws there.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct FrequencyLevel {
+    pub mhz: u32,
+    /// Watts drawn while running a job, switching or waking up.
+    pub power: f64,
+}
+
+impl Default for FrequencyLevel {
+    fn default() -> Self {
+        Self {
+            mhz: 1000,
+            power: 1.0,
+        }
+    }
+}
+
+/// How the frequency of a run is picked.
+#[derive(
+    serde::Deserialize, serde::Serialize, Clone, Copy, Default, Debug, PartialEq, Eq, Hash,
+)]
+pub enum DvfsPolicy {
+    /// Run as fast as possible to get back to idle, and sleep, sooner.
+    #[default]
+    RaceToIdle,
+    /// Run as slowly as every job's deadline allows.
+    LowestFeasible,
+}
+
+impl DvfsPolicy {
+    pub const ALL: [DvfsPolicy; 2] = [DvfsPolicy::RaceToIdle, DvfsPolicy::LowestFeasible];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DvfsPolicy::RaceToIdle => "Race to idle",
+            DvfsPolicy::LowestFeasible => "Lowest feasible frequency",
+        }
+    }
+
+    /// Name used on the command line.
+    pub fn short_name(self) -> &'static str {
+        match self {
+            DvfsPolicy::RaceToIdle => "race-to-idle",
+            DvfsPolicy::LowestFeasible => "lowest-feasible",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<DvfsPolicy> {
+        DvfsPolicy::ALL
+            .into_iter()
+            .find(|policy| policy.name() == name || policy.short_name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// The power the cores draw in each state and the frequency they run at.
+///
+/// A job's CPU cycles are ticks at the fastest level, so at half that frequency its
+/// bursts and critical sections take twice as many ticks. Context switches and waking
+/// up take the same number of ticks at every level. A tick is taken to be a millisecond,
+/// so energy comes out in millijoules.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct PowerModel {
+    pub levels: Vec<FrequencyLevel>,
+    /// Watts an idle core draws while awake.
+    pub idle_power: f64,
+    /// Watts a sleeping core draws.
+    pub sleep_power: f64,
+    /// Idle ticks after which a core goes to sleep, at least 1.
+    pub sleep_after: u32,
+    /// Ticks a sleeping core takes to wake up when a job is dispatched to it.
+    pub wake_latency: u32,
+    pub policy: DvfsPolicy,
+}
+
+impl Default for PowerModel {
+    fn default() -> Self {
+        Self {
+            levels: [(600, 0.15), (1200, 0.45), (1800, 1.0), (2400, 2.0)]
+                .map(|(mhz, power)| FrequencyLevel { mhz, power })
+                .to_vec(),
+            idle_power: 0.1,
+            sleep_power: 0.01,
+            sleep_after: 2,
+            wake_latency: 1,
+            policy: DvfsPolicy::default(),
+        }
+    }
+}
+
+impl PowerModel {
+    /// Explains what is wrong with the model: no levels, a level of 0 MHz or a
+    /// negative power.
+    pub fn check(&self) -> Result<(), String> {
+        if self.levels.is_empty() {
+            return Err("the power model needs at least one frequency level".to_string());
+        }
+        if self.levels.iter().any(|level| level.mhz == 0) {
+            return Err("frequency levels must be above 0 MHz".to_string());
+        }
+        let powers = self.levels.iter().map(|level| level.power);
+        if powers
+            .chain([self.idle_power, self.sleep_power])
+            .any(|power| power < 0.0 || power.is_nan())
+        {
+            return Err("power draws must not be negative".to_string());
+        }
+        Ok(())
+    }
+
+    /// The usable levels, slowest first. A model without any runs at the default level.
+    pub fn sorted_levels(&self) -> Vec<FrequencyLevel> {
+        let mut levels: Vec<FrequencyLevel> = self
+            .levels
+            .iter()
+            .copied()
+            .filter(|level| level.mhz > 0)
+            .collect();
+        levels.sort_by_key(|level| level.mhz);
+        if levels.is_empty() {
+            levels.push(FrequencyLevel::default());
+        }
+        levels
+    }
+
+    pub fn fastest(&self) -> FrequencyLevel {
+        *self
+            .sorted_levels()
+            .last()
+            .expect("there is always a level")
+    }
+
+    /// Whether a core that has been idle for the `idle_ticks` ticks before this one is
+    /// asleep in this one.
+    pub fn asleep(&self, idle_ticks: u32) -> bool {
+        idle_ticks >= self.sleep_after.max(1)
+    }
+}
+
+/// Ticks `cycles` CPU cycles take at `level`, rounded up.
+pub fn scale(cycles: u32, level: FrequencyLevel, fastest: FrequencyLevel) -> u32 {
+    (u64::from(cycles) * u64::from(fastest.mhz))
+        .div_ceil(u64::from(level.mhz.max(1)))
+        .try_into()
+        .unwrap_or(u32::MAX)
+}
+
+/// `jobs` with their bursts and critical sections in ticks at `level`.
+pub fn scaled_jobs(jobs: &[Job], level: FrequencyLevel, fastest: FrequencyLevel) -> Vec<Job> {
+    jobs.iter()
+        .map(|job| Job {
+            needed_cpu_cycle: scale(job.needed_cpu_cycle, level, fastest),
+            critical_sections: job
+                .critical_sections
+                .iter()
+                .map(|section| {
+                    let offset = scale(section.offset, level, fastest);
+                    CriticalSection {
+                        offset,
+                        duration: scale(section.end(), level, fastest) - offset,
+                        ..section.clone()
+                    }
+                })
+                .collect(),
+            ..job.clone()
+        })
+        .collect()
+}
+
+/// Ids of the jobs that finished after their deadline, in completion order.
+pub fn missed_deadlines(schedule: &Schedule) -> Vec<u32> {
+    schedule
+        .jobs
+        .iter()
+        .filter(|job| {
+            job.deadline
+                .is_some_and(|deadline| job.completion_time > deadline)
+        })
+        .map(|job| job.id)
+        .collect()
+}
+
+/// Runs `simulate` at the level the policy picks. Lowest feasible tries the levels
+/// slowest first and falls back to the fastest when no level meets every deadline.
+pub(crate) fn choose_frequency(
+    power: &PowerModel,
+    simulate: impl Fn(FrequencyLevel) -> Schedule,
+) -> (FrequencyLevel, Schedule) {
+    let fastest = power.fastest();
+    if power.policy == DvfsPolicy::LowestFeasible {
+        for level in power.sorted_levels() {
+            let schedule = simulate(level);
+            if level == fastest || missed_deadlines(&schedule).is_empty() {
+                return (level, schedule);
+            }
+        }
+    }
+    (fastest, simulate(fastest))
+}
+
+/// What a core is doing when it is not running a job.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CoreState {
+    Switching,
+    Waking,
+    Idle,
+    Asleep,
+}
+
+impl CoreState {
+    pub fn name(self) -> &'static str {
+        match self {
+            CoreState::Switching => "Switching",
+            CoreState::Waking => "Waking",
+            CoreState::Idle => "Idle",
+            CoreState::Asleep => "Asleep",
+        }
+    }
+}
+
+/// A stretch of time a core spent in one state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CoreSpan {
+    pub core: u32,
+    pub state: CoreState,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Energy a run used and where it went.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EnergyReport {
+    pub frequency: FrequencyLevel,
+    /// Millijoules used by all cores up to the makespan.
+    pub energy: f64,
+    /// Watts drawn on average by all cores together.
+    pub average_power: f64,
+    /// Core ticks spent in each state, running jobs first.
+    pub running_ticks: u32,
+    pub switching_ticks: u32,
+    pub idle_ticks: u32,
+    pub asleep_ticks: u32,
+    pub wakeups: u32,
+}
+
+impl EnergyReport {
+    pub fn describe(&self) -> String {
+        format!(
+            "{} MHz, {:.2} mJ, average power {:.3} W, {} wakeup(s)",
+            self.frequency.mhz, self.energy, self.average_power, self.wakeups
+        )
+    }
+}
+
+/// Adds up the energy of a schedule simulated with `power`, or nothing if it was
+/// simulated without a power model.
+pub fn energy_report(schedule: &Schedule, power: &PowerModel) -> Option<EnergyReport> {
+    let frequency = schedule.frequency?;
+    let mut report = EnergyReport {
+        frequency,
+        running_ticks: schedule
+            .segments
+            .iter()
+            .map(|segment| segment.end - segment.start)
+            .sum(),
+        ..Default::default()
+    };
+    for span in &schedule.core_states {
+        let ticks = span.end - span.start;
+        match span.state {
+            CoreState::Switching => report.switching_ticks += ticks,
+            CoreState::Waking => {
+                report.switching_ticks += ticks;
+                report.wakeups += 1;
+            }
+            CoreState::Idle => report.idle_ticks += ticks,
+            CoreState::Asleep => report.asleep_ticks += ticks,
+        }
+    }
+    report.energy = f64::from(report.running_ticks + report.switching_ticks) * frequency.power
+        + f64::from(report.idle_ticks) * power.idle_power
+        + f64::from(report.asleep_ticks) * power.sleep_power;
+    let makespan = schedule
+        .jobs
+        .iter()
+        .map(|job| job.completion_time)
+        .max()
+        .unwrap_or(0);
+    if makespan > 0 {
+        report.average_power = report.energy / f64::from(makespan);
+    }
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_scheduler::{jobs_from, simulate, timeline, SchedulerConfig};
+
+    /// A runs 6 cycles from 0, B runs 3 from 10 and is due at `deadline`.
+    fn run(policy: DvfsPolicy, deadline: Option<u32>) -> (Schedule, EnergyReport) {
+        let mut jobs = jobs_from(&[("A", 0, 6), ("B", 10, 3)]);
+        jobs[1].deadline = deadline;
+        let power = PowerModel {
+            levels: [(500, 0.3), (750, 0.5), (1000, 1.0)]
+                .map(|(mhz, power)| FrequencyLevel { mhz, power })
+                .to_vec(),
+            idle_power: 0.1,
+            sleep_power: 0.01,
+            sleep_after: 2,
+            wake_latency: 1,
+            policy,
+        };
+        let config = SchedulerConfig {
+            power: Some(power.clone()),
+            ..Default::default()
+        };
+        let schedule = simulate(&jobs, &config);
+        let report = energy_report(&schedule, &power).unwrap();
+        (schedule, report)
+    }
+
+    #[test]
+    fn racing_to_idle_sleeps_in_between() {
+        let (schedule, report) = run(DvfsPolicy::RaceToIdle, None);
+        assert_eq!(timeline(&schedule), [("A", 0, 6), ("B", 11, 14)]);
+        let states: Vec<(CoreState, u32, u32)> = schedule
+            .core_states
+            .iter()
+            .map(|span| (span.state, span.start, span.end))
+            .collect();
+        assert_eq!(
+            states,
+            [
+                (CoreState::Idle, 6, 8),
+                (CoreState::Asleep, 8, 10),
+                (CoreState::Waking, 10, 11)
+            ]
+        );
+        assert_eq!(report.wakeups, 1);
+        assert!((report.energy - 10.22).abs() < 1e-9);
+        assert!((report.average_power - 10.22 / 14.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lowest_feasible_frequency_meets_deadlines() {
+        // without deadlines the slowest level is always feasible
+        let (schedule, report) = run(DvfsPolicy::LowestFeasible, None);
+        assert_eq!(report.frequency.mhz, 500);
+        assert_eq!(timeline(&schedule), [("A", 0, 12), ("B", 12, 18)]);
+        assert!((report.energy - 5.4).abs() < 1e-9);
+
+        // B would finish at 18 at 500 MHz, at 750 A takes 8 ticks and the core has not
+        // fallen asleep yet when B arrives
+        let (schedule, report) = run(DvfsPolicy::LowestFeasible, Some(16));
+        assert_eq!(report.frequency.mhz, 750);
+        assert_eq!(timeline(&schedule), [("A", 0, 8), ("B", 10, 14)]);
+        assert_eq!(report.wakeups, 0);
+        assert!(missed_deadlines(&schedule).is_empty());
+
+        // no level gets B done by 12, so the fastest is used and B is late
+        let (schedule, report) = run(DvfsPolicy::LowestFeasible, Some(12));
+        assert_eq!(report.frequency.mhz, 1000);
+        assert_eq!(missed_deadlines(&schedule), [2]);
+    }
+
+    #[test]
+    fn models_are_checked() {
+        let mut power = PowerModel::default();
+        assert_eq!(power.check(), Ok(()));
+        assert_eq!(power.fastest().mhz, 2400);
+        power.levels[0].mhz = 0;
+        assert!(power.check().is_err());
+        assert_eq!(power.sorted_levels().len(), 3);
+        power.levels.clear();
+        assert!(power.check().is_err());
+        assert_eq!(power.fastest(), FrequencyLevel::default());
+        assert_eq!(
+            scale(
+                3,
+                FrequencyLevel {
+                    mhz: 750,
+                    power: 0.0
+                },
+                FrequencyLevel::default()
+            ),
+            4
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use crate::process_scheduler::Segment;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GanttCell {
+    Idle,
+    /// First column of the segment at this index, where its label goes.
+    Start(usize),
+    Continue(usize),
+}
+
+/// A schedule laid out in character columns for the terminal front ends.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextGantt {
+    /// One row per core.
+    pub rows: Vec<Vec<GanttCell>>,
+    /// Start and end of every segment where they fit, aligned with the columns.
+    pub axis: String,
+}
+
+/// Lays out `segments` in at most `width` columns covering `0..makespan`.
+/// Charts drawn with the same makespan and width share a time scale.
+pub fn text_gantt(
+    segments: &[Segment],
+    cores: u32,
+    makespan: u32,
+    width: usize,
+) -> Option<TextGantt> {
+    let makespan = makespan as usize;
+    if makespan == 0 || width == 0 {
+        return None;
+    }
+    // Columns per tick, or a fraction of a tick when the schedule is wider than the screen
+    let columns = if makespan <= width {
+        makespan * (width / makespan)
+    } else {
+        width
+    };
+    let time_at = |column: usize| (column * makespan / columns) as u32;
+
+    let rows = (0..cores)
+        .map(|core| {
+            let mut previous = None;
+            (0..columns)
+                .map(|column| {
+                    let time = time_at(column);
+                    let segment = segments.iter().position(|segment| {
+                        segment.core == core && segment.start <= time && time < segment.end
+                    });
+                    let cell = match segment {
+                        Some(index) if previous == Some(index) => GanttCell::Continue(index),
+                        Some(index) => GanttCell::Start(index),
+                        None => GanttCell::Idle,
+                    };
+                    previous = segment;
+                    cell
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut axis = vec![' '; columns + 12];
+    let mut boundaries: Vec<u32> = segments
+        .iter()
+        .flat_map(|segment| [segment.start, segment.end])
+        .collect();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    let mut free_from = 0;
+    for time in boundaries {
+        let column = time as usize * columns / makespan;
+        let label = time.to_string();
+        if column >= free_from && column + label.len() <= axis.len() {
+            for (offset, c) in label.chars().enumerate() {
+                axis[column + offset] = c;
+            }
+            free_from = column + label.len() + 1;
+        }
+    }
+
+    Some(TextGantt {
+        rows,
+        axis: axis.into_iter().collect::<String>().trim_end().to_string(),
+    })
+}
+
+impl TextGantt {
+    /// Uncoloured lines: the first letter of each job where its segment starts, `=` while it runs.
+    pub fn plain_lines(&self, segments: &[Segment]) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(core, row)| {
+                let cells: String = row
+                    .iter()
+                    .map(|cell| match *cell {
+                        GanttCell::Idle => '.',
+                        GanttCell::Start(index) => {
+                            segments[index].job_name.chars().next().unwrap_or('?')
+                        }
+                        GanttCell::Continue(_) => '=',
+                    })
+                    .collect();
+                format!("core {core:<3}|{cells}|")
+            })
+            .collect();
+        lines.push(format!("         {}", self.axis));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_scale() {
+        let segments = vec![
+            Segment {
+                job_id: 1,
+                job_name: "A".to_string(),
+                core: 0,
+                start: 0,
+                end: 2,
+            },
+            Segment {
+                job_id: 2,
+                job_name: "B".to_string(),
+                core: 0,
+                start: 3,
+                end: 4,
+            },
+        ];
+        let gantt = text_gantt(&segments, 1, 5, 10).unwrap();
+        assert_eq!(
+            gantt.plain_lines(&segments),
+            ["core 0  |A===..B=..|", "         0   2 3 4"]
+        );
+        assert!(text_gantt(&segments, 1, 0, 10).is_none());
+    }
+}
@@ -0,0 +1,371 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::process_scheduler::Job;
+
+/// How arrival times are spread out.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+pub enum ArrivalProcess {
+    /// Every job arrives at a uniformly random time in `0..=max_arrival`.
+    Uniform { max_arrival: u32 },
+    /// Exponential gaps between arrivals, `rate` jobs per tick on average. The first job arrives at 0.
+    Poisson { rate: f64 },
+    /// Jobs arrive together in groups of `group_size`, with exponential gaps of mean `mean_gap` between groups.
+    Bursty { group_size: u32, mean_gap: f64 },
+}
+
+/// How many CPU cycles a job needs.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+pub enum BurstDistribution {
+    Uniform {
+        min: u32,
+        max: u32,
+    },
+    Exponential {
+        mean: f64,
+    },
+    /// `long` with probability `long_fraction`, otherwise `short`.
+    Bimodal {
+        short: u32,
+        long: u32,
+        long_fraction: f64,
+    },
+    /// Heavy tailed, never below `scale`. Smaller `shape` means a heavier tail.
+    Pareto {
+        scale: f64,
+        shape: f64,
+    },
+    /// `exp(mu + sigma * z)` for a standard normal `z`.
+    LogNormal {
+        mu: f64,
+        sigma: f64,
+    },
+}
+
+/// Seeded random workloads, the same seed and settings always give the same jobs.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct WorkloadGenerator {
+    pub seed: u64,
+    pub arrivals: ArrivalProcess,
+    pub bursts: BurstDistribution,
+}
+
+impl Default for WorkloadGenerator {
+    /// Same ranges `randomize_jobs` always used: arrivals in 0..10 and bursts in 1..10.
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            arrivals: ArrivalProcess::Uniform { max_arrival: 9 },
+            bursts: BurstDistribution::Uniform { min: 1, max: 9 },
+        }
+    }
+}
+
+impl WorkloadGenerator {
+    /// Gives `jobs` new arrival times and CPU cycles, keeping their names and order.
+    pub fn generate(&self, jobs: Vec<Job>) -> Vec<Job> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut jobs = jobs;
+        let mut clock = 0.0;
+
+        for (i, job) in jobs.iter_mut().enumerate() {
+            job.arrival_time = match self.arrivals {
+                ArrivalProcess::Uniform { max_arrival } => rng.gen_range(0..=max_arrival),
+                ArrivalProcess::Poisson { rate } => {
+                    if i > 0 {
+                        clock += exponential(&mut rng, 1.0 / rate);
+                    }
+                    to_ticks(clock, 0)
+                }
+                ArrivalProcess::Bursty {
+                    group_size,
+                    mean_gap,
+                } => {
+                    if i > 0 && i % group_size.max(1) as usize == 0 {
+                        clock += exponential(&mut rng, mean_gap);
+                    }
+                    to_ticks(clock, 0)
+                }
+            };
+            job.needed_cpu_cycle = self.bursts.sample(&mut rng);
+            job.remaining_cpu_cycle = job.needed_cpu_cycle;
+        }
+        jobs
+    }
+}
+
+impl BurstDistribution {
+    fn sample(&self, rng: &mut impl Rng) -> u32 {
+        let cycles = match *self {
+            BurstDistribution::Uniform { min, max } => {
+                rng.gen_range(min.min(max)..=max.max(min)) as f64
+            }
+            BurstDistribution::Exponential { mean } => exponential(rng, mean),
+            BurstDistribution::Bimodal {
+                short,
+                long,
+                long_fraction,
+            } => {
+                // clamp keeps NaN, which gen_bool panics on
+                let long_fraction = match long_fraction.is_nan() {
+                    true => 0.0,
+                    false => long_fraction.clamp(0.0, 1.0),
+                };
+                match rng.gen_bool(long_fraction) {
+                    true => long as f64,
+                    false => short as f64,
+                }
+            }
+            BurstDistribution::Pareto { scale, shape } => {
+                scale / (1.0 - rng.gen::<f64>()).powf(1.0 / shape)
+            }
+            BurstDistribution::LogNormal { mu, sigma } => (mu + sigma * standard_normal(rng)).exp(),
+        };
+        to_ticks(cycles, 1)
+    }
+}
+
+fn exponential(rng: &mut impl Rng, mean: f64) -> f64 {
+    -mean * (1.0 - rng.gen::<f64>()).ln()
+}
+
+/// Box-Muller transform.
+fn standard_normal(rng: &mut impl Rng) -> f64 {
+    let u1 = 1.0 - rng.gen::<f64>(); // (0, 1] so the log is finite
+    let u2 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Rounds to whole ticks within what the job table accepts.
+fn to_ticks(value: f64, min: u32) -> u32 {
+    if value.is_nan() {
+        return min;
+    }
+    value.round().clamp(min as f64, u16::MAX as f64) as u32
+}
+
+// Text form used on the command line, e.g. `poisson:0.5` or `pareto:2,1.5`
+
+impl fmt::Display for ArrivalProcess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArrivalProcess::Uniform { max_arrival } => write!(f, "uniform:{max_arrival}"),
+            ArrivalProcess::Poisson { rate } => write!(f, "poisson:{rate}"),
+            ArrivalProcess::Bursty {
+                group_size,
+                mean_gap,
+            } => write!(f, "bursty:{group_size},{mean_gap}"),
+        }
+    }
+}
+
+impl FromStr for ArrivalProcess {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (kind, parameters) = split_spec(text)?;
+        match (kind, parameters.as_slice()) {
+            ("uniform", [max_arrival]) => Ok(ArrivalProcess::Uniform {
+                max_arrival: parse_parameter(text, max_arrival)?,
+            }),
+            ("poisson", [rate]) => {
+                let rate = parse_parameter(text, rate)?;
+                match rate > 0.0 {
+                    true => Ok(ArrivalProcess::Poisson { rate }),
+                    false => Err(format!("`{text}`: the rate must be positive")),
+                }
+            }
+            ("bursty", [group_size, mean_gap]) => Ok(ArrivalProcess::Bursty {
+                group_size: parse_parameter(text, group_size)?,
+                mean_gap: parse_parameter(text, mean_gap)?,
+            }),
+            _ => Err(format!(
+                "unknown arrival process `{text}`, expected uniform:MAX, poisson:RATE or bursty:GROUP_SIZE,MEAN_GAP"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for BurstDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BurstDistribution::Uniform { min, max } => write!(f, "uniform:{min},{max}"),
+            BurstDistribution::Exponential { mean } => write!(f, "exponential:{mean}"),
+            BurstDistribution::Bimodal {
+                short,
+                long,
+                long_fraction,
+            } => write!(f, "bimodal:{short},{long},{long_fraction}"),
+            BurstDistribution::Pareto { scale, shape } => write!(f, "pareto:{scale},{shape}"),
+            BurstDistribution::LogNormal { mu, sigma } => write!(f, "lognormal:{mu},{sigma}"),
+        }
+    }
+}
+
+impl FromStr for BurstDistribution {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (kind, parameters) = split_spec(text)?;
+        match (kind, parameters.as_slice()) {
+            ("uniform", [min, max]) => Ok(BurstDistribution::Uniform {
+                min: parse_parameter(text, min)?,
+                max: parse_parameter(text, max)?,
+            }),
+            ("exponential", [mean]) => Ok(BurstDistribution::Exponential {
+                mean: parse_parameter(text, mean)?,
+            }),
+            ("bimodal", [short, long, long_fraction]) => {
+                let long_fraction = parse_parameter(text, long_fraction)?;
+                match (0.0..=1.0).contains(&long_fraction) {
+                    true => Ok(BurstDistribution::Bimodal {
+                        short: parse_parameter(text, short)?,
+                        long: parse_parameter(text, long)?,
+                        long_fraction,
+                    }),
+                    false => Err(format!(
+                        "`{text}`: the long fraction must be between 0 and 1"
+                    )),
+                }
+            }
+            ("pareto", [scale, shape]) => {
+                let shape = parse_parameter(text, shape)?;
+                match shape > 0.0 {
+                    true => Ok(BurstDistribution::Pareto {
+                        scale: parse_parameter(text, scale)?,
+                        shape,
+                    }),
+                    false => Err(format!("`{text}`: the shape must be positive")),
+                }
+            }
+            ("lognormal", [mu, sigma]) => Ok(BurstDistribution::LogNormal {
+                mu: parse_parameter(text, mu)?,
+                sigma: parse_parameter(text, sigma)?,
+            }),
+            _ => Err(format!(
+                "unknown burst distribution `{text}`, expected uniform:MIN,MAX, exponential:MEAN, bimodal:SHORT,LONG,LONG_FRACTION, pareto:SCALE,SHAPE or lognormal:MU,SIGMA"
+            )),
+        }
+    }
+}
+
+fn split_spec(text: &str) -> Result<(&str, Vec<&str>), String> {
+    let (kind, parameters) = text
+        .split_once(':')
+        .ok_or_else(|| format!("`{text}` is missing its parameters, e.g. poisson:0.5"))?;
+    Ok((kind.trim(), parameters.split(',').map(str::trim).collect()))
+}
+
+fn parse_parameter<T: FromStr>(text: &str, parameter: &str) -> Result<T, String> {
+    parameter
+        .parse()
+        .map_err(|_| format!("invalid parameter `{parameter}` in `{text}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_scheduler::job_builder;
+
+    #[test]
+    fn same_seed_same_workload() {
+        let generator = WorkloadGenerator {
+            seed: 42,
+            arrivals: ArrivalProcess::Poisson { rate: 0.5 },
+            bursts: BurstDistribution::LogNormal {
+                mu: 1.5,
+                sigma: 0.8,
+            },
+        };
+        let jobs = generator.generate(job_builder(&[], 20));
+        assert_eq!(jobs, generator.generate(job_builder(&[], 20)));
+        assert_ne!(
+            jobs,
+            WorkloadGenerator {
+                seed: 43,
+                ..generator
+            }
+            .generate(job_builder(&[], 20))
+        );
+        assert_eq!(jobs[0].arrival_time, 0);
+        assert!(jobs
+            .windows(2)
+            .all(|pair| pair[0].arrival_time <= pair[1].arrival_time));
+    }
+
+    #[test]
+    fn bursty_arrivals_come_in_groups() {
+        let generator = WorkloadGenerator {
+            arrivals: ArrivalProcess::Bursty {
+                group_size: 3,
+                mean_gap: 20.0,
+            },
+            ..Default::default()
+        };
+        let jobs = generator.generate(job_builder(&[], 6));
+        assert!(jobs[..3].iter().all(|job| job.arrival_time == 0));
+        assert!(jobs[3..]
+            .iter()
+            .all(|job| job.arrival_time == jobs[3].arrival_time));
+    }
+
+    #[test]
+    fn exponential_mean() {
+        let generator = WorkloadGenerator {
+            bursts: BurstDistribution::Exponential { mean: 20.0 },
+            ..Default::default()
+        };
+        let jobs = generator.generate(job_builder(&[], 10_000));
+        let mean = jobs
+            .iter()
+            .map(|job| job.needed_cpu_cycle as f64)
+            .sum::<f64>()
+            / 10_000.0;
+        assert!((mean - 20.0).abs() < 1.0, "mean was {mean}");
+    }
+
+    #[test]
+    fn specs_round_trip() {
+        for spec in ["uniform:9", "poisson:0.25", "bursty:4,12.5"] {
+            assert_eq!(spec.parse::<ArrivalProcess>().unwrap().to_string(), spec);
+        }
+        for spec in [
+            "uniform:1,9",
+            "exponential:5",
+            "bimodal:2,20,0.1",
+            "pareto:1,1.5",
+            "lognormal:1,0.5",
+        ] {
+            assert_eq!(spec.parse::<BurstDistribution>().unwrap().to_string(), spec);
+        }
+        assert!("poisson:0".parse::<ArrivalProcess>().is_err());
+        assert!("gamma:1,2".parse::<BurstDistribution>().is_err());
+    }
+
+    #[test]
+    fn long_fraction_must_be_a_probability() {
+        for spec in [
+            "bimodal:1,9,NaN",
+            "bimodal:1,9,inf",
+            "bimodal:1,9,1.5",
+            "bimodal:1,9,-0.1",
+        ] {
+            assert_eq!(
+                spec.parse::<BurstDistribution>(),
+                Err(format!(
+                    "`{spec}`: the long fraction must be between 0 and 1"
+                ))
+            );
+        }
+        // one built in code rather than parsed still samples
+        let bimodal = BurstDistribution::Bimodal {
+            short: 1,
+            long: 9,
+            long_fraction: f64::NAN,
+        };
+        assert_eq!(bimodal.sample(&mut StdRng::seed_from_u64(0)), 1);
+    }
+}
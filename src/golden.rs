@@ -0,0 +1,217 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::energy::{energy_report, missed_deadlines};
+use crate::metrics::{job_metrics, run_metrics};
+use crate::multilevel::class_ticks;
+use crate::process_scheduler::{simulate, Algorithm, SchedulerConfig};
+use crate::workload::Workload;
+
+/// Timeline and metrics of `workload` under every algorithm, as kept in its golden file.
+/// The algorithm stored in the workload is ignored, the rest of its config applies.
+/// Multilevel queues take the place of the algorithm, so they are run once.
+pub fn render(workload: &Workload) -> String {
+    let mut golden = String::new();
+    let algorithms = match workload.config.multilevel {
+        Some(_) => vec![workload.config.algorithm],
+        None => Algorithm::ALL.to_vec(),
+    };
+    for algorithm in algorithms {
+        let config = SchedulerConfig {
+            algorithm,
+            ..workload.config.clone()
+        };
+        let schedule = simulate(&workload.jobs, &config);
+        // writing to a String cannot fail
+        let _ = match &config.multilevel {
+            Some(multilevel) => writeln!(golden, "== {} ==", multilevel.describe()),
+            None => writeln!(golden, "== {} ==", algorithm.short_name()),
+        };
+        for segment in &schedule.segments {
+            let _ = writeln!(
+                golden,
+                "core {} {:>4} {:>4}  {}",
+                segment.core, segment.start, segment.end, segment.job_name
+            );
+        }
+        for hold in &schedule.holds {
+            let _ = writeln!(
+                golden,
+                "hold {} {:>4} {:>4}  job {}",
+                hold.resource, hold.start, hold.end, hold.job_id
+            );
+        }
+        for residency in &schedule.memory {
+            let _ = writeln!(
+                golden,
+                "memory {:>4} {:>4} {:>4}  job {}",
+                residency.memory, residency.start, residency.end, residency.job_id
+            );
+        }
+        if config
+            .memory
+            .as_ref()
+            .is_some_and(|memory| memory.swap_cost.is_some())
+        {
+            let _ = writeln!(golden, "swaps {}", schedule.swaps);
+        }
+        for job in job_metrics(&schedule) {
+            let _ = write!(
+                golden,
+                "job {}: completion {} turnaround {} waiting {} response {}",
+                job.job_name,
+                job.completion_time,
+                job.turnaround_time,
+                job.waiting_time,
+                job.response_time
+            );
+            // only jobs with critical sections can block, and older golden files have none
+            if job.blocked_time > 0 {
+                let _ = write!(golden, " blocked {}", job.blocked_time);
+            }
+            golden.push('\n');
+        }
+        if let Some(report) = config
+            .power
+            .as_ref()
+            .and_then(|power| energy_report(&schedule, power))
+        {
+            let _ = writeln!(golden, "energy {}", report.describe());
+            let _ = writeln!(golden, "missed deadlines {:?}", missed_deadlines(&schedule));
+        }
+        if config.multilevel.is_some() {
+            for (class, ticks) in class_ticks(&schedule) {
+                let _ = writeln!(golden, "class {} ticks {ticks}", class.short_name());
+            }
+        }
+        for (name, value) in run_metrics(&schedule, config.cores).values() {
+            let _ = writeln!(golden, "{name} {value:.3}");
+        }
+        golden.push('\n');
+    }
+    golden
+}
+
+/// The golden file kept next to a workload file.
+pub fn golden_path(workload: &Path) -> PathBuf {
+    workload.with_extension("golden")
+}
+
+/// Workload files in `dir`, by name.
+fn workload_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|error| format!("cannot read {}: {error}", dir.display()))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// The first line where `actual` departs from `expected`.
+fn first_difference(expected: &str, actual: &str) -> String {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    for line in 1.. {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(expected), Some(actual)) if expected == actual => {}
+            (expected, actual) => {
+                return format!(
+                    "line {line}: expected `{}`, got `{}`",
+                    expected.unwrap_or("end of file"),
+                    actual.unwrap_or("end of file")
+                )
+            }
+        }
+    }
+    unreachable!("the lines differ somewhere")
+}
+
+/// Runs every workload file in `dir` and compares it with its golden file,
+/// returning one message per workload that does not match.
+pub fn check(dir: &Path) -> Result<Vec<String>, String> {
+    let mut failures = Vec::new();
+    for path in workload_files(dir)? {
+        let actual = render(&Workload::load(&path)?);
+        let golden = golden_path(&path);
+        match std::fs::read_to_string(&golden) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!(
+                "{}: {}",
+                golden.display(),
+                first_difference(&expected, &actual)
+            )),
+            Err(error) => failures.push(format!("cannot read {}: {error}", golden.display())),
+        }
+    }
+    Ok(failures)
+}
+
+/// Rewrites the golden file of every workload file in `dir`, returning the ones that changed.
+pub fn update(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut changed = Vec::new();
+    for path in workload_files(dir)? {
+        let actual = render(&Workload::load(&path)?);
+        let golden = golden_path(&path);
+        if std::fs::read_to_string(&golden).ok().as_deref() != Some(actual.as_str()) {
+            std::fs::write(&golden, actual)
+                .map_err(|error| format!("cannot write {}: {error}", golden.display()))?;
+            changed.push(golden);
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::textbook_scenarios;
+
+    fn golden_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+    }
+
+    #[test]
+    fn goldens_match() {
+        let failures = check(&golden_dir()).unwrap();
+        assert!(
+            failures.is_empty(),
+            "{}\n\nIf the new behaviour is intended, regenerate the goldens with\n    \
+            cargo run -- golden tests/golden --update true",
+            failures.join("\n")
+        );
+    }
+
+    #[test]
+    fn mismatches_are_reported_and_updated() {
+        let dir = std::env::temp_dir().join(format!("golden-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let workload = textbook_scenarios()
+            .into_iter()
+            .find(|scenario| scenario.name == "McHoes & Flynn: SRT")
+            .unwrap()
+            .workload;
+        let path = dir.join("srt.json");
+        workload.save(&path).unwrap();
+
+        assert!(check(&dir).unwrap()[0].contains("cannot read"));
+        assert_eq!(update(&dir).unwrap(), [golden_path(&path)]);
+        assert!(check(&dir).unwrap().is_empty());
+        assert!(update(&dir).unwrap().is_empty());
+
+        let golden = std::fs::read_to_string(golden_path(&path)).unwrap();
+        std::fs::write(
+            golden_path(&path),
+            golden.replacen("core 0    0    1  A", "core 0    0    2  A", 1),
+        )
+        .unwrap();
+        let failures = check(&dir).unwrap();
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].ends_with("expected `core 0    0    2  A`, got `core 0    0    1  A`"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
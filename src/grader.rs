@@ -0,0 +1,729 @@
+use std::collections::VecDeque;
+
+use crate::process_scheduler::{ensure_unique_ids, Algorithm, Job, SchedulerConfig};
+use crate::workload::Workload;
+
+/// Tie-break choices explored when only metrics are claimed.
+const MAX_SCHEDULES: usize = 1000;
+
+/// A schedule someone worked out, a timeline, per job metrics or both.
+#[derive(serde::Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Claim {
+    pub segments: Vec<ClaimedSegment>,
+    pub jobs: Vec<ClaimedJob>,
+}
+
+/// Jobs are named by their name, or by their id when names repeat.
+#[derive(serde::Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ClaimedSegment {
+    #[serde(deserialize_with = "job_key")]
+    pub job: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+#[derive(serde::Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ClaimedJob {
+    #[serde(deserialize_with = "job_key")]
+    pub job: String,
+    pub completion_time: Option<u32>,
+    pub turnaround_time: Option<u32>,
+    pub waiting_time: Option<u32>,
+    pub response_time: Option<u32>,
+}
+
+/// Accepts `"A"` as well as `3` for a job.
+fn job_key<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Key {
+        Name(String),
+        Id(u32),
+    }
+    Ok(match serde::Deserialize::deserialize(deserializer)? {
+        Key::Name(name) => name,
+        Key::Id(id) => id.to_string(),
+    })
+}
+
+const METRICS: [&str; 4] = [
+    "completion_time",
+    "turnaround_time",
+    "waiting_time",
+    "response_time",
+];
+
+impl ClaimedJob {
+    fn values(&self) -> [Option<u32>; 4] {
+        [
+            self.completion_time,
+            self.turnaround_time,
+            self.waiting_time,
+            self.response_time,
+        ]
+    }
+}
+
+impl Claim {
+    /// JSON when the text starts with `{`, CSV otherwise.
+    pub fn parse(text: &str) -> Result<Claim, String> {
+        if text.trim_start().starts_with('{') {
+            serde_json::from_str(text).map_err(|error| format!("invalid claim: {error}"))
+        } else {
+            Claim::from_csv(text)
+        }
+    }
+
+    /// Comma separated rows under a header naming the columns: `job`, `start` and `end`
+    /// for the timeline, the metric names for per job results. A blank line may start
+    /// another table with its own header.
+    pub fn from_csv(text: &str) -> Result<Claim, String> {
+        let mut claim = Claim::default();
+        let mut header: Option<Vec<String>> = None;
+        for (line_number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                header = None;
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let Some(columns) = &header else {
+                let columns: Vec<String> =
+                    fields.iter().map(|field| field.to_lowercase()).collect();
+                if !columns.iter().any(|column| column == "job") {
+                    return Err(format!(
+                        "line {}: header has no `job` column",
+                        line_number + 1
+                    ));
+                }
+                header = Some(columns);
+                continue;
+            };
+
+            let mut job = String::new();
+            let mut start = None;
+            let mut end = None;
+            let mut claimed = ClaimedJob::default();
+            for (column, field) in columns.iter().zip(&fields) {
+                if field.is_empty() {
+                    continue;
+                }
+                if column == "job" {
+                    job = field.to_string();
+                    continue;
+                }
+                let value = || {
+                    field.parse::<u32>().map(Some).map_err(|_| {
+                        format!("line {}: `{field}` is not a whole number", line_number + 1)
+                    })
+                };
+                match column.as_str() {
+                    "start" => start = value()?,
+                    "end" => end = value()?,
+                    "completion_time" => claimed.completion_time = value()?,
+                    "turnaround_time" => claimed.turnaround_time = value()?,
+                    "waiting_time" => claimed.waiting_time = value()?,
+                    "response_time" => claimed.response_time = value()?,
+                    _ => {}
+                }
+            }
+            if let (Some(start), Some(end)) = (start, end) {
+                claim.segments.push(ClaimedSegment {
+                    job: job.clone(),
+                    start,
+                    end,
+                });
+            }
+            if claimed.values().iter().any(Option::is_some) {
+                claimed.job = job;
+                claim.jobs.push(claimed);
+            }
+        }
+        Ok(claim)
+    }
+}
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct SegmentCheck {
+    pub job: String,
+    pub start: u32,
+    pub end: u32,
+    pub correct: bool,
+}
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct MetricCheck {
+    pub metric: &'static str,
+    pub claimed: u32,
+    pub expected: u32,
+    pub correct: bool,
+}
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct JobCheck {
+    pub job: String,
+    pub metrics: Vec<MetricCheck>,
+}
+
+/// How a claim compares with the schedule the algorithm allows.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct Report {
+    pub algorithm: String,
+    /// Share of claimed segments and metrics that are right, between 0 and 1.
+    /// Claimed segments past the right number count as wrong.
+    pub score: f64,
+    /// The claimed timeline, back to back segments of one job joined.
+    pub segments: Vec<SegmentCheck>,
+    /// Segments of the expected schedule the timeline does not have.
+    pub missing_segments: Vec<SegmentCheck>,
+    pub jobs: Vec<JobCheck>,
+    /// The first scheduling decision the timeline gets wrong, overlapping segments and the like.
+    pub issues: Vec<String>,
+    /// The schedule the claim was checked against. Where the algorithm leaves a tie
+    /// open it follows the claim, so it is the right schedule closest to the claim.
+    pub expected: Vec<SegmentCheck>,
+}
+
+/// Where a timeline first does something the algorithm would not.
+#[derive(Clone, Debug)]
+struct Deviation {
+    time: u32,
+    allowed: Vec<usize>,
+    claimed: Option<usize>,
+}
+
+/// Steps through a single core schedule the way [`simulate`](crate::process_scheduler::simulate)
+/// does, but lets the caller choose among jobs the algorithm ranks equally.
+#[derive(Clone)]
+struct Replay<'a> {
+    jobs: &'a [Job],
+    config: &'a SchedulerConfig,
+    time: u32,
+    arrived: usize,
+    remaining: Vec<u32>,
+    completion: Vec<Option<u32>>,
+    running: Option<usize>,
+    quantum_used: u32,
+    // Round Robin queue, jobs that joined it in the same tick may run in any order
+    groups: VecDeque<Vec<usize>>,
+    segments: Vec<(usize, u32, u32)>,
+    deviation: Option<Deviation>,
+}
+
+impl<'a> Replay<'a> {
+    fn new(jobs: &'a [Job], config: &'a SchedulerConfig) -> Self {
+        Self {
+            jobs,
+            config,
+            time: 0,
+            arrived: 0,
+            remaining: jobs.iter().map(|job| job.needed_cpu_cycle).collect(),
+            completion: vec![None; jobs.len()],
+            running: None,
+            quantum_used: 0,
+            groups: VecDeque::new(),
+            segments: Vec::new(),
+            deviation: None,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.completion.iter().all(Option::is_some)
+    }
+
+    /// Queues the jobs arriving now and takes the core from a Round Robin job whose quantum is up.
+    fn arrive(&mut self) {
+        let mut group = Vec::new();
+        while self.arrived < self.jobs.len() && self.jobs[self.arrived].arrival_time <= self.time {
+            if self.jobs[self.arrived].needed_cpu_cycle == 0 {
+                self.completion[self.arrived] = Some(self.jobs[self.arrived].arrival_time);
+            } else {
+                group.push(self.arrived);
+            }
+            self.arrived += 1;
+        }
+        if self.config.algorithm == Algorithm::RoundRobin {
+            if let Some(running) = self.running {
+                if self.quantum_used >= self.config.time_quantum.max(1) {
+                    if group.is_empty() && self.groups.is_empty() {
+                        self.quantum_used = 0;
+                    } else {
+                        // textbooks differ on whether it goes before or after the new arrivals
+                        group.push(running);
+                        self.running = None;
+                    }
+                }
+            }
+        }
+        if !group.is_empty() {
+            self.groups.push_back(group);
+        }
+    }
+
+    /// Jobs the algorithm may run in the next tick, the one `simulate` picks first.
+    fn allowed(&self) -> Vec<usize> {
+        let ready = (0..self.arrived).filter(|&job| self.remaining[job] > 0);
+        let ties = |key: &dyn Fn(usize) -> u32| {
+            let min = ready.clone().map(key).min();
+            ready.clone().filter(|&job| Some(key(job)) == min).collect()
+        };
+        match (self.config.algorithm, self.running) {
+            (Algorithm::Srt, running) => {
+                let remaining = |job: usize| self.remaining[job];
+                let ties: Vec<usize> = ties(&remaining);
+                // a running job is not preempted by one needing just as long
+                match running {
+                    Some(running) if ties.contains(&running) => vec![running],
+                    _ => ties,
+                }
+            }
+            (_, Some(running)) => vec![running],
+            (Algorithm::Fcfs, None) => ties(&|job| self.jobs[job].arrival_time),
+            (Algorithm::Sjn, None) => ties(&|job| self.jobs[job].needed_cpu_cycle),
+            (Algorithm::Random, None) => ready.collect(),
+            (Algorithm::RoundRobin, None) => self.groups.front().cloned().unwrap_or_default(),
+        }
+    }
+
+    fn run(&mut self, job: usize) {
+        if self.running != Some(job) {
+            for group in &mut self.groups {
+                group.retain(|&queued| queued != job);
+            }
+            self.groups.retain(|group| !group.is_empty());
+            self.running = Some(job);
+            self.quantum_used = 0;
+        }
+        match self.segments.last_mut() {
+            Some((last, _, end)) if *last == job && *end == self.time => *end += 1,
+            _ => self.segments.push((job, self.time, self.time + 1)),
+        }
+        self.remaining[job] -= 1;
+        self.quantum_used += 1;
+        self.time += 1;
+        if self.remaining[job] == 0 {
+            self.completion[job] = Some(self.time);
+            self.running = None;
+        }
+    }
+
+    /// Waits for the next arrival.
+    fn idle(&mut self) {
+        self.running = None;
+        self.time = self.jobs[self.arrived].arrival_time;
+    }
+
+    /// Completion, turnaround, waiting and response time of `job`.
+    fn metrics(&self, job: usize) -> [u32; 4] {
+        let arrival_time = self.jobs[job].arrival_time;
+        let completion_time = self.completion[job].unwrap_or(arrival_time);
+        let turnaround_time = completion_time - arrival_time;
+        let first_start = self
+            .segments
+            .iter()
+            .find(|(segment_job, _, _)| *segment_job == job)
+            .map_or(completion_time, |(_, start, _)| *start);
+        [
+            completion_time,
+            turnaround_time,
+            turnaround_time.saturating_sub(self.jobs[job].needed_cpu_cycle),
+            first_start - arrival_time,
+        ]
+    }
+}
+
+/// Finds the job a claim means by `key`.
+fn resolve(jobs: &[Job], key: &str) -> Result<usize, String> {
+    let named: Vec<usize> = (0..jobs.len())
+        .filter(|&job| jobs[job].job_name == key)
+        .collect();
+    match named[..] {
+        [job] => Ok(job),
+        [] => key
+            .trim_start_matches('#')
+            .parse::<u32>()
+            .ok()
+            .and_then(|id| jobs.iter().position(|job| job.id == id))
+            .ok_or_else(|| format!("there is no job `{key}`")),
+        _ => Err(format!("more than one job is called `{key}`, use its id")),
+    }
+}
+
+/// Checks `claim` against the algorithm in `workload`'s config.
+///
+/// Where the algorithm ranks jobs equally, e.g. two jobs arriving together under FCFS
+/// or needing the same time under SJN, any of them may go first. Only single core
+/// schedules without context switch cost are checked, and none using aging, job
+/// dependencies, critical sections, a power or memory model or multilevel queues.
+pub fn grade(workload: &Workload, claim: &Claim) -> Result<Report, String> {
+    let config = &workload.config;
+    if config.cores > 1 || config.context_switch_cost > 0 {
+        return Err(
+            "only single core schedules without context switch cost can be graded".to_string(),
+        );
+    }
+    let jobs_use = |uses: fn(&Job) -> bool| workload.jobs.iter().any(uses);
+    let unsupported = [
+        (config.aging.is_some(), "aging"),
+        (
+            jobs_use(|job| !job.depends_on.is_empty()),
+            "job dependencies",
+        ),
+        (
+            jobs_use(|job| !job.critical_sections.is_empty()),
+            "critical sections",
+        ),
+        (config.power.is_some(), "a power model"),
+        (config.memory.is_some(), "a memory model"),
+        (config.multilevel.is_some(), "multilevel queues"),
+    ];
+    if let Some((_, feature)) = unsupported.into_iter().find(|&(used, _)| used) {
+        return Err(format!("schedules with {feature} cannot be graded"));
+    }
+    let mut jobs = workload.jobs.clone();
+    ensure_unique_ids(&mut jobs);
+    jobs.sort_by_key(|job| job.arrival_time);
+    let name = |job: usize| jobs[job].job_name.clone();
+
+    let mut issues = Vec::new();
+    let mut timeline: Vec<(usize, u32, u32)> = Vec::new();
+    let mut claimed_segments = claim.segments.clone();
+    claimed_segments.sort_by_key(|segment| segment.start);
+    for segment in &claimed_segments {
+        if segment.end <= segment.start {
+            issues.push(format!(
+                "segment {} {}-{} does not run for any time",
+                segment.job, segment.start, segment.end
+            ));
+            continue;
+        }
+        let job = resolve(&jobs, &segment.job)?;
+        match timeline.last_mut() {
+            Some((last, _, end)) if *last == job && *end == segment.start => *end = segment.end,
+            Some((last, start, end)) if *end > segment.start => {
+                issues.push(format!(
+                    "segments {} {start}-{end} and {} {}-{} overlap",
+                    name(*last),
+                    segment.job,
+                    segment.start,
+                    segment.end
+                ));
+            }
+            _ => timeline.push((job, segment.start, segment.end)),
+        }
+    }
+    let claimed_jobs = claim
+        .jobs
+        .iter()
+        .map(|claimed| Ok((resolve(&jobs, &claimed.job)?, claimed.values())))
+        .collect::<Result<Vec<_>, String>>()?;
+    let claimed_at = |time: u32| {
+        timeline
+            .iter()
+            .find(|(_, start, end)| *start <= time && time < *end)
+            .map(|(job, _, _)| *job)
+    };
+    let matching_metrics = |replay: &Replay<'_>| {
+        claimed_jobs
+            .iter()
+            .map(|(job, values)| {
+                let expected = replay.metrics(*job);
+                (0..METRICS.len())
+                    .filter(|&metric| values[metric] == Some(expected[metric]))
+                    .count()
+            })
+            .sum::<usize>()
+    };
+
+    // A timeline settles every tie, without one the ties are searched for the best match
+    let mut best: Option<(usize, Replay<'_>)> = None;
+    let mut pending = vec![Replay::new(&jobs, config)];
+    let mut budget = MAX_SCHEDULES;
+    while let Some(mut replay) = pending.pop() {
+        loop {
+            replay.arrive();
+            if replay.is_finished() {
+                break;
+            }
+            let allowed = replay.allowed();
+            if allowed.is_empty() {
+                replay.idle();
+                continue;
+            }
+            let choice = if !timeline.is_empty() {
+                let claimed = claimed_at(replay.time);
+                match claimed {
+                    Some(job) if replay.deviation.is_none() && allowed.contains(&job) => job,
+                    _ => {
+                        if replay.deviation.is_none() {
+                            replay.deviation = Some(Deviation {
+                                time: replay.time,
+                                allowed: allowed.clone(),
+                                claimed,
+                            });
+                        }
+                        allowed[0]
+                    }
+                }
+            } else {
+                if allowed.len() > 1 && budget >= allowed.len() - 1 {
+                    budget -= allowed.len() - 1;
+                    for &job in &allowed[1..] {
+                        let mut branch = replay.clone();
+                        branch.run(job);
+                        pending.push(branch);
+                    }
+                }
+                allowed[0]
+            };
+            replay.run(choice);
+        }
+        let matching = matching_metrics(&replay);
+        if best.as_ref().map_or(true, |(most, _)| matching > *most) {
+            best = Some((matching, replay));
+        }
+    }
+    let (correct_metrics, replay) = best.expect("there is always one schedule");
+
+    if let Some(deviation) = &replay.deviation {
+        let allowed: Vec<String> = deviation.allowed.iter().map(|&job| name(job)).collect();
+        issues.insert(
+            0,
+            format!(
+                "at {} {} runs {}, the timeline {}",
+                deviation.time,
+                config.algorithm.short_name().to_uppercase(),
+                allowed.join(" or "),
+                deviation
+                    .claimed
+                    .map_or("leaves the CPU idle".to_string(), |job| format!(
+                        "runs {}",
+                        name(job)
+                    ))
+            ),
+        );
+    }
+
+    let check = |&(job, start, end): &(usize, u32, u32), correct| SegmentCheck {
+        job: name(job),
+        start,
+        end,
+        correct,
+    };
+    let segments: Vec<SegmentCheck> = timeline
+        .iter()
+        .map(|segment| check(segment, replay.segments.contains(segment)))
+        .collect();
+    let missing_segments = match timeline.is_empty() {
+        true => Vec::new(),
+        false => replay
+            .segments
+            .iter()
+            .filter(|segment| !timeline.contains(segment))
+            .map(|segment| check(segment, false))
+            .collect(),
+    };
+    let job_checks = claimed_jobs
+        .iter()
+        .map(|(job, values)| {
+            let expected = replay.metrics(*job);
+            JobCheck {
+                job: name(*job),
+                metrics: (0..METRICS.len())
+                    .filter_map(|metric| {
+                        values[metric].map(|claimed| MetricCheck {
+                            metric: METRICS[metric],
+                            claimed,
+                            expected: expected[metric],
+                            correct: claimed == expected[metric],
+                        })
+                    })
+                    .collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let correct_segments = segments.iter().filter(|segment| segment.correct).count();
+    let segment_total = match timeline.is_empty() {
+        true => 0,
+        false => timeline.len().max(replay.segments.len()),
+    };
+    let metric_total: usize = job_checks.iter().map(|job| job.metrics.len()).sum();
+    let total = segment_total + metric_total;
+    Ok(Report {
+        algorithm: config.algorithm.short_name().to_string(),
+        score: match total {
+            0 => 0.0,
+            total => (correct_segments + correct_metrics) as f64 / total as f64,
+        },
+        segments,
+        missing_segments,
+        jobs: job_checks,
+        issues,
+        expected: replay
+            .segments
+            .iter()
+            .map(|segment| check(segment, true))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::WorkloadGenerator;
+    use crate::locking::CriticalSection;
+    use crate::process_scheduler::{job_builder, simulate, Aging, Schedule};
+
+    fn workload(algorithm: Algorithm, jobs: &[(u32, u32)]) -> Workload {
+        let mut workload = Workload {
+            jobs: job_builder(&[], jobs.len() as u32),
+            config: SchedulerConfig {
+                algorithm,
+                time_quantum: 2,
+                ..Default::default()
+            },
+        };
+        for (job, &(arrival_time, needed_cpu_cycle)) in workload.jobs.iter_mut().zip(jobs) {
+            job.arrival_time = arrival_time;
+            job.needed_cpu_cycle = needed_cpu_cycle;
+        }
+        workload
+    }
+
+    #[test]
+    fn accepts_any_tie_break() {
+        // A and B arrive together and need the same time
+        let fcfs = workload(Algorithm::Fcfs, &[(0, 3), (0, 3), (1, 1)]);
+        let claim = Claim::parse("job,start,end\nB,0,3\nA,3,6\nC,6,7").unwrap();
+        let report = grade(&fcfs, &claim).unwrap();
+        assert_eq!(report.score, 1.0);
+        assert!(report.issues.is_empty());
+
+        let claim = Claim::parse("job,start,end\nB,0,3\nC,3,4\nA,4,7").unwrap();
+        let report = grade(&fcfs, &claim).unwrap();
+        assert_eq!(report.issues, ["at 3 FCFS runs A, the timeline runs C"]);
+        assert_eq!(
+            report
+                .segments
+                .iter()
+                .map(|segment| segment.correct)
+                .collect::<Vec<_>>(),
+            [true, false, false]
+        );
+        assert_eq!(report.missing_segments.len(), 2);
+
+        // only metrics: C may finish at 7 whichever of A and B goes first
+        let sjn = workload(Algorithm::Sjn, &[(0, 3), (0, 3), (1, 1)]);
+        let claim = Claim::parse(
+            r#"{"jobs": [{"job": "A", "completion_time": 7, "waiting_time": 4},
+                         {"job": 3, "completion_time": 4, "response_time": 2}]}"#,
+        )
+        .unwrap();
+        let report = grade(&sjn, &claim).unwrap();
+        assert_eq!(report.score, 1.0);
+        assert_eq!(report.expected[0].job, "B");
+    }
+
+    #[test]
+    fn simulated_schedules_pass() {
+        for seed in 0..20 {
+            let jobs = WorkloadGenerator {
+                seed,
+                ..Default::default()
+            }
+            .generate(job_builder(&[], 6));
+            for algorithm in Algorithm::ALL {
+                let workload = Workload {
+                    jobs: jobs.clone(),
+                    config: SchedulerConfig {
+                        algorithm,
+                        time_quantum: 3,
+                        seed,
+                        ..Default::default()
+                    },
+                };
+                let schedule = simulate(&workload.jobs, &workload.config);
+                let report = grade(&workload, &claim(&schedule)).unwrap();
+                assert_eq!(report.score, 1.0, "{algorithm:?} seed {seed}: {report:?}");
+            }
+        }
+    }
+
+    /// Everything `schedule` did, as a claim.
+    fn claim(schedule: &Schedule) -> Claim {
+        Claim {
+            segments: schedule
+                .segments
+                .iter()
+                .map(|segment| ClaimedSegment {
+                    job: segment.job_id.to_string(),
+                    start: segment.start,
+                    end: segment.end,
+                })
+                .collect(),
+            jobs: schedule
+                .jobs
+                .iter()
+                .map(|job| ClaimedJob {
+                    job: job.job_name.clone(),
+                    completion_time: Some(job.completion_time),
+                    turnaround_time: Some(job.turnaround_time),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn unmodelled_features_are_rejected() {
+        let plain = workload(Algorithm::Srt, &[(0, 6), (1, 3), (2, 1)]);
+        let mut dependent = plain.clone();
+        dependent.jobs[2].depends_on = vec![dependent.jobs[0].id];
+        let mut locking = plain.clone();
+        for job in &mut locking.jobs {
+            job.critical_sections = vec![CriticalSection {
+                resource: "R".to_string(),
+                offset: 0,
+                duration: 1,
+            }];
+        }
+        let with = |change: fn(&mut SchedulerConfig)| {
+            let mut workload = plain.clone();
+            change(&mut workload.config);
+            workload
+        };
+        let workloads = [
+            (
+                "aging",
+                with(|config| config.aging = Some(Aging::default())),
+            ),
+            ("job dependencies", dependent),
+            ("critical sections", locking),
+            (
+                "a power model",
+                with(|config| config.power = Some(Default::default())),
+            ),
+            (
+                "a memory model",
+                with(|config| config.memory = Some(Default::default())),
+            ),
+            (
+                "multilevel queues",
+                with(|config| config.multilevel = Some(Default::default())),
+            ),
+        ];
+        for (feature, workload) in workloads {
+            // even the simulator's own schedule would be graded against the plain one
+            let schedule = simulate(&workload.jobs, &workload.config);
+            assert_eq!(
+                grade(&workload, &claim(&schedule)),
+                Err(format!("schedules with {feature} cannot be graded"))
+            );
+        }
+        let schedule = simulate(&plain.jobs, &plain.config);
+        assert_eq!(grade(&plain, &claim(&schedule)).unwrap().score, 1.0);
+    }
+}
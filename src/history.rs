@@ -0,0 +1,585 @@
+use crate::energy::PowerModel;
+use crate::locking::{CriticalSection, LockProtocol};
+use crate::memory::MemoryModel;
+use crate::multilevel::{JobClass, MultilevelQueues};
+use crate::process_scheduler::{Aging, Algorithm, Job, TieBreaking};
+use crate::workload::Workload;
+
+/// One undoable change to a workload.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Edit {
+    CpuCycle {
+        job: usize,
+        from: u32,
+        to: u32,
+    },
+    ArrivalTime {
+        job: usize,
+        from: u32,
+        to: u32,
+    },
+    Swap {
+        a: usize,
+        b: usize,
+    },
+    Rename {
+        job: usize,
+        from: String,
+        to: String,
+    },
+    /// The ids a job depends on.
+    Dependencies {
+        job: usize,
+        from: Vec<u32>,
+        to: Vec<u32>,
+    },
+    Deadline {
+        job: usize,
+        from: Option<u32>,
+        to: Option<u32>,
+    },
+    CriticalSections {
+        job: usize,
+        from: Vec<CriticalSection>,
+        to: Vec<CriticalSection>,
+    },
+    /// The memory a job needs.
+    Memory {
+        job: usize,
+        from: u32,
+        to: u32,
+    },
+    Class {
+        job: usize,
+        from: JobClass,
+        to: JobClass,
+    },
+    Algorithm {
+        from: Algorithm,
+        to: Algorithm,
+    },
+    TimeQuantum {
+        from: u32,
+        to: u32,
+    },
+    Cores {
+        from: u32,
+        to: u32,
+    },
+    ContextSwitchCost {
+        from: u32,
+        to: u32,
+    },
+    TieBreaking {
+        from: TieBreaking,
+        to: TieBreaking,
+    },
+    Aging {
+        from: Option<Aging>,
+        to: Option<Aging>,
+    },
+    Locking {
+        from: LockProtocol,
+        to: LockProtocol,
+    },
+    Power {
+        from: Option<PowerModel>,
+        to: Option<PowerModel>,
+    },
+    MemoryModel {
+        from: Option<MemoryModel>,
+        to: Option<MemoryModel>,
+    },
+    Multilevel {
+        from: Option<MultilevelQueues>,
+        to: Option<MultilevelQueues>,
+    },
+    /// Changing the number of jobs, keeping the jobs that were dropped so undo brings them back.
+    Resize {
+        from: Vec<Job>,
+        to: Vec<Job>,
+    },
+    /// Anything that changes many jobs at once: resizing, randomizing, loading a scenario.
+    Replace {
+        description: String,
+        from: Box<Workload>,
+        to: Box<Workload>,
+    },
+}
+
+impl Edit {
+    pub fn apply(&self, workload: &mut Workload) {
+        self.set(workload, false);
+    }
+
+    pub fn revert(&self, workload: &mut Workload) {
+        self.set(workload, true);
+    }
+
+    fn set(&self, workload: &mut Workload, revert: bool) {
+        fn pick<T>(revert: bool, from: T, to: T) -> T {
+            if revert {
+                from
+            } else {
+                to
+            }
+        }
+        match self {
+            Edit::CpuCycle { job, from, to } => {
+                if let Some(job) = workload.jobs.get_mut(*job) {
+                    job.needed_cpu_cycle = pick(revert, *from, *to);
+                    job.remaining_cpu_cycle = job.needed_cpu_cycle;
+                }
+            }
+            Edit::ArrivalTime { job, from, to } => {
+                if let Some(job) = workload.jobs.get_mut(*job) {
+                    job.arrival_time = pick(revert, *from, *to);
+                }
+            }
+            Edit::Swap { a, b } => {
+                if *a < workload.jobs.len() && *b < workload.jobs.len() {
+                    workload.jobs.swap(*a, *b);
+                }
+            }
+            Edit::Rename { job, from, to } => {
+                if let Some(job) = workload.jobs.get_mut(*job) {
+                    job.job_name = pick(revert, from, to).clone();
+                }
+            }
+            Edit::Dependencies { job, from, to } => {
+                if let Some(job) = workload.jobs.get_mut(*job) {
+                    job.depends_on = pick(revert, from, to).clone();
+                }
+            }
+            Edit::Deadline { job, from, to } => {
+                if let Some(job) = workload.jobs.get_mut(*job) {
+                    job.deadline = pick(revert, *from, *to);
+                }
+            }
+            Edit::CriticalSections { job, from, to } => {
+                if let Some(job) = workload.jobs.get_mut(*job) {
+                    job.critical_sections = pick(revert, from, to).clone();
+                }
+            }
+            Edit::Memory { job, from, to } => {
+                if let Some(job) = workload.jobs.get_mut(*job) {
+                    job.memory = pick(revert, *from, *to);
+                }
+            }
+            Edit::Class { job, from, to } => {
+                if let Some(job) = workload.jobs.get_mut(*job) {
+                    job.class = pick(revert, *from, *to);
+                }
+            }
+            Edit::Algorithm { from, to } => workload.config.algorithm = pick(revert, *from, *to),
+            Edit::TimeQuantum { from, to } => {
+                workload.config.time_quantum = pick(revert, *from, *to)
+            }
+            Edit::Cores { from, to } => workload.config.cores = pick(revert, *from, *to),
+            Edit::ContextSwitchCost { from, to } => {
+                workload.config.context_switch_cost = pick(revert, *from, *to)
+            }
+            Edit::TieBreaking { from, to } => {
+                workload.config.tie_breaking = pick(revert, *from, *to)
+            }
+            Edit::Aging { from, to } => workload.config.aging = pick(revert, *from, *to),
+            Edit::Locking { from, to } => workload.config.locking = pick(revert, *from, *to),
+            Edit::Power { from, to } => workload.config.power = pick(revert, from, to).clone(),
+            Edit::MemoryModel { from, to } => {
+                workload.config.memory = pick(revert, from, to).clone()
+            }
+            Edit::Multilevel { from, to } => {
+                workload.config.multilevel = pick(revert, from, to).clone()
+            }
+            Edit::Resize { from, to } => workload.jobs = pick(revert, from, to).clone(),
+            Edit::Replace { from, to, .. } => *workload = pick(revert, from, to).as_ref().clone(),
+        }
+    }
+
+    /// What the history list shows, job names are looked up in `workload` as it is right after the edit.
+    pub fn description(&self, workload: &Workload) -> String {
+        let name = |job: usize| {
+            workload
+                .jobs
+                .get(job)
+                .map_or_else(|| format!("#{}", job + 1), |job| job.job_name.clone())
+        };
+        match self {
+            Edit::CpuCycle { job, from, to } => {
+                format!("Job {} CPU cycle {from} → {to}", name(*job))
+            }
+            Edit::ArrivalTime { job, from, to } => {
+                format!("Job {} arrival time {from} → {to}", name(*job))
+            }
+            Edit::Swap { a, b } => format!("Swap jobs {} and {}", name(*a), name(*b)),
+            Edit::Rename { from, to, .. } => format!("Rename job {from} → {to}"),
+            Edit::Dependencies { job, to, .. } => {
+                let names: Vec<String> = to
+                    .iter()
+                    .map(|id| {
+                        workload
+                            .jobs
+                            .iter()
+                            .find(|job| job.id == *id)
+                            .map_or_else(|| format!("#{id}"), |job| job.job_name.clone())
+                    })
+                    .collect();
+                match names.is_empty() {
+                    true => format!("Job {} depends on nothing", name(*job)),
+                    false => format!("Job {} depends on {}", name(*job), names.join(", ")),
+                }
+            }
+            Edit::Deadline { job, to, .. } => match to {
+                Some(deadline) => format!("Job {} due by {deadline}", name(*job)),
+                None => format!("Job {} has no deadline", name(*job)),
+            },
+            Edit::CriticalSections { job, to, .. } => {
+                let sections: Vec<String> = to
+                    .iter()
+                    .map(|section| {
+                        format!("{} {}–{}", section.resource, section.offset, section.end())
+                    })
+                    .collect();
+                match sections.is_empty() {
+                    true => format!("Job {} has no critical sections", name(*job)),
+                    false => format!(
+                        "Job {} critical sections {}",
+                        name(*job),
+                        sections.join(", ")
+                    ),
+                }
+            }
+            Edit::Algorithm { from, to } => {
+                format!("Algorithm {} → {}", from.short_name(), to.short_name())
+            }
+            Edit::TimeQuantum { from, to } => format!("Time quantum {from} → {to}"),
+            Edit::Cores { from, to } => format!("Cores {from} → {to}"),
+            Edit::ContextSwitchCost { from, to } => format!("Context switch cost {from} → {to}"),
+            Edit::TieBreaking { to, .. } => to.describe(workload.config.algorithm),
+            Edit::Aging { to, .. } => to.map_or("Aging off".to_string(), |aging| aging.describe()),
+            Edit::Locking { to, .. } => to.name().to_string(),
+            Edit::Power { to, .. } => to.as_ref().map_or("Energy model off".to_string(), |power| {
+                format!("Energy model, {}", power.policy.name())
+            }),
+            Edit::Memory { job, from, to } => {
+                format!("Job {} memory {from} → {to}", name(*job))
+            }
+            Edit::MemoryModel { to, .. } => to
+                .as_ref()
+                .map_or("Memory model off".to_string(), MemoryModel::describe),
+            Edit::Class { job, to, .. } => format!("Job {} class {}", name(*job), to.name()),
+            Edit::Multilevel { to, .. } => to.as_ref().map_or(
+                "Multilevel queues off".to_string(),
+                MultilevelQueues::describe,
+            ),
+            Edit::Resize { from, to } => format!("Number of jobs {} → {}", from.len(), to.len()),
+            Edit::Replace { description, .. } => description.clone(),
+        }
+    }
+
+    /// Folds `next` into this edit when both change the same value, so one drag is one step.
+    fn merge(&mut self, next: &Edit) -> bool {
+        match (self, next) {
+            (
+                Edit::CpuCycle { job, to, .. },
+                Edit::CpuCycle {
+                    job: next_job,
+                    to: next_to,
+                    ..
+                },
+            )
+            | (
+                Edit::ArrivalTime { job, to, .. },
+                Edit::ArrivalTime {
+                    job: next_job,
+                    to: next_to,
+                    ..
+                },
+            )
+            | (
+                Edit::Memory { job, to, .. },
+                Edit::Memory {
+                    job: next_job,
+                    to: next_to,
+                    ..
+                },
+            ) if job == next_job => *to = *next_to,
+            (Edit::TimeQuantum { to, .. }, Edit::TimeQuantum { to: next_to, .. })
+            | (Edit::Cores { to, .. }, Edit::Cores { to: next_to, .. })
+            | (Edit::ContextSwitchCost { to, .. }, Edit::ContextSwitchCost { to: next_to, .. }) => {
+                *to = *next_to
+            }
+            (
+                Edit::Deadline {
+                    job, to: Some(to), ..
+                },
+                Edit::Deadline {
+                    job: next_job,
+                    to: Some(next_to),
+                    ..
+                },
+            ) if job == next_job => *to = *next_to,
+            (
+                Edit::Power { to: Some(to), .. },
+                Edit::Power {
+                    to: Some(next_to), ..
+                },
+            ) if to.levels.len() == next_to.levels.len() => *to = next_to.clone(),
+            (
+                Edit::MemoryModel { to: Some(to), .. },
+                Edit::MemoryModel {
+                    to: Some(next_to), ..
+                },
+            ) => *to = next_to.clone(),
+            (
+                Edit::Multilevel { to: Some(to), .. },
+                Edit::Multilevel {
+                    to: Some(next_to), ..
+                },
+            ) => *to = next_to.clone(),
+            (
+                Edit::Aging { to: Some(to), .. },
+                Edit::Aging {
+                    to: Some(next_to), ..
+                },
+            ) => *to = *next_to,
+            (Edit::Resize { to, .. }, Edit::Resize { to: next_to, .. }) => *to = next_to.clone(),
+            (
+                Edit::Rename { job, to, .. },
+                Edit::Rename {
+                    job: next_job,
+                    to: next_to,
+                    ..
+                },
+            ) if job == next_job => *to = next_to.clone(),
+            (
+                Edit::CriticalSections { job, to, .. },
+                Edit::CriticalSections {
+                    job: next_job,
+                    to: next_to,
+                    ..
+                },
+            ) if job == next_job && to.len() == next_to.len() => *to = next_to.clone(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn is_noop(&self) -> bool {
+        match self {
+            Edit::CpuCycle { from, to, .. }
+            | Edit::ArrivalTime { from, to, .. }
+            | Edit::Memory { from, to, .. }
+            | Edit::TimeQuantum { from, to }
+            | Edit::Cores { from, to }
+            | Edit::ContextSwitchCost { from, to } => from == to,
+            Edit::Swap { a, b } => a == b,
+            Edit::Rename { from, to, .. } => from == to,
+            Edit::Dependencies { from, to, .. } => from == to,
+            Edit::CriticalSections { from, to, .. } => from == to,
+            Edit::Locking { from, to } => from == to,
+            Edit::Deadline { from, to, .. } => from == to,
+            Edit::Power { from, to } => from == to,
+            Edit::MemoryModel { from, to } => from == to,
+            Edit::Class { from, to, .. } => from == to,
+            Edit::Multilevel { from, to } => from == to,
+            Edit::Algorithm { from, to } => from == to,
+            Edit::TieBreaking { from, to } => from == to,
+            Edit::Aging { from, to } => from == to,
+            Edit::Resize { from, to } => from == to,
+            Edit::Replace { from, to, .. } => from == to,
+        }
+    }
+}
+
+/// An edit and how it was described when it was made.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    pub edit: Edit,
+    pub description: String,
+}
+
+/// Undo and redo stacks of edits.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct History {
+    done: Vec<Step>,
+    undone: Vec<Step>,
+    // while false, edits of the same value are merged into the last one
+    sealed: bool,
+}
+
+impl History {
+    /// Records an edit that has already been made to `workload`.
+    pub fn push(&mut self, edit: Edit, workload: &Workload) {
+        self.undone.clear();
+        let merged = !self.sealed
+            && self
+                .done
+                .last_mut()
+                .is_some_and(|last| last.edit.merge(&edit));
+        if !merged {
+            self.done.push(Step {
+                edit,
+                description: String::new(),
+            });
+        }
+        self.sealed = false;
+        let last = self.done.last_mut().expect("an edit was just pushed");
+        if last.edit.is_noop() {
+            self.done.pop();
+        } else {
+            last.description = last.edit.description(workload);
+        }
+    }
+
+    /// Ends the current edit, the next change of the same value becomes its own step.
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    pub fn undo(&mut self, workload: &mut Workload) -> bool {
+        let Some(step) = self.done.pop() else {
+            return false;
+        };
+        step.edit.revert(workload);
+        self.undone.push(step);
+        self.sealed = true;
+        true
+    }
+
+    pub fn redo(&mut self, workload: &mut Workload) -> bool {
+        let Some(step) = self.undone.pop() else {
+            return false;
+        };
+        step.edit.apply(workload);
+        self.done.push(step);
+        self.sealed = true;
+        true
+    }
+
+    /// Undoes or redoes until `position` edits are done.
+    pub fn go_to(&mut self, position: usize, workload: &mut Workload) {
+        while self.done.len() > position && self.undo(workload) {}
+        while self.done.len() < position && self.redo(workload) {}
+    }
+
+    /// Edits that can be undone, oldest first.
+    pub fn done(&self) -> &[Step] {
+        &self.done
+    }
+
+    /// Edits that can be redone, the next one to redo last.
+    pub fn undone(&self) -> &[Step] {
+        &self.undone
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_scheduler::job_builder;
+
+    #[test]
+    fn undo_and_redo() {
+        let mut workload = Workload {
+            jobs: job_builder(&[], 3),
+            ..Default::default()
+        };
+        let original = workload.clone();
+        let mut history = History::default();
+
+        workload.jobs.swap(0, 2);
+        history.push(Edit::Swap { a: 0, b: 2 }, &workload);
+        let shrunk = Workload {
+            jobs: workload.jobs[..1].to_vec(),
+            ..workload.clone()
+        };
+        history.push(
+            Edit::Resize {
+                from: workload.jobs.clone(),
+                to: shrunk.jobs.clone(),
+            },
+            &shrunk,
+        );
+        workload = shrunk;
+        assert_eq!(history.done()[0].description, "Swap jobs C and A");
+
+        assert!(history.undo(&mut workload));
+        assert_eq!(workload.jobs.len(), 3);
+        assert!(history.undo(&mut workload));
+        assert_eq!(workload, original);
+        assert!(!history.undo(&mut workload));
+
+        history.go_to(2, &mut workload);
+        assert_eq!(workload.jobs.len(), 1);
+        assert_eq!(workload.jobs[0].job_name, "C");
+        assert!(!history.redo(&mut workload));
+    }
+
+    #[test]
+    fn drags_merge_until_sealed() {
+        let workload = Workload {
+            jobs: job_builder(&[], 2),
+            ..Default::default()
+        };
+        let mut history = History::default();
+        history.push(
+            Edit::CpuCycle {
+                job: 1,
+                from: 1,
+                to: 2,
+            },
+            &workload,
+        );
+        history.push(
+            Edit::CpuCycle {
+                job: 1,
+                from: 2,
+                to: 5,
+            },
+            &workload,
+        );
+        assert_eq!(history.done().len(), 1);
+        assert_eq!(history.done()[0].description, "Job B CPU cycle 1 → 5");
+
+        history.seal();
+        history.push(
+            Edit::CpuCycle {
+                job: 1,
+                from: 5,
+                to: 6,
+            },
+            &workload,
+        );
+        history.push(
+            Edit::CpuCycle {
+                job: 0,
+                from: 1,
+                to: 2,
+            },
+            &workload,
+        );
+        assert_eq!(history.done().len(), 3);
+
+        // dragging back to where it started leaves nothing to undo
+        history.seal();
+        history.push(Edit::TimeQuantum { from: 5, to: 6 }, &workload);
+        history.push(Edit::TimeQuantum { from: 6, to: 5 }, &workload);
+        assert_eq!(history.done().len(), 3);
+    }
+
+    #[test]
+    fn cores_and_switch_cost_undo() {
+        let mut workload = Workload::default();
+        workload.config.cores = 2;
+        let mut history = History::default();
+        history.push(Edit::Cores { from: 1, to: 2 }, &workload);
+        workload.config.context_switch_cost = 3;
+        history.push(Edit::ContextSwitchCost { from: 0, to: 3 }, &workload);
+        assert_eq!(history.done()[1].description, "Context switch cost 0 → 3");
+
+        assert!(history.undo(&mut workload));
+        assert!(history.undo(&mut workload));
+        assert_eq!(workload, Workload::default());
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::BTreeSet;
+
+use crate::process_scheduler::{job_builder, new_job, Job};
+
+/// One pasted spreadsheet row, columns the paste did not have are `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PastedRow {
+    pub job_name: Option<String>,
+    pub needed_cpu_cycle: Option<u32>,
+    pub arrival_time: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Column {
+    JobName,
+    CpuCycle,
+    ArrivalTime,
+}
+
+impl Column {
+    pub const ALL: [Column; 3] = [Column::JobName, Column::CpuCycle, Column::ArrivalTime];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::JobName => "Job",
+            Column::CpuCycle => "CPU Cycle",
+            Column::ArrivalTime => "Arrival Time",
+        }
+    }
+
+    /// Guesses the column from a spreadsheet header.
+    fn from_header(header: &str) -> Option<Column> {
+        let header = header.to_lowercase();
+        if header.contains("arriv") || header.contains("submit") {
+            Some(Column::ArrivalTime)
+        } else if ["cpu", "burst", "cycle", "service", "run"]
+            .iter()
+            .any(|word| header.contains(word))
+        {
+            Some(Column::CpuCycle)
+        } else if header.contains("name") || header.contains("job") {
+            Some(Column::JobName)
+        } else {
+            None
+        }
+    }
+}
+
+/// Reads tab separated rows as copied from a spreadsheet, or comma separated ones.
+/// Without a header row the columns are taken to be in the order of the job table:
+/// job, CPU cycle, arrival time, or just CPU cycle and arrival time when there are two.
+pub fn parse_rows(text: &str) -> Result<Vec<PastedRow>, String> {
+    let separator = if text.contains('\t') { '\t' } else { ',' };
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line_number, line)| {
+            let fields: Vec<&str> = line.split(separator).map(str::trim).collect();
+            (line_number, fields)
+        })
+        .peekable();
+    let Some((_, first)) = lines.peek() else {
+        return Ok(Vec::new());
+    };
+
+    let is_number = |field: &str| field.is_empty() || field.parse::<u32>().is_ok();
+    let header = first
+        .iter()
+        .skip(if first.len() >= 3 { 1 } else { 0 })
+        .any(|field| !is_number(field));
+    let columns: Vec<Option<Column>> = if header {
+        let columns = first
+            .iter()
+            .map(|field| Column::from_header(field))
+            .collect();
+        lines.next();
+        columns
+    } else {
+        match first.len() {
+            1 => vec![Some(Column::CpuCycle)],
+            2 => vec![Some(Column::CpuCycle), Some(Column::ArrivalTime)],
+            _ => vec![
+                Some(Column::JobName),
+                Some(Column::CpuCycle),
+                Some(Column::ArrivalTime),
+            ],
+        }
+    };
+    if !columns.contains(&Some(Column::CpuCycle)) && !columns.contains(&Some(Column::ArrivalTime)) {
+        return Err("no CPU cycle or arrival time column found".to_string());
+    }
+
+    lines
+        .map(|(line_number, fields)| {
+            let mut row = PastedRow::default();
+            for (column, field) in columns.iter().zip(fields) {
+                if field.is_empty() {
+                    continue;
+                }
+                let number = || {
+                    field.parse::<u32>().map_err(|_| {
+                        format!("line {}: `{field}` is not a whole number", line_number + 1)
+                    })
+                };
+                match column {
+                    Some(Column::JobName) => row.job_name = Some(field.to_string()),
+                    Some(Column::CpuCycle) => row.needed_cpu_cycle = Some(number()?.max(1)),
+                    Some(Column::ArrivalTime) => row.arrival_time = Some(number()?),
+                    None => {}
+                }
+            }
+            Ok(row)
+        })
+        .collect()
+}
+
+/// Writes `rows` over the jobs starting at `at`, adding jobs past the end of the table.
+pub fn paste_rows(jobs: &mut Vec<Job>, at: usize, rows: &[PastedRow]) {
+    let at = at.min(jobs.len());
+    if at + rows.len() > jobs.len() {
+        *jobs = job_builder(jobs, (at + rows.len()) as u32);
+    }
+    for (job, row) in jobs[at..].iter_mut().zip(rows) {
+        if let Some(job_name) = &row.job_name {
+            job.job_name = job_name.clone();
+        }
+        if let Some(needed_cpu_cycle) = row.needed_cpu_cycle {
+            job.needed_cpu_cycle = needed_cpu_cycle;
+            job.remaining_cpu_cycle = needed_cpu_cycle;
+        }
+        if let Some(arrival_time) = row.arrival_time {
+            job.arrival_time = arrival_time;
+        }
+    }
+}
+
+/// Inserts a new job at `at`, see [`job_builder`](crate::process_scheduler::job_builder).
+pub fn insert_job(jobs: &mut Vec<Job>, at: usize) {
+    let job = new_job(jobs);
+    jobs.insert(at.min(jobs.len()), job);
+}
+
+pub fn delete_jobs(jobs: &mut Vec<Job>, selected: &BTreeSet<usize>) {
+    let mut index = 0;
+    jobs.retain(|_| {
+        index += 1;
+        !selected.contains(&(index - 1))
+    });
+}
+
+/// Stable sort, so rows that tie keep their order. Names sort as A, B, ..., Z, AA.
+pub fn sort_jobs(jobs: &mut [Job], column: Column, descending: bool) {
+    jobs.sort_by(|a, b| {
+        let order = match column {
+            Column::JobName => {
+                (a.job_name.len(), &a.job_name).cmp(&(b.job_name.len(), &b.job_name))
+            }
+            Column::CpuCycle => a.needed_cpu_cycle.cmp(&b.needed_cpu_cycle),
+            Column::ArrivalTime => a.arrival_time.cmp(&b.arrival_time),
+        };
+        if descending {
+            order.reverse()
+        } else {
+            order
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(jobs: &[Job]) -> Vec<(&str, u32, u32)> {
+        jobs.iter()
+            .map(|job| {
+                (
+                    job.job_name.as_str(),
+                    job.needed_cpu_cycle,
+                    job.arrival_time,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn paste_spreadsheet_rows() {
+        let rows = parse_rows("Arrival\tBurst\n3\t7\n\n5\t2\n1\t\n").unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[2].needed_cpu_cycle, None);
+
+        let mut jobs = job_builder(&[], 2);
+        paste_rows(&mut jobs, 1, &rows);
+        assert_eq!(
+            table(&jobs),
+            [("A", 1, 0), ("B", 7, 3), ("C", 2, 5), ("D", 1, 1)]
+        );
+
+        // names are only labels, two jobs may share one
+        let rows = parse_rows("X, 4, 0\nA, 5, 1").unwrap();
+        paste_rows(&mut jobs, 3, &rows);
+        assert_eq!(table(&jobs)[3..], [("X", 4, 0), ("A", 5, 1)]);
+        assert_eq!(jobs[4].id, 5);
+        assert!(parse_rows("1,2\n3,x").is_err());
+    }
+
+    #[test]
+    fn insert_delete_and_sort() {
+        let mut jobs = job_builder(&[], 3);
+        jobs[0].needed_cpu_cycle = 5;
+        jobs[2].needed_cpu_cycle = 5;
+        delete_jobs(&mut jobs, &BTreeSet::from([1]));
+        insert_job(&mut jobs, 1);
+        assert_eq!(table(&jobs), [("A", 5, 0), ("B", 1, 0), ("C", 5, 0)]);
+        // the new B is a different job than the deleted one
+        assert_eq!(jobs[1].id, 4);
+
+        sort_jobs(&mut jobs, Column::CpuCycle, true);
+        assert_eq!(table(&jobs), [("A", 5, 0), ("C", 5, 0), ("B", 1, 0)]);
+        jobs[0].job_name = "AA".to_string();
+        sort_jobs(&mut jobs, Column::JobName, false);
+        assert_eq!(table(&jobs), [("B", 1, 0), ("C", 5, 0), ("AA", 5, 0)]);
+    }
+}
@@ -1,5 +1,32 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
 mod app;
-mod process_scheduler;
+pub mod batch;
+pub mod charts;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+pub mod dependencies;
+pub mod energy;
+pub mod gantt;
+pub mod generator;
+pub mod golden;
+pub mod grader;
+pub mod history;
+pub mod job_table;
+pub mod lifecycle;
+pub mod locking;
+pub mod manual;
+pub mod memory;
+pub mod metrics;
+pub mod multilevel;
+pub mod optimal;
+pub mod process_scheduler;
+pub mod quiz;
+pub mod reference;
+pub mod scenario;
+pub mod swf;
+pub mod trace;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tui;
+pub mod workload;
 pub use app::App;
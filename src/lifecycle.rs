@@ -0,0 +1,185 @@
+/// The five-state process model, plus Suspended for jobs swapped out of memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProcessState {
+    /// Known to the system but not yet arrived.
+    New,
+    /// Arrived and in the ready queue.
+    Ready,
+    /// On a core.
+    Running,
+    /// Blocked on a resource another job holds.
+    Waiting,
+    Terminated,
+    /// Swapped out of memory by the medium-term scheduler.
+    Suspended,
+}
+
+impl ProcessState {
+    pub const ALL: [ProcessState; 6] = [
+        ProcessState::New,
+        ProcessState::Ready,
+        ProcessState::Running,
+        ProcessState::Waiting,
+        ProcessState::Terminated,
+        ProcessState::Suspended,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ProcessState::New => "New",
+            ProcessState::Ready => "Ready",
+            ProcessState::Running => "Running",
+            ProcessState::Waiting => "Waiting",
+            ProcessState::Terminated => "Terminated",
+            ProcessState::Suspended => "Suspended",
+        }
+    }
+
+    /// What moves a job from this state to `to`, in textbook terms.
+    pub fn transition_name(self, to: ProcessState) -> &'static str {
+        match (self, to) {
+            (ProcessState::New, ProcessState::Ready) => "admitted",
+            (ProcessState::Ready, ProcessState::Running) => "dispatched",
+            (ProcessState::Running, ProcessState::Ready) => "preempted",
+            (ProcessState::Running, ProcessState::Waiting) => "blocked",
+            (ProcessState::Waiting, ProcessState::Ready) => "woken up",
+            (ProcessState::Ready, ProcessState::Suspended) => "swapped out",
+            (ProcessState::Suspended, ProcessState::Ready) => "swapped in",
+            (_, ProcessState::Terminated) => "exited",
+            _ => "",
+        }
+    }
+}
+
+/// The arrows of the textbook state diagram.
+pub const TRANSITIONS: [(ProcessState, ProcessState); 6] = [
+    (ProcessState::New, ProcessState::Ready),
+    (ProcessState::Ready, ProcessState::Running),
+    (ProcessState::Running, ProcessState::Ready),
+    (ProcessState::Running, ProcessState::Waiting),
+    (ProcessState::Waiting, ProcessState::Ready),
+    (ProcessState::Running, ProcessState::Terminated),
+];
+
+/// The arrows to and from Suspended, which only swapping uses.
+pub const SWAP_TRANSITIONS: [(ProcessState, ProcessState); 2] = [
+    (ProcessState::Ready, ProcessState::Suspended),
+    (ProcessState::Suspended, ProcessState::Ready),
+];
+
+/// A job moving from one state to another, as emitted by the scheduler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StateChange {
+    pub time: u32,
+    pub job_id: u32,
+    pub from: ProcessState,
+    pub to: ProcessState,
+}
+
+/// The changes of `job_id` in time order.
+pub fn job_changes(changes: &[StateChange], job_id: u32) -> impl Iterator<Item = &StateChange> {
+    changes.iter().filter(move |change| change.job_id == job_id)
+}
+
+/// State of `job_id` during the tick starting at `time`, and the change that put it there.
+/// Every job is new until its first change.
+pub fn state_at(
+    changes: &[StateChange],
+    job_id: u32,
+    time: u32,
+) -> (ProcessState, Option<&StateChange>) {
+    job_changes(changes, job_id)
+        .take_while(|change| change.time <= time)
+        .last()
+        .map_or((ProcessState::New, None), |change| {
+            (change.to, Some(change))
+        })
+}
+
+/// Stretches of time `job_id` spent in each state as (state, start, end), from 0 up to
+/// `end`. The last state lasts until `end`.
+pub fn state_spans(
+    changes: &[StateChange],
+    job_id: u32,
+    end: u32,
+) -> Vec<(ProcessState, u32, u32)> {
+    let mut spans = Vec::new();
+    let (mut state, mut since) = (ProcessState::New, 0);
+    for change in job_changes(changes, job_id) {
+        if since < change.time {
+            spans.push((state, since, change.time));
+        }
+        (state, since) = (change.to, change.time);
+    }
+    if since < end {
+        spans.push((state, since, end));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_scheduler::{simulate, Algorithm, Job, SchedulerConfig};
+    use ProcessState::*;
+
+    fn round_robin() -> Vec<StateChange> {
+        // A arrives at 0 needing 3, B at 1 needing 2, quantum 2
+        let jobs =
+            [(1, 0, 3), (2, 1, 2), (3, 2, 0)].map(|(id, arrival_time, needed_cpu_cycle)| Job {
+                id,
+                arrival_time,
+                needed_cpu_cycle,
+                ..Default::default()
+            });
+        let config = SchedulerConfig {
+            algorithm: Algorithm::RoundRobin,
+            time_quantum: 2,
+            ..Default::default()
+        };
+        simulate(&jobs, &config).state_changes
+    }
+
+    #[test]
+    fn round_robin_changes() {
+        let changes = round_robin();
+        let a: Vec<(u32, ProcessState, ProcessState)> = job_changes(&changes, 1)
+            .map(|change| (change.time, change.from, change.to))
+            .collect();
+        assert_eq!(
+            a,
+            [
+                (0, New, Ready),
+                (0, Ready, Running),
+                (2, Running, Ready),
+                (4, Ready, Running),
+                (5, Running, Terminated)
+            ]
+        );
+        // a job needing no CPU never runs
+        assert_eq!(
+            state_spans(&changes, 3, 6),
+            [(New, 0, 2), (Terminated, 2, 6)]
+        );
+        assert_eq!(changes[1].from.transition_name(changes[1].to), "dispatched");
+    }
+
+    #[test]
+    fn states_over_time() {
+        let changes = round_robin();
+        assert_eq!(
+            state_spans(&changes, 2, 6),
+            [
+                (New, 0, 1),
+                (Ready, 1, 2),
+                (Running, 2, 4),
+                (Terminated, 4, 6)
+            ]
+        );
+        assert_eq!(state_at(&changes, 2, 0), (New, None));
+        let (state, change) = state_at(&changes, 2, 3);
+        assert_eq!(state, Running);
+        assert_eq!(change.map(|change| change.time), Some(2));
+        assert_eq!(state_at(&changes, 1, 2).0, Ready);
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+
+use crate::process_scheduler::Job;
+
+/// Part of a job that must hold a resource, e.g. a lock on data it shares with other jobs.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct CriticalSection {
+    pub resource: String,
+    /// CPU cycles the job has run when it needs the resource.
+    pub offset: u32,
+    /// CPU cycles the job runs while holding it.
+    pub duration: u32,
+}
+
+impl CriticalSection {
+    pub fn end(&self) -> u32 {
+        self.offset + self.duration
+    }
+}
+
+/// How a job holding a resource is ranked while others want it.
+///
+/// A job's priority is the key its algorithm ranks it by, lower going first: arrival
+/// time under FCFS, CPU cycles under SJN and remaining time under SRT. Random and
+/// Round Robin have no priorities, so the protocols change nothing there.
+#[derive(
+    serde::Deserialize, serde::Serialize, Clone, Copy, Default, Debug, PartialEq, Eq, Hash,
+)]
+pub enum LockProtocol {
+    /// The holder keeps its own priority, so jobs ranked between it and a job waiting for
+    /// it can keep both waiting (priority inversion).
+    #[default]
+    None,
+    /// The holder takes the priority of the best ranked job waiting for its resources.
+    Inheritance,
+    /// The holder takes the ceiling of its resources, the priority of the best ranked job
+    /// that ever uses them, from the moment it takes them.
+    Ceiling,
+}
+
+impl LockProtocol {
+    pub const ALL: [LockProtocol; 3] = [
+        LockProtocol::None,
+        LockProtocol::Inheritance,
+        LockProtocol::Ceiling,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LockProtocol::None => "No protocol",
+            LockProtocol::Inheritance => "Priority inheritance",
+            LockProtocol::Ceiling => "Priority ceiling",
+        }
+    }
+
+    /// Name used on the command line.
+    pub fn short_name(self) -> &'static str {
+        match self {
+            LockProtocol::None => "none",
+            LockProtocol::Inheritance => "inheritance",
+            LockProtocol::Ceiling => "ceiling",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LockProtocol> {
+        LockProtocol::ALL.into_iter().find(|protocol| {
+            protocol.name() == name || protocol.short_name().eq_ignore_ascii_case(name)
+        })
+    }
+}
+
+/// A job holding a resource from `start` until `end`, whether or not it was running.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hold {
+    pub job_id: u32,
+    pub resource: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Explains what is wrong with a job's critical sections: a section with no resource,
+/// one running past the job's CPU cycles, or two overlapping. Overlapping sections would
+/// let a job wait for one resource while holding another, and so deadlock.
+pub fn check_critical_sections(jobs: &[Job]) -> Result<(), String> {
+    for job in jobs {
+        let mut sections: Vec<&CriticalSection> = job.critical_sections.iter().collect();
+        sections.sort_by_key(|section| section.offset);
+        for section in &sections {
+            if section.resource.trim().is_empty() {
+                return Err(format!(
+                    "job {} has a critical section without a resource",
+                    job.job_name
+                ));
+            }
+            if section.end() > job.needed_cpu_cycle {
+                return Err(format!(
+                    "job {}'s critical section on {} ends at cycle {}, after the job's {} cycles",
+                    job.job_name,
+                    section.resource,
+                    section.end(),
+                    job.needed_cpu_cycle
+                ));
+            }
+        }
+        for pair in sections.windows(2) {
+            if pair[1].offset < pair[0].end() {
+                return Err(format!(
+                    "job {}'s critical sections on {} and {} overlap",
+                    job.job_name, pair[0].resource, pair[1].resource
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The sections of `job` the simulator goes by: in order, cut to fit the job's CPU cycles,
+/// each starting no earlier than the one before ends, and leaving out empty ones.
+pub(crate) fn effective_sections(job: &Job) -> Vec<CriticalSection> {
+    let mut sections = job.critical_sections.clone();
+    sections.sort_by_key(|section| section.offset);
+    let mut previous_end = 0;
+    let mut effective = Vec::new();
+    for section in sections {
+        let offset = section.offset.max(previous_end);
+        let end = section.end().min(job.needed_cpu_cycle);
+        if offset < end {
+            previous_end = end;
+            effective.push(CriticalSection {
+                offset,
+                duration: end - offset,
+                ..section
+            });
+        }
+    }
+    effective
+}
+
+/// The ceiling of every resource, the best of the priorities of the jobs using it.
+pub(crate) fn ceilings(jobs: &[Job], priority: impl Fn(&Job) -> u32) -> HashMap<String, u32> {
+    let mut ceilings: HashMap<String, u32> = HashMap::new();
+    for job in jobs {
+        for section in &job.critical_sections {
+            let ceiling = ceilings.entry(section.resource.clone()).or_insert(u32::MAX);
+            *ceiling = (*ceiling).min(priority(job));
+        }
+    }
+    ceilings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifecycle::ProcessState;
+    use crate::metrics::job_metrics;
+    use crate::process_scheduler::{jobs_from, simulate, timeline, Algorithm, SchedulerConfig};
+
+    /// L takes R first, H needs it right after arriving, M needs no resource
+    /// but is ranked between them.
+    fn inversion() -> Vec<Job> {
+        let mut jobs = jobs_from(&[("L", 0, 8), ("H", 1, 2), ("M", 2, 5)]);
+        for (job, duration) in jobs.iter_mut().zip([4, 1]) {
+            job.critical_sections = vec![CriticalSection {
+                resource: "R".to_string(),
+                offset: 0,
+                duration,
+            }];
+        }
+        jobs
+    }
+
+    fn srt(locking: LockProtocol) -> SchedulerConfig {
+        SchedulerConfig {
+            algorithm: Algorithm::Srt,
+            locking,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn protocols_bound_priority_inversion() {
+        // H preempts L but blocks on R before it runs, and then M runs ahead of L while H waits
+        let schedule = simulate(&inversion(), &srt(LockProtocol::None));
+        assert_eq!(
+            timeline(&schedule),
+            [
+                ("L", 0, 2),
+                ("M", 2, 7),
+                ("L", 7, 9),
+                ("H", 9, 11),
+                ("L", 11, 15)
+            ]
+        );
+        // L inherits H's priority, so M has to wait until L is done with R
+        assert_eq!(
+            timeline(&simulate(&inversion(), &srt(LockProtocol::Inheritance))),
+            [("L", 0, 4), ("H", 4, 6), ("L", 6, 10), ("M", 10, 15)]
+        );
+        // L runs at R's ceiling from the start, so H does not even preempt it
+        assert_eq!(
+            timeline(&simulate(&inversion(), &srt(LockProtocol::Ceiling))),
+            [("L", 0, 4), ("H", 4, 6), ("L", 6, 10), ("M", 10, 15)]
+        );
+
+        // H blocking before it ran switched nothing
+        assert_eq!(schedule.context_switches, 4);
+        let blocked: Vec<(String, u32)> = job_metrics(&schedule)
+            .into_iter()
+            .map(|job| (job.job_name, job.blocked_time))
+            .collect();
+        assert!(blocked.contains(&("H".to_string(), 8)));
+        assert!(blocked.contains(&("L".to_string(), 0)));
+        assert!(blocked.contains(&("M".to_string(), 0)));
+        assert_eq!(
+            schedule.holds,
+            [
+                Hold {
+                    job_id: 1,
+                    resource: "R".to_string(),
+                    start: 0,
+                    end: 9
+                },
+                Hold {
+                    job_id: 2,
+                    resource: "R".to_string(),
+                    start: 9,
+                    end: 10
+                },
+            ]
+        );
+        assert!(schedule
+            .state_changes
+            .iter()
+            .any(|change| change.job_id == 2 && change.to == ProcessState::Waiting));
+    }
+
+    #[test]
+    fn sections_are_checked() {
+        let mut jobs = inversion();
+        assert_eq!(check_critical_sections(&jobs), Ok(()));
+        jobs[0].critical_sections.push(CriticalSection {
+            resource: "S".to_string(),
+            offset: 2,
+            duration: 3,
+        });
+        assert_eq!(
+            check_critical_sections(&jobs),
+            Err("job L's critical sections on R and S overlap".to_string())
+        );
+        // the simulator starts S once R is released
+        assert_eq!(effective_sections(&jobs[0])[1].offset, 4);
+        assert_eq!(effective_sections(&jobs[0])[1].duration, 1);
+
+        jobs[0].critical_sections[1].offset = 6;
+        assert_eq!(
+            check_critical_sections(&jobs),
+            Err(
+                "job L's critical section on S ends at cycle 9, after the job's 8 cycles"
+                    .to_string()
+            )
+        );
+    }
+}
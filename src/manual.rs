@@ -0,0 +1,364 @@
+use crate::dependencies;
+use crate::metrics::{run_metrics, RunMetrics};
+use crate::process_scheduler::{
+    ensure_unique_ids, simulate, Algorithm, Job, Schedule, SchedulerConfig, Segment,
+};
+
+/// A single core where the user makes every scheduling decision.
+///
+/// The scheduler waits at each decision point, i.e. whenever the core is free and
+/// some job is ready, until [`run`](ManualScheduler::run) says which job goes next.
+/// Idle stretches with nothing ready are skipped over. As in [`simulate`], a job is
+/// only ready once the jobs it depends on have finished.
+#[derive(Clone, Debug, Default)]
+pub struct ManualScheduler {
+    // sorted by arrival time, remaining_cpu_cycle counts down as jobs run
+    jobs: Vec<Job>,
+    // positions of the jobs each job waits for, none if the dependencies form a cycle
+    predecessors: Vec<Vec<usize>>,
+    clock: u32,
+    last_job: Option<u32>,
+    schedule: Schedule,
+}
+
+impl ManualScheduler {
+    pub fn new(jobs: &[Job]) -> Self {
+        let mut jobs = jobs.to_vec();
+        ensure_unique_ids(&mut jobs);
+        for job in &mut jobs {
+            job.remaining_cpu_cycle = job.needed_cpu_cycle;
+        }
+        jobs.sort_by_key(|job| job.arrival_time);
+        let predecessors = match dependencies::topological_order(&jobs) {
+            Ok(_) => dependencies::predecessors(&jobs),
+            Err(_) => vec![Vec::new(); jobs.len()],
+        };
+        let mut scheduler = Self {
+            jobs,
+            predecessors,
+            ..Default::default()
+        };
+        scheduler.advance();
+        scheduler
+    }
+
+    /// The jobs being scheduled, as they were when the run started apart from the remaining cycles.
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    pub fn clock(&self) -> u32 {
+        self.clock
+    }
+
+    /// Jobs that have arrived, are not waiting for another job and still need the CPU,
+    /// in arrival order.
+    pub fn ready(&self) -> impl Iterator<Item = &Job> {
+        (0..self.jobs.len())
+            .filter(|&job| self.jobs[job].remaining_cpu_cycle > 0 && self.released(job))
+            .map(|job| &self.jobs[job])
+    }
+
+    /// Whether the job at `job` has arrived and the jobs it depends on have finished.
+    fn released(&self, job: usize) -> bool {
+        self.jobs[job].arrival_time <= self.clock
+            && self.predecessors[job]
+                .iter()
+                .all(|&predecessor| self.is_done(predecessor))
+    }
+
+    fn is_done(&self, job: usize) -> bool {
+        let id = self.jobs[job].id;
+        self.schedule.jobs.iter().any(|done| done.id == id)
+    }
+
+    /// Arrival time of the next job that has not arrived yet.
+    pub fn next_arrival(&self) -> Option<u32> {
+        self.jobs
+            .iter()
+            .map(|job| job.arrival_time)
+            .find(|&arrival_time| arrival_time > self.clock)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.schedule.jobs.len() == self.jobs.len()
+    }
+
+    /// What has been decided so far, the finished schedule once [`is_finished`](ManualScheduler::is_finished).
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Runs the ready job `job_id` for `ticks`, or until it finishes when `ticks` is `None`.
+    /// Asking for more ticks than the job has left runs it to completion.
+    pub fn run(&mut self, job_id: u32, ticks: Option<u32>) -> Result<(), String> {
+        let clock = self.clock;
+        let position = self
+            .jobs
+            .iter()
+            .position(|job| job.id == job_id)
+            .ok_or_else(|| format!("there is no job {job_id}"))?;
+        let waiting_for: Vec<String> = self.predecessors[position]
+            .iter()
+            .filter(|&&predecessor| !self.is_done(predecessor))
+            .map(|&predecessor| self.jobs[predecessor].job_name.clone())
+            .collect();
+        let job = &mut self.jobs[position];
+        if job.arrival_time > clock {
+            return Err(format!(
+                "job {} does not arrive until {}",
+                job.job_name, job.arrival_time
+            ));
+        }
+        if job.remaining_cpu_cycle == 0 {
+            return Err(format!("job {} has already finished", job.job_name));
+        }
+        if !waiting_for.is_empty() {
+            return Err(format!(
+                "job {} waits for {} to finish",
+                job.job_name,
+                waiting_for.join(", ")
+            ));
+        }
+        let ticks = ticks
+            .unwrap_or(job.remaining_cpu_cycle)
+            .clamp(1, job.remaining_cpu_cycle);
+
+        job.remaining_cpu_cycle -= ticks;
+        let end = clock + ticks;
+        if job.remaining_cpu_cycle == 0 {
+            job.completion_time = end;
+            job.turnaround_time = end - job.arrival_time;
+            self.schedule.jobs.push(job.clone());
+        }
+        if self.last_job.is_some_and(|last_job| last_job != job_id) {
+            self.schedule.context_switches += 1;
+        }
+        self.last_job = Some(job_id);
+        // a job picked again right away carries on in the same segment
+        match self.schedule.segments.last_mut() {
+            Some(segment) if segment.job_id == job_id && segment.end == clock => segment.end = end,
+            _ => self.schedule.segments.push(Segment {
+                job_id,
+                job_name: job.job_name.clone(),
+                core: 0,
+                start: clock,
+                end,
+            }),
+        }
+        self.clock = end;
+        self.advance();
+        Ok(())
+    }
+
+    /// Finishes jobs that need no CPU and skips ahead to the next arrival while nothing is ready.
+    fn advance(&mut self) {
+        loop {
+            // finishing one job may release another that needs no CPU
+            while let Some(job) = (0..self.jobs.len()).find(|&job| {
+                self.jobs[job].needed_cpu_cycle == 0 && !self.is_done(job) && self.released(job)
+            }) {
+                // it finishes as soon as it arrives or the last job it waits for finishes
+                let completion_time = self.predecessors[job]
+                    .iter()
+                    .map(|&predecessor| self.jobs[predecessor].completion_time)
+                    .fold(self.jobs[job].arrival_time, u32::max);
+                self.jobs[job].completion_time = completion_time;
+                self.jobs[job].turnaround_time = completion_time - self.jobs[job].arrival_time;
+                self.schedule.jobs.push(self.jobs[job].clone());
+            }
+            if self.ready().next().is_some() {
+                return;
+            }
+            match self.next_arrival() {
+                Some(arrival_time) => self.clock = arrival_time,
+                None => return,
+            }
+        }
+    }
+}
+
+/// Metrics of the user's schedule next to every built-in algorithm.
+///
+/// `config` supplies the Round Robin quantum, the Random seed and the tie-breaking. The
+/// rest is run like the manual schedule: on a single core without switching cost,
+/// aging, critical sections, power, memory or multilevel queues.
+pub fn compare(
+    jobs: &[Job],
+    manual: &Schedule,
+    config: &SchedulerConfig,
+) -> Vec<(String, RunMetrics)> {
+    let jobs: Vec<Job> = jobs
+        .iter()
+        .map(|job| Job {
+            critical_sections: Vec::new(),
+            ..job.clone()
+        })
+        .collect();
+    let mut rows = vec![("You".to_string(), run_metrics(manual, 1))];
+    for algorithm in Algorithm::ALL {
+        let config = SchedulerConfig {
+            algorithm,
+            time_quantum: config.time_quantum,
+            seed: config.seed,
+            tie_breaking: config.tie_breaking,
+            ..Default::default()
+        };
+        rows.push((
+            algorithm.name().to_string(),
+            run_metrics(&simulate(&jobs, &config), 1),
+        ));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::energy::PowerModel;
+    use crate::locking::{CriticalSection, LockProtocol};
+    use crate::memory::MemoryModel;
+    use crate::multilevel::MultilevelQueues;
+    use crate::process_scheduler::{jobs_from, Aging};
+
+    #[test]
+    fn user_picks_every_job() {
+        let jobs = &jobs_from(&[("A", 0, 6), ("B", 1, 3), ("C", 2, 1), ("D", 3, 4)]);
+        let mut manual = ManualScheduler::new(jobs);
+        assert_eq!(manual.ready().count(), 1);
+        assert!(manual.run(3, None).is_err());
+
+        manual.run(1, Some(2)).unwrap();
+        assert_eq!(manual.clock(), 2);
+        assert_eq!(
+            manual.ready().map(|job| job.id).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        manual.run(1, Some(1)).unwrap();
+        manual.run(3, None).unwrap();
+        manual.run(2, Some(99)).unwrap();
+        manual.run(4, None).unwrap();
+        assert!(!manual.is_finished());
+        manual.run(1, None).unwrap();
+        assert!(manual.is_finished());
+        assert!(manual.run(1, None).is_err());
+
+        let schedule = manual.schedule();
+        let spans: Vec<_> = schedule
+            .segments
+            .iter()
+            .map(|segment| (segment.job_id, segment.start, segment.end))
+            .collect();
+        assert_eq!(
+            spans,
+            [(1, 0, 3), (3, 3, 4), (2, 4, 7), (4, 7, 11), (1, 11, 14)]
+        );
+        assert_eq!(schedule.context_switches, 4);
+
+        let rows = compare(jobs, schedule, &SchedulerConfig::default());
+        assert_eq!(rows.len(), Algorithm::ALL.len() + 1);
+        let optimum = crate::optimal::preemptive_optimum(jobs, 1);
+        assert_eq!(optimum.average_turnaround_time, 6.25);
+        assert!(rows
+            .iter()
+            .all(|(_, metrics)| metrics.average_turnaround_time >= 6.25));
+    }
+
+    #[test]
+    fn idle_time_is_skipped() {
+        let mut jobs = crate::process_scheduler::job_builder(&[], 2);
+        jobs[0].needed_cpu_cycle = 2;
+        jobs[1].arrival_time = 5;
+        let mut manual = ManualScheduler::new(&jobs);
+        manual.run(1, None).unwrap();
+        assert_eq!(manual.clock(), 5);
+        assert_eq!(manual.next_arrival(), None);
+        manual.run(2, None).unwrap();
+        assert_eq!(manual.schedule().jobs[1].completion_time, 6);
+        assert_eq!(manual.schedule().context_switches, 1);
+    }
+
+    #[test]
+    fn comparison_leaves_out_what_the_user_cannot_use() {
+        let mut jobs = crate::process_scheduler::job_builder(&[], 3);
+        for job in &mut jobs {
+            job.needed_cpu_cycle = 3;
+        }
+        let mut manual = ManualScheduler::new(&jobs);
+        for job in 1..=3 {
+            manual.run(job, None).unwrap();
+        }
+        let plain = SchedulerConfig {
+            time_quantum: 2,
+            ..Default::default()
+        };
+        let expected = compare(&jobs, manual.schedule(), &plain);
+
+        // none of these may reach the built-in rows
+        for job in &mut jobs {
+            job.memory = 60;
+            job.critical_sections = vec![CriticalSection {
+                resource: "R".to_string(),
+                offset: 1,
+                duration: 1,
+            }];
+        }
+        let config = SchedulerConfig {
+            cores: 2,
+            context_switch_cost: 1,
+            aging: Some(Aging::default()),
+            locking: LockProtocol::Inheritance,
+            power: Some(PowerModel::default()),
+            memory: Some(MemoryModel {
+                size: 100,
+                ..Default::default()
+            }),
+            ..plain.clone()
+        };
+        assert_eq!(compare(&jobs, manual.schedule(), &config), expected);
+
+        // multilevel queues would put every job in the same queue whatever the row's algorithm
+        let multilevel = SchedulerConfig {
+            multilevel: Some(MultilevelQueues::default()),
+            ..plain
+        };
+        let rows = compare(&jobs, manual.schedule(), &multilevel);
+        assert_eq!(rows, expected);
+        let average = |name: &str| {
+            rows.iter()
+                .find(|(row, _)| row == name)
+                .map(|(_, metrics)| metrics.average_turnaround_time)
+        };
+        assert_ne!(
+            average(Algorithm::Fcfs.name()),
+            average(Algorithm::RoundRobin.name())
+        );
+    }
+
+    #[test]
+    fn dependents_wait_for_predecessors() {
+        let mut jobs = crate::process_scheduler::job_builder(&[], 3);
+        jobs[0].needed_cpu_cycle = 2;
+        jobs[1].depends_on = vec![1];
+        jobs[2].needed_cpu_cycle = 0;
+        jobs[2].depends_on = vec![2];
+        let mut manual = ManualScheduler::new(&jobs);
+        assert_eq!(manual.ready().map(|job| job.id).collect::<Vec<_>>(), [1]);
+        assert_eq!(
+            manual.run(2, None),
+            Err("job B waits for A to finish".to_string())
+        );
+
+        manual.run(1, None).unwrap();
+        assert_eq!(manual.ready().map(|job| job.id).collect::<Vec<_>>(), [2]);
+        manual.run(2, None).unwrap();
+        assert!(manual.is_finished());
+        // C needs no CPU and finishes with B
+        let done = &manual.schedule().jobs;
+        assert_eq!((done[2].id, done[2].completion_time), (3, 3));
+        assert_eq!(
+            manual.schedule().segments,
+            simulate(&jobs, &SchedulerConfig::default()).segments
+        );
+    }
+}
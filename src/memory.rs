@@ -0,0 +1,271 @@
+use crate::lifecycle::ProcessState;
+use crate::process_scheduler::{Job, Schedule};
+
+/// How the long-term scheduler picks the next job to let in from the job queue.
+#[derive(
+    serde::Deserialize, serde::Serialize, Clone, Copy, Default, Debug, PartialEq, Eq, Hash,
+)]
+pub enum Admission {
+    /// Strictly in queue order, so a job that does not fit holds up the ones behind it.
+    #[default]
+    Fcfs,
+    /// The first job in queue order that fits.
+    FirstFit,
+    /// The job that fits most tightly, leaving the least memory free.
+    BestFit,
+}
+
+impl Admission {
+    pub const ALL: [Admission; 3] = [Admission::Fcfs, Admission::FirstFit, Admission::BestFit];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Admission::Fcfs => "FCFS",
+            Admission::FirstFit => "First fit",
+            Admission::BestFit => "Best fit",
+        }
+    }
+
+    /// Name used on the command line.
+    pub fn short_name(self) -> &'static str {
+        match self {
+            Admission::Fcfs => "fcfs",
+            Admission::FirstFit => "first-fit",
+            Admission::BestFit => "best-fit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Admission> {
+        Admission::ALL.into_iter().find(|admission| {
+            admission.name() == name || admission.short_name().eq_ignore_ascii_case(name)
+        })
+    }
+
+    /// Position of the job to admit next given the memory each queued job needs, in
+    /// queue order, and the memory free. Ties go to the job queued first.
+    pub fn select(self, needs: impl IntoIterator<Item = u32>, free: u32) -> Option<usize> {
+        let mut needs = needs.into_iter().enumerate();
+        match self {
+            Admission::Fcfs => needs.next().filter(|&(_, need)| need <= free),
+            Admission::FirstFit => needs.find(|&(_, need)| need <= free),
+            Admission::BestFit => needs
+                .filter(|&(_, need)| need <= free)
+                .min_by_key(|&(position, need)| (free - need, position)),
+        }
+        .map(|(position, _)| position)
+    }
+}
+
+/// Memory for jobs, and how jobs are let into it.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct MemoryModel {
+    /// Memory the system has for jobs, in the units of [`Job::memory`].
+    pub size: u32,
+    pub admission: Admission,
+    /// Ticks the medium-term scheduler takes to swap a job out, and again to swap it back
+    /// in. Off unless set.
+    pub swap_cost: Option<u32>,
+}
+
+impl MemoryModel {
+    pub fn describe(&self) -> String {
+        let mut description = format!(
+            "Memory: {} units, {} admission",
+            self.size,
+            self.admission.name()
+        );
+        if let Some(cost) = self.swap_cost {
+            description.push_str(&format!(", swapping at {cost} ticks each way"));
+        }
+        description
+    }
+}
+
+impl Default for MemoryModel {
+    fn default() -> Self {
+        Self {
+            size: 100,
+            admission: Admission::default(),
+            swap_cost: None,
+        }
+    }
+}
+
+/// Explains which job needs more memory than the whole system has, and so could never
+/// be admitted.
+pub fn check_memory(jobs: &[Job], memory: &MemoryModel) -> Result<(), String> {
+    match jobs.iter().find(|job| job.memory > memory.size) {
+        Some(job) => Err(format!(
+            "job {} needs {} units of memory, more than the {} the system has",
+            job.job_name, job.memory, memory.size
+        )),
+        None => Ok(()),
+    }
+}
+
+/// A job holding `memory` units from `start` until `end`, from its admission until it
+/// finished or was swapped out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Residency {
+    pub job_id: u32,
+    pub memory: u32,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Memory in use during each tick, up to the last completion.
+pub fn memory_used(schedule: &Schedule) -> Vec<u32> {
+    let makespan = schedule
+        .jobs
+        .iter()
+        .map(|job| job.completion_time)
+        .max()
+        .unwrap_or(0);
+    let mut used = vec![0; makespan as usize];
+    for residency in &schedule.memory {
+        let end = residency.end.min(makespan) as usize;
+        for tick in used.iter_mut().take(end).skip(residency.start as usize) {
+            *tick += residency.memory;
+        }
+    }
+    used
+}
+
+/// Ticks from a job's arrival until the long-term scheduler first admitted it.
+pub fn admission_delay(schedule: &Schedule, job: &Job) -> u32 {
+    schedule
+        .state_changes
+        .iter()
+        .find(|change| change.job_id == job.id && change.from == ProcessState::New)
+        .map_or(0, |change| change.time.saturating_sub(job.arrival_time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_scheduler::{jobs_from, simulate, timeline, Algorithm, SchedulerConfig};
+
+    fn with_memory(mut jobs: Vec<Job>, memory: [u32; 3]) -> Vec<Job> {
+        for (job, memory) in jobs.iter_mut().zip(memory) {
+            job.memory = memory;
+        }
+        jobs
+    }
+
+    fn round_robin(memory: MemoryModel) -> SchedulerConfig {
+        SchedulerConfig {
+            algorithm: Algorithm::RoundRobin,
+            time_quantum: 2,
+            memory: Some(memory),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn admission_policies() {
+        assert_eq!(Admission::Fcfs.select([60, 10], 50), None);
+        assert_eq!(Admission::FirstFit.select([60, 10, 40], 50), Some(1));
+        assert_eq!(Admission::BestFit.select([60, 10, 40, 40], 50), Some(2));
+
+        // A holds 60 of 100 until it finishes at 4, B needs 50 and C 30
+        let jobs = with_memory(
+            jobs_from(&[("A", 0, 4), ("B", 0, 2), ("C", 0, 2)]),
+            [60, 50, 30],
+        );
+        let memory = |admission| MemoryModel {
+            admission,
+            ..Default::default()
+        };
+        // FCFS keeps C waiting behind B
+        let schedule = simulate(&jobs, &round_robin(memory(Admission::Fcfs)));
+        assert_eq!(timeline(&schedule), [("A", 0, 4), ("B", 4, 6), ("C", 6, 8)]);
+        assert_eq!(admission_delay(&schedule, &jobs[2]), 4);
+        assert_eq!(memory_used(&schedule), [60, 60, 60, 60, 80, 80, 30, 30]);
+        // first fit lets C in next to A
+        let schedule = simulate(&jobs, &round_robin(memory(Admission::FirstFit)));
+        assert_eq!(
+            timeline(&schedule),
+            [("A", 0, 2), ("C", 2, 4), ("A", 4, 6), ("B", 6, 8)]
+        );
+        assert_eq!(check_memory(&jobs, &memory(Admission::Fcfs)), Ok(()));
+        assert_eq!(
+            check_memory(
+                &jobs,
+                &MemoryModel {
+                    size: 50,
+                    ..memory(Admission::Fcfs)
+                }
+            ),
+            Err("job A needs 60 units of memory, more than the 50 the system has".to_string())
+        );
+    }
+
+    #[test]
+    fn swapping_makes_room() {
+        // A and B fill 80 of 100 when C arrives needing 50
+        let jobs = with_memory(
+            jobs_from(&[("A", 0, 4), ("B", 0, 2), ("C", 1, 2)]),
+            [60, 20, 50],
+        );
+        let memory = MemoryModel {
+            swap_cost: Some(1),
+            ..Default::default()
+        };
+        let schedule = simulate(&jobs, &round_robin(memory));
+        // A is swapped out once its quantum is up, C gets in as soon as A's memory is
+        // written out and A is swapped back in once C finishes
+        assert_eq!(
+            timeline(&schedule),
+            [("A", 0, 2), ("B", 2, 4), ("C", 4, 6), ("A", 7, 9)]
+        );
+        assert_eq!(schedule.swaps, 1);
+        assert_eq!(
+            schedule.memory,
+            [
+                Residency {
+                    job_id: 2,
+                    memory: 20,
+                    start: 0,
+                    end: 4
+                },
+                Residency {
+                    job_id: 1,
+                    memory: 60,
+                    start: 0,
+                    end: 4
+                },
+                Residency {
+                    job_id: 3,
+                    memory: 50,
+                    start: 4,
+                    end: 6
+                },
+                Residency {
+                    job_id: 1,
+                    memory: 60,
+                    start: 6,
+                    end: 9
+                },
+            ]
+        );
+        let a: Vec<(u32, ProcessState, ProcessState)> = schedule
+            .state_changes
+            .iter()
+            .filter(|change| change.job_id == 1)
+            .map(|change| (change.time, change.from, change.to))
+            .collect();
+        assert_eq!(
+            a,
+            [
+                (0, ProcessState::New, ProcessState::Ready),
+                (0, ProcessState::Ready, ProcessState::Running),
+                (2, ProcessState::Running, ProcessState::Ready),
+                (3, ProcessState::Ready, ProcessState::Suspended),
+                (7, ProcessState::Suspended, ProcessState::Ready),
+                (7, ProcessState::Ready, ProcessState::Running),
+                (9, ProcessState::Running, ProcessState::Terminated),
+            ]
+        );
+    }
+}
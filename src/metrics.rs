@@ -0,0 +1,191 @@
+use crate::lifecycle::{state_spans, ProcessState};
+use crate::process_scheduler::Schedule;
+
+/// Timing of a single finished job.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JobMetrics {
+    pub job_id: u32,
+    pub job_name: String,
+    pub arrival_time: u32,
+    pub needed_cpu_cycle: u32,
+    pub completion_time: u32,
+    pub turnaround_time: u32,
+    /// Time spent in the ready queue, i.e. turnaround minus the CPU cycles the job needed.
+    pub waiting_time: u32,
+    /// Time from arrival until the job first got a core.
+    pub response_time: u32,
+    /// Part of the waiting time spent blocked on a resource another job held.
+    pub blocked_time: u32,
+}
+
+/// When a job counts as starved: it waited longer than `max_wait` ticks, or longer
+/// than `burst_multiple` times the CPU cycles it needed. Either limit can be left out.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Starvation {
+    pub max_wait: Option<u32>,
+    pub burst_multiple: Option<f64>,
+}
+
+impl Default for Starvation {
+    fn default() -> Self {
+        Self {
+            max_wait: None,
+            burst_multiple: Some(5.0),
+        }
+    }
+}
+
+impl Starvation {
+    pub fn is_starved(&self, job: &JobMetrics) -> bool {
+        self.max_wait
+            .is_some_and(|max_wait| job.waiting_time > max_wait)
+            || self.burst_multiple.is_some_and(|multiple| {
+                job.waiting_time as f64 > multiple * job.needed_cpu_cycle as f64
+            })
+    }
+
+    /// Ids of the jobs in `schedule` that starved.
+    pub fn starved_jobs(&self, schedule: &Schedule) -> Vec<u32> {
+        job_metrics(schedule)
+            .iter()
+            .filter(|job| self.is_starved(job))
+            .map(|job| job.job_id)
+            .collect()
+    }
+}
+
+/// Summary of one simulation run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunMetrics {
+    pub average_turnaround_time: f64,
+    pub average_waiting_time: f64,
+    pub average_response_time: f64,
+    /// Completion time of the last job.
+    pub makespan: u32,
+    /// Share of core time spent running jobs, between 0 and 1.
+    pub cpu_utilisation: f64,
+    /// Jobs finished per tick.
+    pub throughput: f64,
+    pub context_switches: u32,
+}
+
+impl RunMetrics {
+    /// Column names and values, in the order used for CSV output.
+    pub fn values(&self) -> [(&'static str, f64); 7] {
+        [
+            ("average_turnaround_time", self.average_turnaround_time),
+            ("average_waiting_time", self.average_waiting_time),
+            ("average_response_time", self.average_response_time),
+            ("makespan", self.makespan as f64),
+            ("cpu_utilisation", self.cpu_utilisation),
+            ("throughput", self.throughput),
+            ("context_switches", self.context_switches as f64),
+        ]
+    }
+}
+
+pub fn job_metrics(schedule: &Schedule) -> Vec<JobMetrics> {
+    schedule
+        .jobs
+        .iter()
+        .map(|job| {
+            let first_start = schedule
+                .segments
+                .iter()
+                .filter(|segment| segment.job_id == job.id)
+                .map(|segment| segment.start)
+                .min()
+                .unwrap_or(job.completion_time);
+            JobMetrics {
+                job_id: job.id,
+                job_name: job.job_name.clone(),
+                arrival_time: job.arrival_time,
+                needed_cpu_cycle: job.needed_cpu_cycle,
+                completion_time: job.completion_time,
+                turnaround_time: job.turnaround_time,
+                waiting_time: job.turnaround_time.saturating_sub(job.needed_cpu_cycle),
+                response_time: first_start.saturating_sub(job.arrival_time),
+                blocked_time: state_spans(&schedule.state_changes, job.id, job.completion_time)
+                    .iter()
+                    .filter(|(state, _, _)| *state == ProcessState::Waiting)
+                    .map(|(_, start, end)| end - start)
+                    .sum(),
+            }
+        })
+        .collect()
+}
+
+pub fn run_metrics(schedule: &Schedule, cores: u32) -> RunMetrics {
+    let jobs = job_metrics(schedule);
+    let job_count = jobs.len().max(1) as f64;
+    let average = |value: fn(&JobMetrics) -> u32| {
+        jobs.iter().map(|job| value(job) as f64).sum::<f64>() / job_count
+    };
+
+    let makespan = jobs
+        .iter()
+        .map(|job| job.completion_time)
+        .max()
+        .unwrap_or(0);
+    let busy_time: u32 = schedule
+        .segments
+        .iter()
+        .map(|segment| segment.end - segment.start)
+        .sum();
+    let (cpu_utilisation, throughput) = if makespan == 0 {
+        (0.0, 0.0)
+    } else {
+        (
+            busy_time as f64 / (makespan as f64 * cores.max(1) as f64),
+            jobs.len() as f64 / makespan as f64,
+        )
+    };
+
+    RunMetrics {
+        average_turnaround_time: average(|job| job.turnaround_time),
+        average_waiting_time: average(|job| job.waiting_time),
+        average_response_time: average(|job| job.response_time),
+        makespan,
+        cpu_utilisation,
+        throughput,
+        context_switches: schedule.context_switches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_scheduler::{simulate, Algorithm, Job, SchedulerConfig};
+
+    #[test]
+    fn rr_metrics() {
+        let jobs = vec![
+            Job {
+                job_name: "A".to_string(),
+                arrival_time: 0,
+                needed_cpu_cycle: 4,
+                ..Default::default()
+            },
+            Job {
+                job_name: "B".to_string(),
+                arrival_time: 1,
+                needed_cpu_cycle: 2,
+                ..Default::default()
+            },
+        ];
+        let config = SchedulerConfig {
+            algorithm: Algorithm::RoundRobin,
+            time_quantum: 2,
+            ..Default::default()
+        };
+        // A 0-2, B 2-4, A 4-6
+        let metrics = run_metrics(&simulate(&jobs, &config), 1);
+        assert_eq!(metrics.average_turnaround_time, (6.0 + 3.0) / 2.0);
+        assert_eq!(metrics.average_waiting_time, (2.0 + 1.0) / 2.0);
+        assert_eq!(metrics.average_response_time, (0.0 + 1.0) / 2.0);
+        assert_eq!(metrics.makespan, 6);
+        assert_eq!(metrics.cpu_utilisation, 1.0);
+        assert_eq!(metrics.context_switches, 2);
+    }
+}
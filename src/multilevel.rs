@@ -0,0 +1,341 @@
+use crate::process_scheduler::{Algorithm, Job, Schedule};
+
+/// Which of the fixed multilevel queues a job goes in, highest priority first.
+#[derive(
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    Copy,
+    Default,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+pub enum JobClass {
+    System,
+    #[default]
+    Interactive,
+    Batch,
+}
+
+impl JobClass {
+    pub const ALL: [JobClass; 3] = [JobClass::System, JobClass::Interactive, JobClass::Batch];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            JobClass::System => "System",
+            JobClass::Interactive => "Interactive",
+            JobClass::Batch => "Batch",
+        }
+    }
+
+    /// Name used on the command line.
+    pub fn short_name(self) -> &'static str {
+        match self {
+            JobClass::System => "system",
+            JobClass::Interactive => "interactive",
+            JobClass::Batch => "batch",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<JobClass> {
+        JobClass::ALL
+            .into_iter()
+            .find(|class| class.name() == name || class.short_name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// How the CPU is shared between the queues.
+#[derive(
+    serde::Deserialize, serde::Serialize, Clone, Copy, Default, Debug, PartialEq, Eq, Hash,
+)]
+pub enum QueueSharing {
+    /// A queue only gets the CPU while every queue above it is empty, and a job arriving
+    /// in a higher queue preempts a job from a lower one.
+    #[default]
+    StrictPriority,
+    /// The queues take turns, each owning the CPU for its slice of ticks, and a queue
+    /// with nothing to run hands its turn down.
+    TimeSlicing,
+}
+
+impl QueueSharing {
+    pub const ALL: [QueueSharing; 2] = [QueueSharing::StrictPriority, QueueSharing::TimeSlicing];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            QueueSharing::StrictPriority => "Strict priority",
+            QueueSharing::TimeSlicing => "Time slicing",
+        }
+    }
+
+    /// Name used on the command line.
+    pub fn short_name(self) -> &'static str {
+        match self {
+            QueueSharing::StrictPriority => "priority",
+            QueueSharing::TimeSlicing => "slicing",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<QueueSharing> {
+        QueueSharing::ALL.into_iter().find(|sharing| {
+            sharing.name() == name || sharing.short_name().eq_ignore_ascii_case(name)
+        })
+    }
+}
+
+/// The algorithm one class's queue is scheduled by.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct ClassQueue {
+    pub algorithm: Algorithm,
+    /// Only used by Round Robin.
+    pub time_quantum: u32,
+    /// Ticks the queue owns the CPU for in each round of time slicing.
+    pub slice: u32,
+}
+
+impl Default for ClassQueue {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::Fcfs,
+            time_quantum: 2,
+            slice: 2,
+        }
+    }
+}
+
+/// A ready queue per job class, each with its own algorithm, taking the place of the
+/// config's algorithm.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct MultilevelQueues {
+    pub system: ClassQueue,
+    pub interactive: ClassQueue,
+    pub batch: ClassQueue,
+    pub sharing: QueueSharing,
+}
+
+impl Default for MultilevelQueues {
+    fn default() -> Self {
+        Self {
+            system: ClassQueue::default(),
+            interactive: ClassQueue {
+                algorithm: Algorithm::RoundRobin,
+                slice: 8,
+                ..Default::default()
+            },
+            batch: ClassQueue::default(),
+            sharing: QueueSharing::default(),
+        }
+    }
+}
+
+impl MultilevelQueues {
+    pub fn queue(&self, class: JobClass) -> &ClassQueue {
+        match class {
+            JobClass::System => &self.system,
+            JobClass::Interactive => &self.interactive,
+            JobClass::Batch => &self.batch,
+        }
+    }
+
+    pub fn queue_mut(&mut self, class: JobClass) -> &mut ClassQueue {
+        match class {
+            JobClass::System => &mut self.system,
+            JobClass::Interactive => &mut self.interactive,
+            JobClass::Batch => &mut self.batch,
+        }
+    }
+
+    /// Explains why the queues cannot be used, time slicing needs a slice to hand out.
+    pub fn check(&self) -> Result<(), String> {
+        match self.sharing {
+            QueueSharing::TimeSlicing if self.round() == 0 => {
+                Err("time slicing needs at least one queue with a slice".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn round(&self) -> u32 {
+        JobClass::ALL
+            .into_iter()
+            .map(|class| self.queue(class).slice)
+            .sum()
+    }
+
+    /// The queue owning the CPU at `time` under time slicing, by the slices in class order.
+    pub fn owner(&self, time: u32) -> Option<JobClass> {
+        let round = self.round();
+        if self.sharing != QueueSharing::TimeSlicing || round == 0 {
+            return None;
+        }
+        let mut tick = time % round;
+        JobClass::ALL.into_iter().find(|&class| {
+            let slice = self.queue(class).slice;
+            match tick < slice {
+                true => true,
+                false => {
+                    tick -= slice;
+                    false
+                }
+            }
+        })
+    }
+
+    /// The classes in the order they get the CPU at `time`: the queue owning the slice
+    /// first, then the rest from highest priority down.
+    pub fn order(&self, time: u32) -> [JobClass; 3] {
+        let mut order = JobClass::ALL;
+        if let Some(owner) = self.owner(time) {
+            order.sort_by_key(|&class| class != owner);
+        }
+        order
+    }
+
+    /// The queues and how they share the CPU in a sentence, for showing next to results.
+    pub fn describe(&self) -> String {
+        let queues: Vec<String> = JobClass::ALL
+            .into_iter()
+            .map(|class| {
+                let queue = self.queue(class);
+                let mut description =
+                    format!("{} {}", class.short_name(), queue.algorithm.short_name());
+                if queue.algorithm == Algorithm::RoundRobin {
+                    description.push_str(&format!(" q{}", queue.time_quantum.max(1)));
+                }
+                description
+            })
+            .collect();
+        let mut description = format!(
+            "Multilevel queues: {}; {}",
+            queues.join(", "),
+            self.sharing.name().to_lowercase()
+        );
+        if self.sharing == QueueSharing::TimeSlicing {
+            let slices: Vec<String> = JobClass::ALL
+                .into_iter()
+                .map(|class| self.queue(class).slice.to_string())
+                .collect();
+            description.push_str(&format!(" {}", slices.join("/")));
+        }
+        description
+    }
+}
+
+/// Ticks of CPU time each class got over the run.
+pub fn class_ticks(schedule: &Schedule) -> [(JobClass, u32); 3] {
+    JobClass::ALL.map(|class| {
+        let ticks = schedule
+            .segments
+            .iter()
+            .filter(|segment| {
+                schedule
+                    .jobs
+                    .iter()
+                    .any(|job| job.id == segment.job_id && job.class == class)
+            })
+            .map(|segment| segment.end - segment.start)
+            .sum();
+        (class, ticks)
+    })
+}
+
+/// Classes with at least one job, in priority order.
+pub fn classes_used(jobs: &[Job]) -> Vec<JobClass> {
+    JobClass::ALL
+        .into_iter()
+        .filter(|&class| jobs.iter().any(|job| job.class == class))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_scheduler::{jobs_from, simulate, timeline, SchedulerConfig};
+
+    fn with_classes(mut jobs: Vec<Job>, classes: &[JobClass]) -> Vec<Job> {
+        for (job, &class) in jobs.iter_mut().zip(classes) {
+            job.class = class;
+        }
+        jobs
+    }
+
+    fn queued(multilevel: MultilevelQueues) -> SchedulerConfig {
+        SchedulerConfig {
+            multilevel: Some(multilevel),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn strict_priority_preempts_lower_queues() {
+        use JobClass::*;
+        let jobs = with_classes(
+            jobs_from(&[("B", 0, 5), ("I", 1, 3), ("J", 1, 2), ("S", 2, 1)]),
+            &[Batch, Interactive, Interactive, System],
+        );
+        let schedule = simulate(&jobs, &queued(MultilevelQueues::default()));
+        // I preempts B, S preempts I, the interactive jobs take turns by Round Robin
+        // and B only gets back on once both are done
+        assert_eq!(
+            timeline(&schedule),
+            [
+                ("B", 0, 1),
+                ("I", 1, 2),
+                ("S", 2, 3),
+                ("J", 3, 5),
+                ("I", 5, 7),
+                ("B", 7, 11)
+            ]
+        );
+    }
+
+    #[test]
+    fn time_slicing_shares_the_cpu() {
+        use JobClass::*;
+        let jobs = with_classes(
+            jobs_from(&[("I", 0, 16), ("B", 0, 4)]),
+            &[Interactive, Batch],
+        );
+        let multilevel = MultilevelQueues {
+            sharing: QueueSharing::TimeSlicing,
+            system: ClassQueue {
+                slice: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(multilevel.order(7), [Interactive, System, Batch]);
+        assert_eq!(multilevel.order(8), [Batch, System, Interactive]);
+        let schedule = simulate(&jobs, &queued(multilevel.clone()));
+        // 8 ticks for interactive, then 2 for batch, round after round
+        assert_eq!(
+            timeline(&schedule),
+            [("I", 0, 8), ("B", 8, 10), ("I", 10, 18), ("B", 18, 20)]
+        );
+        assert_eq!(
+            class_ticks(&schedule),
+            [(System, 0), (Interactive, 16), (Batch, 4)]
+        );
+        let unsliced = MultilevelQueues {
+            interactive: ClassQueue {
+                slice: 0,
+                ..multilevel.interactive
+            },
+            batch: ClassQueue {
+                slice: 0,
+                ..multilevel.batch
+            },
+            ..multilevel
+        };
+        assert_eq!(
+            unsliced.check(),
+            Err("time slicing needs at least one queue with a slice".to_string())
+        );
+    }
+}
@@ -0,0 +1,365 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::metrics::run_metrics;
+use crate::process_scheduler::{simulate, Algorithm, Job, SchedulerConfig};
+
+/// Non-preemptive schedules are searched exactly up to this many jobs.
+pub const EXACT_JOB_LIMIT: usize = 9;
+/// Search nodes visited before settling for a lower bound.
+const MAX_NODES: usize = 200_000;
+
+/// The lowest average turnaround time any schedule of a workload can reach, or a
+/// lower bound on it when the exact value is too expensive to find.
+///
+/// Turnaround is completion minus arrival, so minimising the average turnaround is the
+/// same as minimising the average completion time. Context switches are taken to be free
+/// and job dependencies and critical sections are left out, so with any of those it is
+/// only a lower bound.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Optimum {
+    pub average_turnaround_time: f64,
+    /// False when `average_turnaround_time` is only a lower bound.
+    pub exact: bool,
+}
+
+impl Optimum {
+    /// How much worse `average_turnaround_time` is than the optimum, as a fraction of it.
+    pub fn gap(&self, average_turnaround_time: f64) -> f64 {
+        if self.average_turnaround_time > 0.0 {
+            (average_turnaround_time - self.average_turnaround_time) / self.average_turnaround_time
+        } else {
+            0.0
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.exact {
+            true => format!("{:.2}", self.average_turnaround_time),
+            false => format!("at least {:.2}", self.average_turnaround_time),
+        }
+    }
+}
+
+/// Arrival and CPU cycles of every job that needs the CPU, and the arrival sum of all jobs.
+/// Jobs needing no cycles finish the moment they arrive whatever the schedule.
+fn split(jobs: &[Job]) -> (Vec<(u64, u64)>, u64) {
+    let work = jobs
+        .iter()
+        .filter(|job| job.needed_cpu_cycle > 0)
+        .map(|job| (job.arrival_time as u64, job.needed_cpu_cycle as u64))
+        .collect();
+    let arrivals = jobs.iter().map(|job| job.arrival_time as u64).sum();
+    (work, arrivals)
+}
+
+fn optimum(jobs: &[Job], completion_sum: f64, exact: bool) -> Optimum {
+    let exact = exact
+        && jobs
+            .iter()
+            .all(|job| job.depends_on.is_empty() && job.critical_sections.is_empty());
+    let (_, arrivals) = split(jobs);
+    let zero_cycle: u64 = jobs
+        .iter()
+        .filter(|job| job.needed_cpu_cycle == 0)
+        .map(|job| job.arrival_time as u64)
+        .sum();
+    Optimum {
+        average_turnaround_time: (completion_sum + zero_cycle as f64 - arrivals as f64)
+            / jobs.len().max(1) as f64,
+        exact,
+    }
+}
+
+/// Sum of completion times when always running the job with the least work left (SRPT)
+/// on a single core, with no job starting before `start`.
+fn srpt_completion_sum(jobs: &[(u64, u64)], start: u64) -> u64 {
+    let mut jobs: Vec<(u64, u64)> = jobs
+        .iter()
+        .map(|&(arrival, work)| (arrival.max(start), work))
+        .collect();
+    jobs.sort_unstable();
+    let mut ready = BinaryHeap::new();
+    let (mut clock, mut next, mut sum) = (start, 0, 0);
+    while next < jobs.len() || !ready.is_empty() {
+        if ready.is_empty() {
+            clock = clock.max(jobs[next].0);
+        }
+        while next < jobs.len() && jobs[next].0 <= clock {
+            ready.push(Reverse(jobs[next].1));
+            next += 1;
+        }
+        let Reverse(work) = ready.pop().expect("a job is ready");
+        let next_arrival = jobs.get(next).map_or(u64::MAX, |&(arrival, _)| arrival);
+        if clock + work <= next_arrival {
+            clock += work;
+            sum += clock;
+        } else {
+            ready.push(Reverse(work - (next_arrival - clock)));
+            clock = next_arrival;
+        }
+    }
+    sum
+}
+
+/// A lower bound on the completion sum of `jobs` on `cores` cores, none starting before `start`.
+///
+/// No job finishes before it could have run uninterrupted, and `cores` cores can do no
+/// better than a single core `cores` times as fast. The fast core is simulated with every
+/// time stretched by `cores` so that it stays in whole ticks.
+fn completion_sum_bound(jobs: &[(u64, u64)], cores: u64, start: u64) -> f64 {
+    let uninterrupted: u64 = jobs
+        .iter()
+        .map(|&(arrival, work)| arrival.max(start) + work)
+        .sum();
+    let stretched: Vec<(u64, u64)> = jobs
+        .iter()
+        .map(|&(arrival, work)| (arrival.max(start) * cores, work))
+        .collect();
+    let fast_core = srpt_completion_sum(&stretched, start * cores) as f64 / cores as f64;
+    fast_core.max(uninterrupted as f64)
+}
+
+/// The best average turnaround when jobs may be preempted, exact on a single core.
+///
+/// On one core always running the job with the shortest remaining processing time
+/// (SRPT) is optimal. With more cores the problem is NP-hard, so only a bound is given
+/// unless every job can have a core to itself.
+pub fn preemptive_optimum(jobs: &[Job], cores: u32) -> Optimum {
+    let (work, _) = split(jobs);
+    let cores = cores.max(1) as u64;
+    if cores == 1 {
+        optimum(jobs, srpt_completion_sum(&work, 0) as f64, true)
+    } else {
+        let exact = work.len() as u64 <= cores;
+        optimum(jobs, completion_sum_bound(&work, cores, 0), exact)
+    }
+}
+
+/// The best average turnaround when a job keeps its core until it finishes.
+///
+/// Searched exactly by branch and bound for up to [`EXACT_JOB_LIMIT`] jobs, otherwise or
+/// when the search runs too long the preemptive bound is returned instead.
+pub fn non_preemptive_optimum(jobs: &[Job], cores: u32) -> Optimum {
+    let (work, _) = split(jobs);
+    let core_count = cores.max(1) as usize;
+    if work.len() <= core_count {
+        let sum: u64 = work.iter().map(|&(arrival, work)| arrival + work).sum();
+        return optimum(jobs, sum as f64, true);
+    }
+    if work.len() <= EXACT_JOB_LIMIT {
+        // SJN is a good first guess to prune against
+        let config = SchedulerConfig {
+            algorithm: Algorithm::Sjn,
+            cores,
+            ..Default::default()
+        };
+        let greedy: u64 = simulate(jobs, &config)
+            .jobs
+            .iter()
+            .filter(|job| job.needed_cpu_cycle > 0)
+            .map(|job| job.completion_time as u64)
+            .sum();
+        let mut search = Search {
+            jobs: &work,
+            cores: core_count as u64,
+            best: greedy,
+            nodes: 0,
+        };
+        let mut scheduled = vec![false; work.len()];
+        if search.branch(&mut vec![0; core_count], &mut scheduled, 0, work.len()) {
+            return optimum(jobs, search.best as f64, true);
+        }
+    }
+    Optimum {
+        exact: false,
+        ..preemptive_optimum(jobs, cores)
+    }
+}
+
+/// Branch and bound over the order jobs start in. Given that order, putting each job
+/// on the core that frees up first is optimal, since the cores are identical.
+struct Search<'a> {
+    jobs: &'a [(u64, u64)],
+    cores: u64,
+    // lowest completion sum found so far
+    best: u64,
+    nodes: usize,
+}
+
+impl Search<'_> {
+    /// Tries every job as the next to start on the first free core. `free` holds when each
+    /// core is next free, in ascending order. False if the search ran out of nodes.
+    fn branch(&mut self, free: &mut [u64], scheduled: &mut [bool], sum: u64, left: usize) -> bool {
+        if left == 0 {
+            self.best = self.best.min(sum);
+            return true;
+        }
+        self.nodes += 1;
+        if self.nodes > MAX_NODES {
+            return false;
+        }
+        let now = free[0];
+        let unscheduled: Vec<(u64, u64)> = (0..self.jobs.len())
+            .filter(|&job| !scheduled[job])
+            .map(|job| self.jobs[job])
+            .collect();
+        if sum as f64 + completion_sum_bound(&unscheduled, self.cores, now) >= self.best as f64 {
+            return true;
+        }
+
+        for job in 0..self.jobs.len() {
+            if scheduled[job] {
+                continue;
+            }
+            let (arrival, work) = self.jobs[job];
+            let start = arrival.max(now);
+            // leaving the core idle is pointless when another job could run in the meantime
+            let dominated = (0..self.jobs.len()).any(|other| {
+                other != job && !scheduled[other] && {
+                    let (arrival, work) = self.jobs[other];
+                    arrival.max(now) + work <= start
+                }
+            });
+            if dominated {
+                continue;
+            }
+
+            let mut next_free = free.to_vec();
+            next_free[0] = start + work;
+            next_free.sort_unstable();
+            scheduled[job] = true;
+            let finished = self.branch(&mut next_free, scheduled, sum + start + work, left - 1);
+            scheduled[job] = false;
+            if !finished {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Average turnaround of every algorithm on `jobs` under `config`, with its gap from the
+/// optimum of its kind, preemptive or not. Multilevel queues are left out so each
+/// algorithm schedules every job.
+pub fn gaps(jobs: &[Job], config: &SchedulerConfig) -> Vec<(Algorithm, f64, f64)> {
+    let preemptive = preemptive_optimum(jobs, config.cores);
+    let non_preemptive = non_preemptive_optimum(jobs, config.cores);
+    Algorithm::ALL
+        .into_iter()
+        .map(|algorithm| {
+            let config = SchedulerConfig {
+                algorithm,
+                multilevel: None,
+                ..config.clone()
+            };
+            let average =
+                run_metrics(&simulate(jobs, &config), config.cores).average_turnaround_time;
+            let optimum = match algorithm.preemptive() {
+                true => preemptive,
+                false => non_preemptive,
+            };
+            (algorithm, average, optimum.gap(average))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_scheduler::jobs_from;
+    use proptest::prelude::*;
+
+    #[test]
+    fn textbook_optima() {
+        let jobs = &jobs_from(&[("A", 0, 6), ("B", 1, 3), ("C", 2, 1), ("D", 3, 4)]);
+        let srpt = simulate(
+            jobs,
+            &SchedulerConfig {
+                algorithm: Algorithm::Srt,
+                ..Default::default()
+            },
+        );
+        let srpt = run_metrics(&srpt, 1);
+        let preemptive = preemptive_optimum(jobs, 1);
+        assert_eq!(
+            preemptive.average_turnaround_time,
+            srpt.average_turnaround_time
+        );
+        assert!(preemptive.exact);
+
+        // idling until B arrives beats starting A: B 1-4, C 4-5, D 5-9, A 9-15
+        let non_preemptive = non_preemptive_optimum(jobs, 1);
+        assert_eq!(non_preemptive.average_turnaround_time, 27.0 / 4.0);
+        assert!(non_preemptive.exact);
+        assert_eq!(non_preemptive.gap(13.5), 1.0);
+
+        let gaps = gaps(jobs, &SchedulerConfig::default());
+        let (_, _, srt_gap) = gaps[3];
+        assert_eq!(srt_gap, 0.0);
+        assert!(gaps.iter().all(|&(_, _, gap)| gap >= 0.0));
+
+        // too many jobs to search, the preemptive optimum bounds it from below
+        let many = crate::process_scheduler::job_builder(jobs, EXACT_JOB_LIMIT as u32 + 1);
+        assert_eq!(
+            non_preemptive_optimum(&many, 1),
+            Optimum {
+                exact: false,
+                ..preemptive_optimum(&many, 1)
+            }
+        );
+    }
+
+    /// The best non-preemptive completion sum found by trying every start order.
+    fn brute_force(jobs: &[(u64, u64)], free: Vec<u64>, sum: u64) -> u64 {
+        if jobs.is_empty() {
+            return sum;
+        }
+        (0..jobs.len())
+            .map(|i| {
+                let (arrival, work) = jobs[i];
+                let mut rest = jobs.to_vec();
+                rest.remove(i);
+                let mut free = free.clone();
+                let end = arrival.max(free[0]) + work;
+                free[0] = end;
+                free.sort_unstable();
+                brute_force(&rest, free, sum + end)
+            })
+            .min()
+            .unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn optima_bound_every_algorithm(
+            specs in prop::collection::vec((0u32..12, 0u32..7), 1..7),
+            cores in 1u32..3,
+        ) {
+            let jobs: Vec<Job> = specs
+                .iter()
+                .map(|&(arrival_time, needed_cpu_cycle)| Job {
+                    arrival_time,
+                    needed_cpu_cycle,
+                    ..Default::default()
+                })
+                .collect();
+            let non_preemptive = non_preemptive_optimum(&jobs, cores);
+            prop_assert!(non_preemptive.exact);
+            let (work, _) = split(&jobs);
+            let expected = optimum(
+                &jobs,
+                brute_force(&work, vec![0; cores as usize], 0) as f64,
+                true,
+            );
+            prop_assert_eq!(non_preemptive, expected);
+
+            let preemptive = preemptive_optimum(&jobs, cores);
+            prop_assert!(preemptive.average_turnaround_time <= non_preemptive.average_turnaround_time + 1e-9);
+            let config = SchedulerConfig { cores, ..Default::default() };
+            for (algorithm, _, gap) in gaps(&jobs, &config) {
+                prop_assert!(gap >= -1e-9, "{:?} beat the optimum by {}", algorithm, -gap);
+            }
+        }
+    }
+}
@@ -1,24 +1,59 @@
-use core::panic;
-use std::{collections::VecDeque, ops::Not};
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
 
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-#[derive(serde::Deserialize, serde::Serialize, Clone, Default, Debug, PartialEq)]
-pub enum CPUStatus {
-    Working,
-    #[default]
-    Idle,
-}
+use crate::dependencies;
+use crate::energy::{self, CoreSpan, CoreState, FrequencyLevel, PowerModel};
+use crate::generator::WorkloadGenerator;
+use crate::lifecycle::{ProcessState, StateChange};
+use crate::locking::{self, CriticalSection, Hold, LockProtocol};
+use crate::memory::{MemoryModel, Residency};
+use crate::multilevel::{JobClass, MultilevelQueues};
 
 #[derive(serde::Deserialize, serde::Serialize, Default, Clone, Debug, PartialEq)]
 #[serde(default)]
 pub struct Job {
+    /// Identifies the job however it is renamed or moved, 0 until one is assigned.
+    pub id: u32,
+    /// Display name, free to edit and not necessarily unique.
     pub job_name: String,
     pub arrival_time: u32,
     pub needed_cpu_cycle: u32,
     pub remaining_cpu_cycle: u32, // rem cpu needs to be initialized with needed; code has no proper setter/getter
     pub completion_time: u32,
     pub turnaround_time: u32,
+    /// Ids of the jobs that must finish before this one can join the ready queue.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub critical_sections: Vec<CriticalSection>,
+    /// Time the job should be done by, only used to pick a CPU frequency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<u32>,
+    /// Memory the job holds from its admission until it finishes, if the config has a
+    /// memory model.
+    #[serde(skip_serializing_if = "is_default")]
+    pub memory: u32,
+    /// Queue the job goes in, only used with multilevel queues.
+    #[serde(skip_serializing_if = "is_default")]
+    pub class: JobClass,
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// Colour of a job in the job table and Gantt chart, derived from its id so renaming keeps it.
+pub fn job_color(id: u32) -> [u8; 3] {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    id.hash(&mut hasher);
+    let hash = hasher.finish();
+    let r = (hash & 0xFF) as u8;
+    let g = ((hash >> 8) & 0xFF) as u8;
+    let b = ((hash >> 16) & 0xFF) as u8;
+    [r, g, b]
 }
 
 fn return_job_name(i: usize) -> String {
@@ -31,543 +66,1253 @@ fn return_job_name(i: usize) -> String {
     }
     name
 }
-pub fn job_builder(old_jobs: &Vec<Job>, job_count: u32) -> Vec<Job> {
-    let mut jobs = Vec::new();
-    let mut old_jobs_cloned: Vec<Job> = old_jobs.clone();
-    old_jobs_cloned.sort_by(|a, b| a.job_name.partial_cmp(&b.job_name).unwrap());
-
-    // https://stackoverflow.com/a/45344045
-    let mut job_built: usize = 0;
-    for mut old_job in old_jobs_cloned {
-        old_job.job_name = return_job_name(job_built);
-        jobs.push(old_job);
-        job_built += 1;
-    }
-
-    let remaining_job_to_build = job_count as i32 - jobs.len() as i32;
-    // To build
-    // FIXME: Properly test and increment letters
-    if remaining_job_to_build > 0 {
-        for _ in 0..remaining_job_to_build {
-            jobs.push(Job {
-                // job_name: format!("{}", (b'A' + (jobs.len() as u8 + i as u8)) as char),
-                job_name: return_job_name(job_built as usize),
-                arrival_time: 0,
-                needed_cpu_cycle: 1,
-                remaining_cpu_cycle: 1,
-                completion_time: 0,
-                turnaround_time: 0,
-            });
-            job_built += 1;
-        }
-    } else {
-        // Destroy
-        for _ in 0..remaining_job_to_build.abs() {
-            jobs.pop();
+/// Grows or shrinks the job table to `job_count`, keeping the jobs' names and order.
+/// Jobs are removed from the end; new ones need one cycle, get the next free id and
+/// the first letter name not already taken.
+pub fn job_builder(old_jobs: &[Job], job_count: u32) -> Vec<Job> {
+    let mut jobs = old_jobs.to_vec();
+    ensure_unique_ids(&mut jobs);
+    jobs.truncate(job_count as usize);
+
+    let mut taken: std::collections::HashSet<String> =
+        jobs.iter().map(|job| job.job_name.clone()).collect();
+    let mut next_id = jobs.iter().map(|job| job.id).max().unwrap_or(0) + 1;
+    let mut letter = 0;
+    while jobs.len() < job_count as usize {
+        while taken.contains(&return_job_name(letter)) {
+            letter += 1;
         }
+        let job_name = return_job_name(letter);
+        taken.insert(job_name.clone());
+        jobs.push(Job {
+            id: next_id,
+            job_name,
+            needed_cpu_cycle: 1,
+            remaining_cpu_cycle: 1,
+            ..Default::default()
+        });
+        next_id += 1;
     }
-
     jobs
 }
 
+/// The job [`job_builder`] would add next.
+pub(crate) fn new_job(jobs: &[Job]) -> Job {
+    job_builder(jobs, jobs.len() as u32 + 1)
+        .pop()
+        .expect("job_builder added a job")
+}
+
+/// Gives jobs without an id, or with an id an earlier job already has, the next free one.
+/// Workloads saved before jobs had ids load with every id 0.
+pub fn ensure_unique_ids(jobs: &mut [Job]) {
+    let mut next = jobs.iter().map(|job| job.id).max().unwrap_or(0) + 1;
+    let mut seen = std::collections::HashSet::new();
+    for job in jobs {
+        if job.id == 0 || !seen.insert(job.id) {
+            job.id = next;
+            next += 1;
+        }
+    }
+}
+
+/// Fills in random arrival times and CPU cycles, see [`WorkloadGenerator`] for seeded workloads.
 pub fn randomize_jobs(jobs: Vec<Job>) -> Vec<Job> {
-    let mut rng = rand::thread_rng();
-    let mut scheduled_jobs = jobs.clone();
+    WorkloadGenerator {
+        seed: rand::thread_rng().gen(),
+        ..Default::default()
+    }
+    .generate(jobs)
+}
+
+/// Scheduling policies understood by [`simulate`].
+#[derive(
+    serde::Deserialize, serde::Serialize, Clone, Copy, Default, Debug, PartialEq, Eq, Hash,
+)]
+pub enum Algorithm {
+    Random,
+    #[default]
+    Fcfs,
+    Sjn,
+    Srt,
+    RoundRobin,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 5] = [
+        Algorithm::Random,
+        Algorithm::Fcfs,
+        Algorithm::Sjn,
+        Algorithm::Srt,
+        Algorithm::RoundRobin,
+    ];
+
+    /// Name shown in the algorithm dropdown.
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Random => "Random",
+            Algorithm::Fcfs => "First Come First Serve (FCFS)",
+            Algorithm::Sjn => "Shortest Job Next (SJN)",
+            Algorithm::Srt => "Shortest Remaining Time (SRT)",
+            Algorithm::RoundRobin => "Round Robin",
+        }
+    }
+
+    /// Name used on the command line and in CSV output.
+    pub fn short_name(self) -> &'static str {
+        match self {
+            Algorithm::Random => "random",
+            Algorithm::Fcfs => "fcfs",
+            Algorithm::Sjn => "sjn",
+            Algorithm::Srt => "srt",
+            Algorithm::RoundRobin => "rr",
+        }
+    }
+
+    /// Whether a running job can be taken off its core before it finishes.
+    pub fn preemptive(self) -> bool {
+        matches!(self, Algorithm::Srt | Algorithm::RoundRobin)
+    }
 
-    for job in &mut scheduled_jobs {
-        job.arrival_time = rng.gen_range(0..10);
-        job.needed_cpu_cycle = rng.gen_range(1..10);
+    /// Accepts either the dropdown name or the short name.
+    pub fn from_name(name: &str) -> Option<Algorithm> {
+        Algorithm::ALL.into_iter().find(|algorithm| {
+            algorithm.name() == name || algorithm.short_name().eq_ignore_ascii_case(name)
+        })
     }
-    let output = scheduled_jobs.clone();
-    output
 }
 
-/// Returns Jobs and Timeline
-pub fn process_scheduler(
-    algorithm: String,
-    mut jobs: Vec<Job>,
-    time_quantum: u32,
-) -> (Vec<Job>, Vec<(String, u32, u32)>) {
-    let mut rng = rand::thread_rng();
-    let mut timeline: Vec<(String, u32, u32)> = Vec::new();
-    let mut job_name;
-    let mut start_time;
-    let mut end_time;
-
-    let mut queue: VecDeque<Job> = Vec::new().into(); // Contains jobs that have arrived but are in queue
-    let mut finished_jobs: Vec<Job> = vec![];
-    let mut cpu_counter: u32 = 0;
-
-    let total_job_count = jobs.len();
-    let mut finished_jobs_count: usize = 0;
-    let mut arrived_jobs_count: usize = 0;
-
-    let mut cpu_status: CPUStatus = CPUStatus::Idle;
-
-    // process_scheduling_algorithm
-    // 1: FCFS
-    // 2: SJN
-    // 3: SRN
-    // 4: Round Robin
-
-    // INITIALIZE JOBS
-    let mut cpu_time_max: u32 = 0;
-    let mut arrival_max: u32 = 0;
-    for job in &mut jobs {
-        cpu_time_max += job.needed_cpu_cycle;
-        job.remaining_cpu_cycle = job.needed_cpu_cycle; // Initialize remaining_cpu_cycle
-        if job.arrival_time > arrival_max {
-            arrival_max = job.arrival_time;
+/// Which of several jobs an algorithm ranks equally goes first, e.g. two jobs arriving
+/// together under FCFS or needing the same time under SJN.
+#[derive(
+    serde::Deserialize, serde::Serialize, Clone, Copy, Default, Debug, PartialEq, Eq, Hash,
+)]
+pub enum TieBreak {
+    /// The job that joined the ready queue first, jobs arriving together in table order.
+    #[default]
+    Fifo,
+    /// The job that joined the ready queue last, jobs arriving together in reverse table order.
+    Lifo,
+    /// The job that arrived earliest, then the one that joined the ready queue first.
+    ArrivalTime,
+    /// The job with the lowest id.
+    JobId,
+    /// The job whose name comes first, A before B before AA.
+    JobName,
+}
+
+impl TieBreak {
+    pub const ALL: [TieBreak; 5] = [
+        TieBreak::Fifo,
+        TieBreak::Lifo,
+        TieBreak::ArrivalTime,
+        TieBreak::JobId,
+        TieBreak::JobName,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TieBreak::Fifo => "First queued (FIFO)",
+            TieBreak::Lifo => "Last queued (LIFO)",
+            TieBreak::ArrivalTime => "Earliest arrival",
+            TieBreak::JobId => "Lowest job id",
+            TieBreak::JobName => "Job name",
         }
     }
 
-    // DONE: Fix crash for when arrival time outlives expected_cpu_max
-    let expected_cpu_max: u32 = if cpu_time_max > arrival_max {
-        cpu_time_max
-    } else {
-        arrival_max
-    };
-    let mut current_job: Job = jobs[0].clone(); // Initialize to first job
-    let mut to_return_jobs: Vec<Job> = jobs.clone();
-
-    // revise if this is revised src/app.rs:156:163
-    let algorithm_num = match algorithm.as_str() {
-        "Random" => 0,
-        "First Come First Serve (FCFS)" => 1,
-        "Shortest Job Next (SJN)" => 2,
-        "Shortest Remaining Time (SRT)" => 3,
-        "Round Robin" => 4,
-        _ => -1, // Unknown, program will panic
-    };
+    /// Name used on the command line.
+    pub fn short_name(self) -> &'static str {
+        match self {
+            TieBreak::Fifo => "fifo",
+            TieBreak::Lifo => "lifo",
+            TieBreak::ArrivalTime => "arrival",
+            TieBreak::JobId => "id",
+            TieBreak::JobName => "name",
+        }
+    }
 
-    // Sort Jobs by Arrival Time
-    jobs.sort_by(|a, b| a.arrival_time.partial_cmp(&b.arrival_time).unwrap());
+    pub fn from_name(name: &str) -> Option<TieBreak> {
+        TieBreak::ALL.into_iter().find(|tie_break| {
+            tie_break.name() == name || tie_break.short_name().eq_ignore_ascii_case(name)
+        })
+    }
 
-    // Randomize (Ignore Parameters)
-    if algorithm_num == 0 {
-        for job in &mut jobs {
-            let current_timeline_index = timeline.len();
-            // println!("{}", current_timeline_index);
-            job_name = job.job_name.to_string(); // idk why this works
-            if current_timeline_index == 0 {
-                start_time = 0;
-            } else {
-                start_time = timeline[current_timeline_index - 1].2;
-            }
+    /// Orders two equally ranked jobs, `a_position` and `b_position` being their places in the queue.
+    pub(crate) fn compare(
+        self,
+        a: &Job,
+        a_position: usize,
+        b: &Job,
+        b_position: usize,
+    ) -> Ordering {
+        let fifo = a_position.cmp(&b_position);
+        match self {
+            TieBreak::Fifo => fifo,
+            TieBreak::Lifo => fifo.reverse(),
+            TieBreak::ArrivalTime => a.arrival_time.cmp(&b.arrival_time).then(fifo),
+            TieBreak::JobId => a.id.cmp(&b.id).then(fifo),
+            TieBreak::JobName => (a.job_name.len(), &a.job_name)
+                .cmp(&(b.job_name.len(), &b.job_name))
+                .then(fifo),
+        }
+    }
+}
+
+/// The rules deciding between jobs the algorithm ranks equally.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct TieBreaking {
+    pub rule: TieBreak,
+    /// Whether a preempted job goes back in the queue ahead of the jobs arriving in the
+    /// same tick rather than behind them.
+    pub preempted_first: bool,
+}
+
+impl TieBreaking {
+    /// The active rules in a sentence, for showing next to results.
+    pub fn describe(&self, algorithm: Algorithm) -> String {
+        let mut description = format!("Tie-break: {}", self.rule.name());
+        if matches!(algorithm, Algorithm::Srt | Algorithm::RoundRobin) {
+            description.push_str(match self.preempted_first {
+                true => "; preempted jobs queue ahead of new arrivals",
+                false => "; new arrivals queue ahead of preempted jobs",
+            });
+        }
+        description
+    }
+}
+
+/// Lets jobs that have waited long jump ahead of jobs the algorithm would otherwise prefer.
+///
+/// Applies to the algorithms that rank the ready queue (FCFS, SJN, SRT): every tick in the
+/// queue lowers the value a job is ranked by, its burst or remaining time, by `rate`,
+/// but by no more than `cap`. A job keeps what it earned while it runs and starts over
+/// when it is preempted.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Aging {
+    pub rate: f64,
+    pub cap: u32,
+}
+
+impl Default for Aging {
+    fn default() -> Self {
+        Self { rate: 0.5, cap: 10 }
+    }
+}
+
+impl Aging {
+    /// How much a job is moved up after waiting `waited` ticks.
+    pub fn bonus(&self, waited: u32) -> u32 {
+        ((waited as f64 * self.rate.max(0.0)) as u32).min(self.cap)
+    }
+
+    pub fn describe(&self) -> String {
+        format!("Aging: {} per tick waited, at most {}", self.rate, self.cap)
+    }
+}
+
+/// Everything besides the workload that decides how a simulation runs.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    pub algorithm: Algorithm,
+    /// Only used by Round Robin.
+    pub time_quantum: u32,
+    /// Number of identical CPUs sharing one ready queue.
+    pub cores: u32,
+    /// Ticks a core spends switching when it moves from one job to another.
+    pub context_switch_cost: u32,
+    /// Seed for the Random algorithm so a run can be reproduced.
+    pub seed: u64,
+    pub tie_breaking: TieBreaking,
+    /// Off unless set.
+    pub aging: Option<Aging>,
+    pub locking: LockProtocol,
+    /// Power draw and frequency scaling, off unless set.
+    pub power: Option<PowerModel>,
+    /// Memory jobs must be admitted into before they can run, off unless set.
+    pub memory: Option<MemoryModel>,
+    /// A queue per job class in place of `algorithm`, off unless set.
+    pub multilevel: Option<MultilevelQueues>,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::Fcfs,
+            time_quantum: 5,
+            cores: 1,
+            context_switch_cost: 0,
+            seed: 0,
+            tie_breaking: TieBreaking::default(),
+            aging: None,
+            locking: LockProtocol::None,
+            power: None,
+            memory: None,
+            multilevel: None,
+        }
+    }
+}
+
+impl SchedulerConfig {
+    /// The algorithm `job` is scheduled by, its class's with multilevel queues.
+    pub fn algorithm_for(&self, job: &Job) -> Algorithm {
+        self.multilevel
+            .as_ref()
+            .map_or(self.algorithm, |multilevel| {
+                multilevel.queue(job.class).algorithm
+            })
+    }
+
+    /// The Round Robin time quantum `job` gets, at least 1.
+    pub fn time_quantum_for(&self, job: &Job) -> u32 {
+        self.multilevel
+            .as_ref()
+            .map_or(self.time_quantum, |multilevel| {
+                multilevel.queue(job.class).time_quantum
+            })
+            .max(1)
+    }
 
-            end_time = &start_time + rng.gen_range(0..5);
-            timeline.push(({ job_name }, { start_time }, { end_time }));
-            job.arrival_time = rng.gen_range(0..10);
-            job.needed_cpu_cycle = rng.gen_range(1..10);
+    /// Whether any job could be scheduled by `algorithm`.
+    fn uses(&self, algorithm: Algorithm) -> bool {
+        match &self.multilevel {
+            Some(multilevel) => JobClass::ALL
+                .into_iter()
+                .any(|class| multilevel.queue(class).algorithm == algorithm),
+            None => self.algorithm == algorithm,
         }
     }
-    // First Come First Serve
-    else if algorithm_num == 1 {
-        while finished_jobs_count < total_job_count {
+}
+
+/// An uninterrupted stretch of a job running on one core.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub job_id: u32,
+    pub job_name: String,
+    pub core: u32,
+    pub start: u32,
+    pub end: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Schedule {
+    /// Finished jobs in completion order.
+    pub jobs: Vec<Job>,
+    pub segments: Vec<Segment>,
+    pub context_switches: u32,
+    /// Every job's moves through the process states, in time order.
+    pub state_changes: Vec<StateChange>,
+    /// When each job held each resource, by when it took it.
+    pub holds: Vec<Hold>,
+    /// Frequency the cores ran at, if the config has a power model.
+    pub frequency: Option<FrequencyLevel>,
+    /// What each core did while not running a job, by when. Only kept with a power model.
+    pub core_states: Vec<CoreSpan>,
+    /// When each job held memory, by when it was admitted.
+    pub memory: Vec<Residency>,
+    /// Jobs swapped out by the medium-term scheduler.
+    pub swaps: u32,
+}
+
+#[derive(Clone, Default)]
+struct Core {
+    running: Option<usize>,
+    last_job: Option<usize>,
+    // the job before the running one, and when the running one was dispatched, so a
+    // job blocking before it runs can be taken back
+    switched_from: Option<usize>,
+    dispatched_at: u32,
+    segment_start: u32,
+    quantum_used: u32,
+    // ticks left before the dispatched job actually starts running
+    switching: u32,
+    // aging bonus the running job had earned when it was dispatched
+    aging_bonus: u32,
+    // ticks the core has been idle for, and whether that got it to sleep
+    idle_ticks: u32,
+    asleep: bool,
+    // whether `switching` includes waking up
+    waking: bool,
+}
+
+struct Simulation<'a> {
+    config: &'a SchedulerConfig,
+    jobs: Vec<Job>,
+    cores: Vec<Core>,
+    queue: VecDeque<usize>, // Contains jobs that have arrived but are in queue
+    // when each job last joined the queue, for aging
+    queued_since: Vec<u32>,
+    // positions of the jobs each job waits for, see `dependencies::predecessors`
+    predecessors: Vec<Vec<usize>>,
+    finished: Vec<bool>,
+    // arrived jobs waiting for their predecessors to finish
+    held: Vec<usize>,
+    // critical sections of each job, see `locking::effective_sections`
+    sections: Vec<Vec<CriticalSection>>,
+    ceilings: HashMap<String, u32>,
+    // each resource taken, by which job and since when
+    holders: HashMap<String, (usize, u32)>,
+    // jobs blocked on each resource
+    blocked: HashMap<String, Vec<usize>>,
+    // jobs whose resource was released in the last tick
+    woken: Vec<usize>,
+    // released jobs waiting to be admitted into memory, and when each joined it
+    job_queue: Vec<usize>,
+    job_queued_since: Vec<u32>,
+    // memory no job holds, since when each job holds memory, and whether it has to be
+    // swapped back in
+    free_memory: u32,
+    admitted_at: Vec<Option<u32>>,
+    swapped_out: Vec<bool>,
+    // jobs being swapped out or in, and when that is done
+    swapping_out: Vec<(u32, usize)>,
+    swapping_in: Vec<(u32, usize)>,
+    schedule: Schedule,
+    rng: StdRng,
+    cpu_counter: u32,
+}
+
+/// Runs `jobs` through the algorithm in `config` one tick at a time.
+///
+/// A job joins the ready queue once it has arrived and the jobs it depends on have
+/// finished. Dependencies are ignored altogether if they form a cycle, which
+/// [`topological_order`](crate::dependencies::topological_order) reports.
+pub fn simulate(jobs: &[Job], config: &SchedulerConfig) -> Schedule {
+    let Some(power) = &config.power else {
+        return simulate_at(jobs, config);
+    };
+    energy::choose_frequency(power, |level| simulate_at_frequency(jobs, config, level)).1
+}
+
+/// Jobs numbered from 1, each given as (name, arrival time, CPU cycles).
+#[cfg(test)]
+pub(crate) fn jobs_from(specs: &[(&str, u32, u32)]) -> Vec<Job> {
+    specs
+        .iter()
+        .zip(1..)
+        .map(|(&(name, arrival_time, needed_cpu_cycle), id)| Job {
+            id,
+            job_name: name.to_string(),
+            arrival_time,
+            needed_cpu_cycle,
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Who ran when in `schedule`, as (job name, start, end).
+#[cfg(test)]
+pub(crate) fn timeline(schedule: &Schedule) -> Vec<(&str, u32, u32)> {
+    schedule
+        .segments
+        .iter()
+        .map(|segment| (segment.job_name.as_str(), segment.start, segment.end))
+        .collect()
+}
+
+/// Simulates `jobs` with the cores at `level` whatever the policy, with the power model
+/// of `config` or the default one if it has none.
+pub fn simulate_at_frequency(
+    jobs: &[Job],
+    config: &SchedulerConfig,
+    level: FrequencyLevel,
+) -> Schedule {
+    let config = SchedulerConfig {
+        power: Some(config.power.clone().unwrap_or_default()),
+        ..config.clone()
+    };
+    let fastest = config.power.as_ref().map_or(level, PowerModel::fastest);
+    let mut schedule = simulate_at(&energy::scaled_jobs(jobs, level, fastest), &config);
+    schedule.frequency = Some(level);
+    schedule
+}
+
+/// Simulates `jobs` with their CPU cycles taken as ticks.
+fn simulate_at(jobs: &[Job], config: &SchedulerConfig) -> Schedule {
+    let mut jobs = jobs.to_vec();
+    ensure_unique_ids(&mut jobs);
+    for job in &mut jobs {
+        job.remaining_cpu_cycle = job.needed_cpu_cycle; // Initialize remaining_cpu_cycle
+    }
+    // Sort Jobs by Arrival Time, ties keep their input order
+    jobs.sort_by_key(|job| job.arrival_time);
+    let predecessors = match dependencies::topological_order(&jobs) {
+        Ok(_) => dependencies::predecessors(&jobs),
+        Err(error) => {
+            log::warn!("{error}, ignoring dependencies");
+            vec![Vec::new(); jobs.len()]
+        }
+    };
+
+    let ceilings = locking::ceilings(&jobs, |job| match config.algorithm_for(job) {
+        Algorithm::Fcfs => job.arrival_time,
+        Algorithm::Sjn | Algorithm::Srt => job.needed_cpu_cycle,
+        Algorithm::Random | Algorithm::RoundRobin => 0,
+    });
+
+    let mut simulation = Simulation {
+        config,
+        queued_since: vec![0; jobs.len()],
+        predecessors,
+        finished: vec![false; jobs.len()],
+        held: Vec::new(),
+        sections: jobs.iter().map(locking::effective_sections).collect(),
+        ceilings,
+        holders: HashMap::new(),
+        blocked: HashMap::new(),
+        woken: Vec::new(),
+        job_queue: Vec::new(),
+        job_queued_since: vec![0; jobs.len()],
+        free_memory: config.memory.as_ref().map_or(0, |memory| memory.size),
+        admitted_at: vec![None; jobs.len()],
+        swapped_out: vec![false; jobs.len()],
+        swapping_out: Vec::new(),
+        swapping_in: Vec::new(),
+        jobs,
+        cores: vec![Core::default(); config.cores.max(1) as usize],
+        queue: VecDeque::new(),
+        schedule: Schedule::default(),
+        rng: StdRng::seed_from_u64(config.seed),
+        cpu_counter: 0,
+    };
+    simulation.run();
+    // a dispatch after a context switch is recorded ahead of time
+    simulation
+        .schedule
+        .state_changes
+        .sort_by_key(|change| change.time);
+    simulation.schedule.holds.sort_by_key(|hold| hold.start);
+    simulation
+        .schedule
+        .memory
+        .sort_by_key(|residency| residency.start);
+    simulation.schedule
+}
+
+impl Simulation<'_> {
+    fn run(&mut self) {
+        let mut arrived_jobs_count = 0;
+        while self.schedule.jobs.len() < self.jobs.len() {
             // Handle Job Arrival, if there are still jobs pending
-            let mut all_jobs_in_check: bool = false;
-            while all_jobs_in_check != true {
-                if arrived_jobs_count < total_job_count {
-                    // .                                             vv prevents crashing when arrival times are misconfigured
-                    if jobs[arrived_jobs_count].arrival_time <= cpu_counter {
-                        println!(
-                            "JOB ARRIVED: {} // CPU_COUNTER: {}",
-                            jobs[arrived_jobs_count].job_name, cpu_counter
-                        );
-                        queue.push_back(jobs[arrived_jobs_count].clone());
-                        arrived_jobs_count += 1;
+            while arrived_jobs_count < self.jobs.len()
+                && self.jobs[arrived_jobs_count].arrival_time <= self.cpu_counter
+            {
+                log::debug!(
+                    "JOB ARRIVED: {} // CPU_COUNTER: {}",
+                    self.jobs[arrived_jobs_count].job_name,
+                    self.cpu_counter
+                );
+                self.held.push(arrived_jobs_count);
+                arrived_jobs_count += 1;
+            }
+            // Jobs whose predecessors are done join the queue, those needing no CPU finish
+            // straight away and may free others in turn
+            let mut arrivals = Vec::new();
+            loop {
+                let (free, held): (Vec<usize>, Vec<usize>) = self.held.iter().partition(|&&job| {
+                    self.predecessors[job]
+                        .iter()
+                        .all(|&predecessor| self.finished[predecessor])
+                });
+                if free.is_empty() {
+                    break;
+                }
+                self.held = held;
+                for job in free {
+                    if self.jobs[job].needed_cpu_cycle == 0 {
+                        self.change_state(job, ProcessState::New, ProcessState::Terminated);
+                        self.finish(job, self.cpu_counter);
+                    } else if self.config.memory.is_some() {
+                        self.job_queued_since[job] = self.cpu_counter;
+                        self.job_queue.push(job);
                     } else {
-                        all_jobs_in_check = true;
+                        self.change_state(job, ProcessState::New, ProcessState::Ready);
+                        arrivals.push(job);
                     }
-                } else {
-                    all_jobs_in_check = true;
                 }
             }
-
-            // If CPU idle and queue is not empty && PROCESS JOB
-            if cpu_status == CPUStatus::Idle && queue.is_empty().not() {
-                queue
-                    .make_contiguous()
-                    .sort_by(|a, b| a.arrival_time.partial_cmp(&b.arrival_time).unwrap());
-                let pop_back = queue.pop_front();
-                if pop_back != None {
-                    current_job = pop_back.expect("Unexpected: pop_back is None.");
-                }
-                println!("JOB WORKING: {}", current_job.job_name);
-                cpu_status = CPUStatus::Working;
+            for job in std::mem::take(&mut self.woken) {
+                self.change_state(job, ProcessState::Waiting, ProcessState::Ready);
+                arrivals.push(job);
             }
-            // IF CPU is Working
-            // Not else if to allow cpu to work upon arrival
-            if cpu_status == CPUStatus::Working {
-                if current_job.remaining_cpu_cycle > 0 {
-                    current_job.remaining_cpu_cycle -= 1;
-                }
-                // If Job just finished; Cannot be else if
-                if current_job.remaining_cpu_cycle == 0 {
-                    println!(
-                        "JOB FINISHED: {} // CPU_COUNTER: {}",
-                        current_job.job_name, cpu_counter
-                    );
-                    // Will not work for other algorithms
-                    timeline.push((
-                        { current_job.job_name.to_string() },
-                        { (cpu_counter + 1) - current_job.needed_cpu_cycle },
-                        { cpu_counter + 1 },
-                    )); // Return already processed
-                    current_job.completion_time = cpu_counter + 1;
-                    current_job.turnaround_time =
-                        current_job.completion_time - current_job.arrival_time;
-                    finished_jobs.push(current_job.clone());
-                    cpu_status = CPUStatus::Idle;
-                    finished_jobs_count += 1;
-
-                    println!(
-                        "FINISHED JOBS COUNT: {} // CPU_COUNTER: {}",
-                        finished_jobs_count, cpu_counter
-                    );
-                }
+            if let Some(memory) = &self.config.memory {
+                self.manage_memory(memory, &mut arrivals);
+            }
+            arrivals.sort_unstable();
+            // Round Robin always takes the front of the queue, so jobs arriving together
+            // queue in tie-break order. The other algorithms break ties when they select.
+            // Each class has a queue of its own, so only the order within a class matters.
+            let tie_break = self.config.tie_breaking.rule;
+            let class = |job: usize| {
+                self.config
+                    .multilevel
+                    .as_ref()
+                    .map(|_| self.jobs[job].class)
+            };
+            arrivals.sort_by(|&a, &b| {
+                class(a).cmp(&class(b)).then_with(|| {
+                    match self.config.algorithm_for(&self.jobs[a]) {
+                        Algorithm::RoundRobin => {
+                            tie_break.compare(&self.jobs[a], a, &self.jobs[b], b)
+                        }
+                        _ => a.cmp(&b),
+                    }
+                })
+            });
+            for &job in &arrivals {
+                self.queued_since[job] = self.cpu_counter;
             }
-            cpu_counter += 1;
+            self.queue.extend(arrivals);
+
+            self.preempt_running();
+            self.fill_idle_cores();
+            self.enter_critical_sections();
+            self.record_core_states();
+            self.work();
+            self.cpu_counter += 1;
+        }
+    }
 
-            if cpu_counter > expected_cpu_max * 2 {
-                panic!("cpu_counter is greater than DOUBLE of EXPECTED_CPU_MAX")
+    /// If a core is idle and the queue is not empty, give it the next job
+    fn fill_idle_cores(&mut self) {
+        for core in 0..self.cores.len() {
+            if self.cores[core].running.is_none() {
+                if let Some(position) = self.select() {
+                    let job = self.queue.remove(position).expect("selected job is queued");
+                    self.dispatch(core, job);
+                }
             }
         }
-        to_return_jobs = finished_jobs.clone();
-
-        // for job in &mut jobs {
-        //     timeline.push(({ job.job_name.to_string() }, { job.arrival_time }, {
-        //         job.needed_cpu_cycle
-        //     }))
-        //     // TODO: Modify jobs, and update numbers
-        // }
-    }
-    // Shortest Job Next (SJN)
-    else if algorithm_num == 2 {
-        to_return_jobs = vec![]; // Reset
-                                 // Example
-                                 //jn:  a    b   c
-                                 //ar:  1    2   3
-                                 //cc:  5    4   2
-                                 // Needs reordering of queue every job arrival
-
-        // 1) Job Arrives, Put Job in Queue
-        // 2) If Something is in Progress, Put Job in Queue
-        // 3) If something cpu is waiting, put next job in queue
-
-        // Get job (already ordered by arrival time)
-        println!("Total Job Count: {}", total_job_count);
-        while finished_jobs_count < total_job_count {
-            // Handle Job Arrival, if there are still jobs pending
-            let mut all_jobs_in_check: bool = false;
-            while all_jobs_in_check != true {
-                if arrived_jobs_count < total_job_count {
-                    // .                                             vv prevents crashing when arrival times are misconfigured
-                    if jobs[arrived_jobs_count].arrival_time <= cpu_counter {
-                        println!(
-                            "JOB ARRIVED: {} // CPU_COUNTER: {}",
-                            jobs[arrived_jobs_count].job_name, cpu_counter
+    }
+
+    /// Running jobs about to enter a critical section take its resource, or give up their
+    /// core and wait if another job holds it. Cores given up go to the next queued job.
+    fn enter_critical_sections(&mut self) {
+        loop {
+            let mut any_blocked = false;
+            for core in 0..self.cores.len() {
+                let (Some(job), 0) = (self.cores[core].running, self.cores[core].switching) else {
+                    continue;
+                };
+                let progress = self.jobs[job].needed_cpu_cycle - self.jobs[job].remaining_cpu_cycle;
+                let Some(section) = self.sections[job]
+                    .iter()
+                    .find(|section| section.offset == progress)
+                else {
+                    continue;
+                };
+                let resource = section.resource.clone();
+                match self.holders.get(&resource) {
+                    Some(&(holder, _)) if holder == job => {}
+                    Some(_) => {
+                        log::debug!(
+                            "JOB BLOCKED: {} // ON: {}",
+                            self.jobs[job].job_name,
+                            resource
                         );
-                        queue.push_back(jobs[arrived_jobs_count].clone());
-                        arrived_jobs_count += 1;
-                    } else {
-                        all_jobs_in_check = true;
+                        let state = &mut self.cores[core];
+                        state.running = None;
+                        // blocked on being switched in for free, so no switch happened
+                        if state.dispatched_at == self.cpu_counter {
+                            if state.switched_from.is_some_and(|previous| previous != job) {
+                                self.schedule.context_switches -= 1;
+                            }
+                            state.last_job = state.switched_from;
+                        }
+                        self.push_segment(core, job, self.cpu_counter);
+                        self.change_state(job, ProcessState::Running, ProcessState::Waiting);
+                        self.blocked.entry(resource).or_default().push(job);
+                        any_blocked = true;
+                    }
+                    None => {
+                        self.holders.insert(resource, (job, self.cpu_counter));
                     }
-                } else {
-                    all_jobs_in_check = true;
                 }
             }
+            if !any_blocked {
+                return;
+            }
+            self.fill_idle_cores();
+        }
+    }
+
+    /// Finishes swaps due this tick, admits jobs from the job queue while they fit, and
+    /// swaps out ready jobs to make room for the job at the head of the job queue.
+    /// Jobs let into the ready queue are added to `arrivals`.
+    fn manage_memory(&mut self, memory: &MemoryModel, arrivals: &mut Vec<usize>) {
+        let now = self.cpu_counter;
+        let (done, swapping_out) = self.swapping_out.iter().partition(|&&(at, _)| at <= now);
+        self.swapping_out = swapping_out;
+        for (_, job) in done {
+            self.release_memory(job, now);
+            self.job_queued_since[job] = now;
+            self.job_queue.push(job);
+        }
+        let (done, swapping_in) = self.swapping_in.iter().partition(|&&(at, _)| at <= now);
+        self.swapping_in = swapping_in;
+        for (_, job) in done {
+            self.swapped_out[job] = false;
+            self.change_state(job, ProcessState::Suspended, ProcessState::Ready);
+            arrivals.push(job);
+        }
 
-            // If CPU idle and queue is not empty && PROCESS JOB
-            if cpu_status == CPUStatus::Idle && queue.is_empty().not() {
-                queue
-                    .make_contiguous()
-                    .sort_by(|a, b| a.needed_cpu_cycle.partial_cmp(&b.needed_cpu_cycle).unwrap());
-                let pop_back = queue.pop_front();
-                if pop_back != None {
-                    current_job = pop_back.expect("Unexpected: pop_back is None.");
+        self.admit(memory, arrivals);
+        let Some(swap_cost) = memory.swap_cost else {
+            return;
+        };
+        let Some(&candidate) = self.job_queue.first() else {
+            return;
+        };
+        let mut freed = self.free_memory
+            + self
+                .swapping_out
+                .iter()
+                .map(|&(_, job)| self.memory_need(job))
+                .sum::<u32>();
+        let need = self.memory_need(candidate);
+        if freed >= need {
+            return;
+        }
+        // only jobs let in before the candidate queued up, or the candidate could push
+        // out a job that was let in for it, most recently let in first
+        let mut victims: Vec<usize> = self
+            .queue
+            .iter()
+            .copied()
+            .filter(|&job| {
+                self.admitted_at[job]
+                    .is_some_and(|admitted| admitted < self.job_queued_since[candidate])
+                    && !self.holders.values().any(|&(holder, _)| holder == job)
+            })
+            .collect();
+        victims.sort_by_key(|&job| std::cmp::Reverse(self.admitted_at[job]));
+        let mut chosen = Vec::new();
+        for job in victims {
+            if freed >= need {
+                break;
+            }
+            freed += self.memory_need(job);
+            chosen.push(job);
+        }
+        if freed < need {
+            return;
+        }
+        for job in chosen {
+            log::debug!(
+                "JOB SWAPPED OUT: {} // CPU_COUNTER: {}",
+                self.jobs[job].job_name,
+                now
+            );
+            self.queue.retain(|&queued| queued != job);
+            self.change_state(job, ProcessState::Ready, ProcessState::Suspended);
+            self.swapped_out[job] = true;
+            self.schedule.swaps += 1;
+            match swap_cost {
+                0 => {
+                    self.release_memory(job, now);
+                    self.job_queued_since[job] = now;
+                    self.job_queue.push(job);
                 }
-                println!("JOB WORKING: {}", current_job.job_name);
-                cpu_status = CPUStatus::Working;
+                _ => self.swapping_out.push((now + swap_cost, job)),
             }
-            // IF CPU is Working
-            // Not else if to allow cpu to work upon arrival
-            if cpu_status == CPUStatus::Working {
-                if current_job.remaining_cpu_cycle > 0 {
-                    current_job.remaining_cpu_cycle -= 1;
+        }
+        self.admit(memory, arrivals);
+    }
+
+    /// Lets jobs from the job queue into memory in the order the long-term scheduler
+    /// picks, while they fit. Jobs that were swapped out first have to be swapped in.
+    fn admit(&mut self, memory: &MemoryModel, arrivals: &mut Vec<usize>) {
+        let now = self.cpu_counter;
+        while let Some(position) = memory.admission.select(
+            self.job_queue.iter().map(|&job| self.memory_need(job)),
+            self.free_memory,
+        ) {
+            let job = self.job_queue.remove(position);
+            self.free_memory -= self.memory_need(job);
+            self.admitted_at[job] = Some(now);
+            match (self.swapped_out[job], memory.swap_cost.unwrap_or(0)) {
+                (false, _) => {
+                    self.change_state(job, ProcessState::New, ProcessState::Ready);
+                    arrivals.push(job);
                 }
-                // If Job just finished; Cannot be else if
-                if current_job.remaining_cpu_cycle == 0 {
-                    println!(
-                        "JOB FINISHED: {} // CPU_COUNTER: {}",
-                        current_job.job_name, cpu_counter
-                    );
-                    // Will not work for other algorithms
-                    timeline.push((
-                        { current_job.job_name.to_string() },
-                        { (cpu_counter + 1) - current_job.needed_cpu_cycle },
-                        { cpu_counter + 1 },
-                    )); // Return already processed
-                    current_job.completion_time = cpu_counter + 1;
-                    current_job.turnaround_time =
-                        current_job.completion_time - current_job.arrival_time;
-                    finished_jobs.push(current_job.clone());
-                    cpu_status = CPUStatus::Idle;
-                    finished_jobs_count += 1;
-
-                    println!(
-                        "FINISHED JOBS COUNT: {} // CPU_COUNTER: {}",
-                        finished_jobs_count, cpu_counter
-                    );
+                (true, 0) => {
+                    self.swapped_out[job] = false;
+                    self.change_state(job, ProcessState::Suspended, ProcessState::Ready);
+                    arrivals.push(job);
                 }
+                (true, cost) => self.swapping_in.push((now + cost, job)),
             }
-            cpu_counter += 1;
+        }
+    }
 
-            if cpu_counter > expected_cpu_max * 2 {
-                panic!("cpu_counter is greater than DOUBLE of EXPECTED_CPU_MAX")
-            }
-            to_return_jobs = finished_jobs.clone();
+    /// Memory `job` takes, no more than there is.
+    fn memory_need(&self, job: usize) -> u32 {
+        let size = self.config.memory.as_ref().map_or(0, |memory| memory.size);
+        self.jobs[job].memory.min(size)
+    }
+
+    fn release_memory(&mut self, job: usize, end: u32) {
+        if let Some(start) = self.admitted_at[job].take() {
+            self.free_memory += self.memory_need(job);
+            self.schedule.memory.push(Residency {
+                job_id: self.jobs[job].id,
+                memory: self.memory_need(job),
+                start,
+                end,
+            });
         }
     }
-    // Shortest Remaining Time (SRT)
-    else if algorithm_num == 3 {
-        let mut time_start_work_uninterrupted: u32 = 0;
-        let mut time_end_work_uninterrupted: u32;
-        println!("Total Job Count: {}", total_job_count);
-        while finished_jobs_count < total_job_count {
-            // Handle Job Arrival, if there are still jobs pending
-            let mut all_jobs_in_check: bool = false;
-            while all_jobs_in_check != true {
-                if arrived_jobs_count < total_job_count {
-                    // .                                             vv prevents crashing when arrival times are misconfigured
-                    if jobs[arrived_jobs_count].arrival_time <= cpu_counter {
-                        println!(
-                            "JOB ARRIVED: {} // CPU_COUNTER: {}",
-                            jobs[arrived_jobs_count].job_name, cpu_counter
-                        );
-                        queue.push_back(jobs[arrived_jobs_count].clone());
-                        arrived_jobs_count += 1;
-                    } else {
-                        all_jobs_in_check = true;
-                    }
-                } else {
-                    all_jobs_in_check = true;
-                }
-            }
 
-            // If CPU idle and queue is not empty && PROCESS JOB
-            if cpu_status == CPUStatus::Idle && queue.is_empty().not() {
-                queue.make_contiguous().sort_by(|a, b| {
-                    a.remaining_cpu_cycle
-                        .partial_cmp(&b.remaining_cpu_cycle)
-                        .unwrap()
-                });
-                // TODO: Not sure if necessary
-                let pop_back = queue.pop_front();
-                if pop_back != None {
-                    current_job = pop_back.expect("Unexpected: pop_back is None.");
+    /// Notes what the cores not running a job are doing this tick, and puts cores that
+    /// have been idle long enough to sleep.
+    fn record_core_states(&mut self) {
+        let Some(power) = &self.config.power else {
+            return;
+        };
+        for (core, state) in self.cores.iter_mut().enumerate() {
+            let core_state = match state.running {
+                Some(_) if state.switching > 0 => match state.waking {
+                    true => CoreState::Waking,
+                    false => CoreState::Switching,
+                },
+                Some(_) => {
+                    state.idle_ticks = 0;
+                    state.asleep = false;
+                    state.waking = false;
+                    continue;
                 }
-                time_start_work_uninterrupted = cpu_counter;
-                cpu_status = CPUStatus::Working;
-            }
-
-            // IF CPU is Working and There's a Job with Shorter CPU Time
-            if cpu_status == CPUStatus::Working {
-                // SORT by accending remaining_cpu_cycle
-                queue.make_contiguous().sort_by(|a, b| {
-                    a.remaining_cpu_cycle
-                        .partial_cmp(&b.remaining_cpu_cycle)
-                        .unwrap()
-                });
-                // INTERRUPT JOB if queue job has more remaining
-                if queue.is_empty().not() && current_job.remaining_cpu_cycle > 0 {
-                    if current_job.remaining_cpu_cycle > queue[0].remaining_cpu_cycle {
-                        time_end_work_uninterrupted = cpu_counter;
-                        timeline.push((
-                            { current_job.job_name.to_string() },
-                            { time_start_work_uninterrupted },
-                            { time_end_work_uninterrupted },
-                        ));
-
-                        queue.push_back(current_job);
-                        current_job = queue.pop_front().unwrap();
-                        time_start_work_uninterrupted = cpu_counter;
+                None => {
+                    state.asleep = power.asleep(state.idle_ticks);
+                    state.idle_ticks += 1;
+                    match state.asleep {
+                        true => CoreState::Asleep,
+                        false => CoreState::Idle,
                     }
                 }
-                // WORKING
-                if current_job.remaining_cpu_cycle > 0 {
-                    println!("JOB WORKING: {}", current_job.job_name);
-                    current_job.remaining_cpu_cycle -= 1;
-                }
-
-                // IF JOB JUST FINISHED
-                if current_job.remaining_cpu_cycle == 0 {
-                    println!(
-                        "JOB FINISHED: {} // CPU_COUNTER: {}",
-                        current_job.job_name, cpu_counter
-                    );
-
-                    // Will not work for other algorithms
-                    // Return already processed
-                    time_end_work_uninterrupted = cpu_counter + 1;
-                    timeline.push((
-                        { current_job.job_name.to_string() },
-                        { time_start_work_uninterrupted },
-                        { time_end_work_uninterrupted },
-                    ));
-                    current_job.completion_time = cpu_counter + 1;
-                    current_job.turnaround_time =
-                        current_job.completion_time - current_job.arrival_time;
-
-                    finished_jobs.push(current_job.clone());
-                    // if !queue.is_empty() {
-                    //     queue.make_contiguous().sort_by(|a, b| {
-                    //         a.remaining_cpu_cycle
-                    //             .partial_cmp(&b.remaining_cpu_cycle)
-                    //             .unwrap()
-                    //     });
-                    //     time_start_work_uninterrupted = cpu_counter + 1;
-                    //     current_job = queue.pop_front().unwrap();
-                    // }
-
-                    finished_jobs_count += 1;
-
-                    println!(
-                        "FINISHED JOBS COUNT: {} // CPU_COUNTER: {}",
-                        finished_jobs_count, cpu_counter
-                    );
+            };
+            let core = core as u32;
+            match self
+                .schedule
+                .core_states
+                .iter_mut()
+                .rev()
+                .find(|span| span.core == core)
+            {
+                Some(span) if span.state == core_state && span.end == self.cpu_counter => {
+                    span.end += 1
                 }
+                _ => self.schedule.core_states.push(CoreSpan {
+                    core,
+                    state: core_state,
+                    start: self.cpu_counter,
+                    end: self.cpu_counter + 1,
+                }),
             }
+        }
+    }
 
-            if current_job.remaining_cpu_cycle == 0 {
-                cpu_status = CPUStatus::Idle;
-            }
+    /// Lets go of the resource whose critical section `job` has just run to the end of,
+    /// waking the jobs blocked on it at the start of the next tick.
+    fn leave_critical_section(&mut self, job: usize) {
+        let progress = self.jobs[job].needed_cpu_cycle - self.jobs[job].remaining_cpu_cycle;
+        let Some(section) = self.sections[job]
+            .iter()
+            .find(|section| section.end() == progress)
+        else {
+            return;
+        };
+        let resource = section.resource.clone();
+        if let Some((_, start)) = self.holders.remove(&resource) {
+            self.schedule.holds.push(Hold {
+                job_id: self.jobs[job].id,
+                resource: resource.clone(),
+                start,
+                end: self.cpu_counter + 1,
+            });
+        }
+        self.woken
+            .extend(self.blocked.remove(&resource).unwrap_or_default());
+    }
 
-            cpu_counter += 1;
+    /// The key the algorithm ranks `job` by before aging, lower going first.
+    fn priority(&self, job: usize) -> u32 {
+        let job = &self.jobs[job];
+        match self.config.algorithm_for(job) {
+            Algorithm::Fcfs => job.arrival_time,
+            Algorithm::Sjn => job.needed_cpu_cycle,
+            Algorithm::Srt => job.remaining_cpu_cycle,
+            Algorithm::Random | Algorithm::RoundRobin => 0,
+        }
+    }
 
-            if cpu_counter > expected_cpu_max * 2 {
-                panic!("cpu_counter is greater than DOUBLE of EXPECTED_CPU_MAX")
+    /// The priority of `job`, raised by the locking protocol while it holds resources.
+    fn effective_priority(&self, job: usize) -> u32 {
+        let own = self.priority(job);
+        let held = self
+            .holders
+            .iter()
+            .filter(|(_, &(holder, _))| holder == job)
+            .map(|(resource, _)| resource);
+        match self.config.locking {
+            LockProtocol::None => own,
+            LockProtocol::Inheritance => held
+                .filter_map(|resource| self.blocked.get(resource))
+                .flatten()
+                .map(|&waiting| self.priority(waiting))
+                .fold(own, u32::min),
+            LockProtocol::Ceiling => held
+                .filter_map(|resource| self.ceilings.get(resource))
+                .fold(own, |priority, &ceiling| priority.min(ceiling)),
+        }
+    }
+
+    /// How soon `job`'s class gets the CPU this tick, 0 for the first. Without multilevel
+    /// queues every job is in the one queue.
+    fn class_rank(&self, job: usize) -> usize {
+        let Some(multilevel) = &self.config.multilevel else {
+            return 0;
+        };
+        let class = self.jobs[job].class;
+        multilevel
+            .order(self.cpu_counter)
+            .iter()
+            .position(|&ranked| ranked == class)
+            .unwrap_or(0)
+    }
+
+    /// Position in the queue of the job the algorithm wants to run next, from the first
+    /// class with a job queued.
+    fn select(&mut self) -> Option<usize> {
+        let rank = self.queue.iter().map(|&job| self.class_rank(job)).min()?;
+        let positions: Vec<usize> = (0..self.queue.len())
+            .filter(|&position| self.class_rank(self.queue[position]) == rank)
+            .collect();
+        let jobs = &self.jobs;
+        let tie_break = self.config.tie_breaking.rule;
+        let position = match self.config.algorithm_for(&jobs[self.queue[positions[0]]]) {
+            Algorithm::Random => positions[self.rng.gen_range(0..positions.len())],
+            // preempted jobs go to the back of the queue
+            Algorithm::RoundRobin => positions[0],
+            Algorithm::Fcfs | Algorithm::Sjn | Algorithm::Srt => {
+                position_of_min(&self.queue, &positions, jobs, tie_break, |job| {
+                    self.effective_priority(job)
+                        .saturating_sub(self.aging_bonus(job))
+                })
             }
+        };
+        Some(position)
+    }
 
-            to_return_jobs = finished_jobs.clone();
+    /// What aging has earned a queued job so far, 0 without aging.
+    fn aging_bonus(&self, job: usize) -> u32 {
+        self.config.aging.map_or(0, |aging| {
+            aging.bonus(self.cpu_counter - self.queued_since[job])
+        })
+    }
+
+    fn preempt_running(&mut self) {
+        if self.config.multilevel.is_some() {
+            self.preempt_lower_classes();
+        }
+        if self.config.uses(Algorithm::Srt) {
+            self.preempt_longer_jobs();
+        }
+        if self.config.uses(Algorithm::RoundRobin) {
+            self.preempt_expired_quanta();
         }
     }
-    // Round Robin
-    else if algorithm_num == 4 {
-        let mut time_start_work_uninterrupted: u32 = 0;
-        let mut time_end_work_uninterrupted: u32;
-        let mut just_popped: bool;
-        println!("Total Job Count: {}", total_job_count);
-        while finished_jobs_count < total_job_count {
-            // Handle Job Arrival, if there are still jobs pending
-            let mut all_jobs_in_check: bool = false;
-            while all_jobs_in_check != true {
-                if arrived_jobs_count < total_job_count {
-                    // .                                             vv prevents crashing when arrival times are misconfigured
-                    if jobs[arrived_jobs_count].arrival_time <= cpu_counter {
-                        println!(
-                            "JOB ARRIVED: {} // CPU_COUNTER: {}",
-                            jobs[arrived_jobs_count].job_name, cpu_counter
-                        );
-                        queue.push_back(jobs[arrived_jobs_count].clone());
-                        arrived_jobs_count += 1;
-                    } else {
-                        all_jobs_in_check = true;
+
+    /// Interrupts the running job with the most remaining work if a queued job of the
+    /// same class needs less.
+    fn preempt_longer_jobs(&mut self) {
+        loop {
+            let Some(position) = self.select() else {
+                break;
+            };
+            let candidate = self.queue[position];
+            if self.config.algorithm_for(&self.jobs[candidate]) != Algorithm::Srt {
+                break;
+            }
+            let rank = self.class_rank(candidate);
+            let mut victim: Option<(usize, u32)> = None;
+            for (core, state) in self.cores.iter().enumerate() {
+                if let (Some(job), 0) = (state.running, state.switching) {
+                    if self.class_rank(job) != rank {
+                        continue;
+                    }
+                    let remaining = self
+                        .effective_priority(job)
+                        .saturating_sub(state.aging_bonus);
+                    if victim.map_or(true, |(_, most)| remaining > most) {
+                        victim = Some((core, remaining));
                     }
-                } else {
-                    all_jobs_in_check = true;
                 }
             }
-            just_popped = false;
-            // If CPU idle and queue is not empty && PROCESS JOB
-            if cpu_status == CPUStatus::Idle && queue.is_empty().not() {
-                // TODO: Not sure if necessary
-                let pop_back = queue.pop_front();
-                if pop_back != None {
-                    current_job = pop_back.expect("Unexpected: pop_back is None.");
+            match victim {
+                Some((core, remaining))
+                    if self
+                        .effective_priority(candidate)
+                        .saturating_sub(self.aging_bonus(candidate))
+                        < remaining =>
+                {
+                    self.queue.remove(position);
+                    self.preempt(core);
+                    self.dispatch(core, candidate);
                 }
-                time_start_work_uninterrupted = cpu_counter;
-                cpu_status = CPUStatus::Working;
-                just_popped = true;
+                _ => break,
+            }
+        }
+    }
+
+    /// Preempts Round Robin jobs that used up their time quantum, new arrivals are
+    /// already queued.
+    fn preempt_expired_quanta(&mut self) {
+        for core in 0..self.cores.len() {
+            let state = &self.cores[core];
+            let Some(job) = state.running else {
+                continue;
+            };
+            if self.config.algorithm_for(&self.jobs[job]) != Algorithm::RoundRobin
+                || state.switching > 0
+                || state.quantum_used < self.config.time_quantum_for(&self.jobs[job])
+            {
+                continue;
             }
+            let rank = self.class_rank(job);
+            let waiting = self
+                .queue
+                .iter()
+                .take(self.queued_before_arrivals())
+                .any(|&queued| self.class_rank(queued) == rank);
+            if !waiting {
+                // Nobody is waiting ahead of it, the job gets a fresh quantum
+                self.cores[core].quantum_used = 0;
+                continue;
+            }
+            self.preempt(core);
+            let position = self.select().expect("queue is not empty");
+            let job = self.queue.remove(position).expect("selected job is queued");
+            self.dispatch(core, job);
+        }
+    }
 
-            if cpu_status == CPUStatus::Working {
-                // Handle preempting jobs on time quantum
-                // e.g. time_quantum = 3;; 0, 1, 2, 3, 4, 5, 6
-                //                                  ^        ^
-                if cpu_counter % time_quantum == 0 && !just_popped {
-                    if queue.is_empty().not() {
-                        time_end_work_uninterrupted = cpu_counter; // preempt happens before work
-                        timeline.push((
-                            { current_job.job_name.to_string() },
-                            { time_start_work_uninterrupted },
-                            { time_end_work_uninterrupted },
-                        ));
-                        queue.push_back(current_job);
-                        current_job = queue.pop_front().unwrap();
-                        time_start_work_uninterrupted = cpu_counter; // new start
-                    }
-                }
+    /// Gives the cores running jobs from a lower class than a queued one to the queued
+    /// jobs, the lowest class first. A job keeps its core until it has run for a tick, or
+    /// slices shorter than a context switch would have the queues take the CPU from each
+    /// other forever without any job running.
+    fn preempt_lower_classes(&mut self) {
+        loop {
+            let Some(position) = self.select() else {
+                break;
+            };
+            let candidate = self.queue[position];
+            let rank = self.class_rank(candidate);
+            let victim = (0..self.cores.len())
+                .filter_map(|core| {
+                    let state = &self.cores[core];
+                    let job = state
+                        .running
+                        .filter(|_| state.segment_start < self.cpu_counter)?;
+                    Some((core, self.class_rank(job)))
+                })
+                .filter(|&(_, running)| running > rank)
+                .max_by_key(|&(_, running)| running);
+            let Some((core, _)) = victim else {
+                break;
+            };
+            self.queue.remove(position);
+            self.preempt(core);
+            self.dispatch(core, candidate);
+        }
+    }
 
-                // WORKING
-                if current_job.remaining_cpu_cycle > 0 {
-                    println!("JOB WORKING: {}", current_job.job_name);
-                    current_job.remaining_cpu_cycle -= 1;
-                }
+    fn dispatch(&mut self, core: usize, job: usize) {
+        log::debug!("JOB WORKING: {} // CORE: {}", self.jobs[job].job_name, core);
+        let aging_bonus = self.aging_bonus(job);
+        let state = &mut self.cores[core];
+        let mut cost = 0;
+        if state.last_job.is_some_and(|last_job| last_job != job) {
+            self.schedule.context_switches += 1;
+            cost = self.config.context_switch_cost;
+        }
+        state.waking = false;
+        if let Some(power) = self.config.power.as_ref().filter(|_| state.asleep) {
+            cost += power.wake_latency;
+            state.waking = power.wake_latency > 0;
+        }
+        state.running = Some(job);
+        state.switched_from = state.last_job;
+        state.last_job = Some(job);
+        state.dispatched_at = self.cpu_counter;
+        state.switching = cost;
+        state.segment_start = self.cpu_counter + cost;
+        state.quantum_used = 0;
+        state.aging_bonus = aging_bonus;
+        self.schedule.state_changes.push(StateChange {
+            time: self.cpu_counter + cost,
+            job_id: self.jobs[job].id,
+            from: ProcessState::Ready,
+            to: ProcessState::Running,
+        });
+    }
 
-                // IF JOB JUST FINISHED
-                if current_job.remaining_cpu_cycle == 0 {
-                    println!(
-                        "JOB FINISHED: {} // CPU_COUNTER: {}",
-                        current_job.job_name, cpu_counter
-                    );
-
-                    // Will not work for other algorithms
-                    // Return already processed
-                    time_end_work_uninterrupted = cpu_counter + 1;
-                    timeline.push((
-                        { current_job.job_name.to_string() },
-                        { time_start_work_uninterrupted },
-                        { time_end_work_uninterrupted },
-                    ));
-                    current_job.completion_time = cpu_counter + 1;
-                    current_job.turnaround_time =
-                        current_job.completion_time - current_job.arrival_time;
-                    finished_jobs.push(current_job.clone());
-                    // if !queue.is_empty() {
-                    //     queue.make_contiguous().sort_by(|a, b| {
-                    //         a.remaining_cpu_cycle
-                    //             .partial_cmp(&b.remaining_cpu_cycle)
-                    //             .unwrap()
-                    //     });
-                    //     time_start_work_uninterrupted = cpu_counter + 1;
-                    //     current_job = queue.pop_front().unwrap();
-                    // }
-
-                    finished_jobs_count += 1;
-
-                    println!(
-                        "FINISHED JOBS COUNT: {} // CPU_COUNTER: {}",
-                        finished_jobs_count, cpu_counter
-                    );
+    fn change_state(&mut self, job: usize, from: ProcessState, to: ProcessState) {
+        self.schedule.state_changes.push(StateChange {
+            time: self.cpu_counter,
+            job_id: self.jobs[job].id,
+            from,
+            to,
+        });
+    }
+
+    /// Puts the running job back at the end of the queue, or ahead of the jobs that
+    /// arrived this tick if preempted jobs go first.
+    fn preempt(&mut self, core: usize) {
+        if let Some(job) = self.cores[core].running.take() {
+            self.push_segment(core, job, self.cpu_counter);
+            if self.cores[core].segment_start < self.cpu_counter {
+                self.change_state(job, ProcessState::Running, ProcessState::Ready);
+            } else {
+                // preempted before it ran, right as it was switched in
+                let id = self.jobs[job].id;
+                let dispatched =
+                    self.schedule.state_changes.iter().rposition(|change| {
+                        change.job_id == id && change.to == ProcessState::Running
+                    });
+                if let Some(position) = dispatched {
+                    self.schedule.state_changes.remove(position);
                 }
             }
-            if current_job.remaining_cpu_cycle == 0 {
-                cpu_status = CPUStatus::Idle;
+            let position = self.queued_before_arrivals();
+            self.queue.insert(position, job);
+            self.queued_since[job] = self.cpu_counter;
+        }
+    }
+
+    /// Where a preempted job rejoins the queue.
+    fn queued_before_arrivals(&self) -> usize {
+        if !self.config.tie_breaking.preempted_first {
+            return self.queue.len();
+        }
+        // this tick's arrivals are at the back, nothing queued earlier arrived this late
+        self.queue
+            .iter()
+            .position(|&job| self.jobs[job].arrival_time == self.cpu_counter)
+            .unwrap_or(self.queue.len())
+    }
+
+    /// Advances every core by one tick.
+    fn work(&mut self) {
+        for core in 0..self.cores.len() {
+            if self.cores[core].switching > 0 {
+                self.cores[core].switching -= 1;
+                continue;
             }
-            cpu_counter += 1;
+            let Some(job) = self.cores[core].running else {
+                continue;
+            };
+            self.jobs[job].remaining_cpu_cycle -= 1;
+            self.cores[core].quantum_used += 1;
+            self.leave_critical_section(job);
 
-            if cpu_counter > expected_cpu_max * 2 {
-                panic!("cpu_counter is greater than DOUBLE of EXPECTED_CPU_MAX")
+            // IF JOB JUST FINISHED
+            if self.jobs[job].remaining_cpu_cycle == 0 {
+                self.push_segment(core, job, self.cpu_counter + 1);
+                self.cores[core].running = None;
+                self.schedule.state_changes.push(StateChange {
+                    time: self.cpu_counter + 1,
+                    job_id: self.jobs[job].id,
+                    from: ProcessState::Running,
+                    to: ProcessState::Terminated,
+                });
+                self.finish(job, self.cpu_counter + 1);
             }
         }
-        to_return_jobs = finished_jobs.clone();
-    } else {
-        panic!("Unexpected: algorithm_num is -1")
     }
 
+    fn finish(&mut self, job: usize, completion_time: u32) {
+        self.finished[job] = true;
+        self.release_memory(job, completion_time);
+        let job = &mut self.jobs[job];
+        job.completion_time = completion_time;
+        job.turnaround_time = job.completion_time - job.arrival_time;
+        log::debug!(
+            "JOB FINISHED: {} // CPU_COUNTER: {}",
+            job.job_name,
+            self.cpu_counter
+        );
+        self.schedule.jobs.push(job.clone());
+    }
+
+    /// Records `job` running on `core` until `end`, carrying on the core's last segment if
+    /// that was the same job, e.g. when the job that preempted it blocked straight away.
+    fn push_segment(&mut self, core: usize, job: usize, end: u32) {
+        let start = self.cores[core].segment_start;
+        if start >= end {
+            return;
+        }
+        let id = self.jobs[job].id;
+        let last = self
+            .schedule
+            .segments
+            .iter_mut()
+            .rev()
+            .find(|segment| segment.core == core as u32);
+        match last {
+            Some(last) if last.job_id == id && last.end == start => last.end = end,
+            _ => self.schedule.segments.push(Segment {
+                job_id: self.jobs[job].id,
+                job_name: self.jobs[job].job_name.clone(),
+                core: core as u32,
+                start,
+                end,
+            }),
+        }
+    }
+}
+
+/// Which of `positions` in `queue` holds the job with the smallest key, ties broken by
+/// `tie_break`.
+fn position_of_min(
+    queue: &VecDeque<usize>,
+    positions: &[usize],
+    jobs: &[Job],
+    tie_break: TieBreak,
+    key: impl Fn(usize) -> u32,
+) -> usize {
+    positions
+        .iter()
+        .copied()
+        .min_by(|&a, &b| {
+            key(queue[a])
+                .cmp(&key(queue[b]))
+                .then_with(|| tie_break.compare(&jobs[queue[a]], a, &jobs[queue[b]], b))
+        })
+        .unwrap_or(0)
+}
+
+/// Returns Jobs and Timeline
+pub fn process_scheduler(
+    algorithm: String,
+    jobs: Vec<Job>,
+    time_quantum: u32,
+) -> (Vec<Job>, Vec<(String, u32, u32)>) {
+    let config = SchedulerConfig {
+        algorithm: Algorithm::from_name(&algorithm).expect("Unexpected: unknown algorithm"),
+        time_quantum,
+        ..Default::default()
+    };
+    let schedule = simulate(&jobs, &config);
+    let timeline = schedule
+        .segments
+        .into_iter()
+        .map(|segment| (segment.job_name, segment.start, segment.end))
+        .collect();
+
     // Return Jobs and Timeline
-    (to_return_jobs, timeline)
+    (schedule.jobs, timeline)
 }
 
 // Function Tests
@@ -576,34 +1321,39 @@ pub fn process_scheduler(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory::{memory_used, Admission};
+    use crate::multilevel::{class_ticks, ClassQueue, QueueSharing};
+    use proptest::prelude::*;
+    use proptest::test_runner::TestCaseError;
 
     #[test]
     fn sjn_works() {
-        let mut jobs: Vec<Job> = vec![];
-        jobs.push(Job {
-            job_name: "A".to_string(),
-            arrival_time: 0,
-            needed_cpu_cycle: 5,
-            remaining_cpu_cycle: 5,
-            completion_time: 0,
-            turnaround_time: 0,
-        });
-        jobs.push(Job {
-            job_name: "B".to_string(),
-            arrival_time: 2,
-            needed_cpu_cycle: 5,
-            remaining_cpu_cycle: 5,
-            completion_time: 0,
-            turnaround_time: 0,
-        });
-        jobs.push(Job {
-            job_name: "C".to_string(),
-            arrival_time: 3,
-            needed_cpu_cycle: 3,
-            remaining_cpu_cycle: 3,
-            completion_time: 0,
-            turnaround_time: 0,
-        });
+        let jobs: Vec<Job> = vec![
+            Job {
+                id: 1,
+                job_name: "A".to_string(),
+                arrival_time: 0,
+                needed_cpu_cycle: 5,
+                remaining_cpu_cycle: 5,
+                ..Default::default()
+            },
+            Job {
+                id: 2,
+                job_name: "B".to_string(),
+                arrival_time: 2,
+                needed_cpu_cycle: 5,
+                remaining_cpu_cycle: 5,
+                ..Default::default()
+            },
+            Job {
+                id: 3,
+                job_name: "C".to_string(),
+                arrival_time: 3,
+                needed_cpu_cycle: 3,
+                remaining_cpu_cycle: 3,
+                ..Default::default()
+            },
+        ];
         let (_, timeline) = process_scheduler("Shortest Job Next (SJN)".to_string(), jobs, 0);
         assert_eq!(
             timeline,
@@ -617,39 +1367,40 @@ mod tests {
 
     #[test]
     fn srt_works() {
-        let mut jobs: Vec<Job> = vec![];
-        jobs.push(Job {
-            job_name: "A".to_string(),
-            arrival_time: 0,
-            needed_cpu_cycle: 6,
-            remaining_cpu_cycle: 6,
-            completion_time: 0,
-            turnaround_time: 0,
-        });
-        jobs.push(Job {
-            job_name: "B".to_string(),
-            arrival_time: 1,
-            needed_cpu_cycle: 3,
-            remaining_cpu_cycle: 3,
-            completion_time: 0,
-            turnaround_time: 0,
-        });
-        jobs.push(Job {
-            job_name: "C".to_string(),
-            arrival_time: 2,
-            needed_cpu_cycle: 1,
-            remaining_cpu_cycle: 1,
-            completion_time: 0,
-            turnaround_time: 0,
-        });
-        jobs.push(Job {
-            job_name: "D".to_string(),
-            arrival_time: 3,
-            needed_cpu_cycle: 4,
-            remaining_cpu_cycle: 4,
-            completion_time: 0,
-            turnaround_time: 0,
-        });
+        let jobs: Vec<Job> = vec![
+            Job {
+                id: 1,
+                job_name: "A".to_string(),
+                arrival_time: 0,
+                needed_cpu_cycle: 6,
+                remaining_cpu_cycle: 6,
+                ..Default::default()
+            },
+            Job {
+                id: 2,
+                job_name: "B".to_string(),
+                arrival_time: 1,
+                needed_cpu_cycle: 3,
+                remaining_cpu_cycle: 3,
+                ..Default::default()
+            },
+            Job {
+                id: 3,
+                job_name: "C".to_string(),
+                arrival_time: 2,
+                needed_cpu_cycle: 1,
+                remaining_cpu_cycle: 1,
+                ..Default::default()
+            },
+            Job {
+                id: 4,
+                job_name: "D".to_string(),
+                arrival_time: 3,
+                needed_cpu_cycle: 4,
+                remaining_cpu_cycle: 4,
+                ..Default::default()
+            },
+        ];
         let (_, timeline) = process_scheduler("Shortest Remaining Time (SRT)".to_string(), jobs, 0);
         assert_eq!(
             timeline,
@@ -666,39 +1417,40 @@ mod tests {
 
     #[test]
     fn rr_works() {
-        let mut jobs: Vec<Job> = vec![];
-        jobs.push(Job {
-            job_name: "A".to_string(),
-            arrival_time: 0,
-            needed_cpu_cycle: 8,
-            remaining_cpu_cycle: 8,
-            completion_time: 0,
-            turnaround_time: 0,
-        });
-        jobs.push(Job {
-            job_name: "B".to_string(),
-            arrival_time: 1,
-            needed_cpu_cycle: 4,
-            remaining_cpu_cycle: 4,
-            completion_time: 0,
-            turnaround_time: 0,
-        });
-        jobs.push(Job {
-            job_name: "C".to_string(),
-            arrival_time: 2,
-            needed_cpu_cycle: 9,
-            remaining_cpu_cycle: 9,
-            completion_time: 0,
-            turnaround_time: 0,
-        });
-        jobs.push(Job {
-            job_name: "D".to_string(),
-            arrival_time: 3,
-            needed_cpu_cycle: 5,
-            remaining_cpu_cycle: 5,
-            completion_time: 0,
-            turnaround_time: 0,
-        });
+        let jobs: Vec<Job> = vec![
+            Job {
+                id: 1,
+                job_name: "A".to_string(),
+                arrival_time: 0,
+                needed_cpu_cycle: 8,
+                remaining_cpu_cycle: 8,
+                ..Default::default()
+            },
+            Job {
+                id: 2,
+                job_name: "B".to_string(),
+                arrival_time: 1,
+                needed_cpu_cycle: 4,
+                remaining_cpu_cycle: 4,
+                ..Default::default()
+            },
+            Job {
+                id: 3,
+                job_name: "C".to_string(),
+                arrival_time: 2,
+                needed_cpu_cycle: 9,
+                remaining_cpu_cycle: 9,
+                ..Default::default()
+            },
+            Job {
+                id: 4,
+                job_name: "D".to_string(),
+                arrival_time: 3,
+                needed_cpu_cycle: 5,
+                remaining_cpu_cycle: 5,
+                ..Default::default()
+            },
+        ];
         let (_, timeline) = process_scheduler("Round Robin".to_string(), jobs, 4);
         assert_eq!(
             timeline,
@@ -714,4 +1466,493 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn rr_quantum_starts_at_dispatch() {
+        // B is dispatched at 1 when A finishes and still gets all 3 ticks of its quantum
+        let jobs = jobs_from(&[("A", 0, 1), ("B", 0, 4), ("C", 0, 4)]);
+        let (_, timeline) = process_scheduler("Round Robin".to_string(), jobs, 3);
+        assert_eq!(
+            timeline,
+            [
+                ("A".to_string(), 0, 1),
+                ("B".to_string(), 1, 4),
+                ("C".to_string(), 4, 7),
+                ("B".to_string(), 7, 8),
+                ("C".to_string(), 8, 9),
+            ]
+        )
+    }
+
+    #[test]
+    fn random_runs_ready_jobs_to_completion() {
+        let jobs = jobs_from(&[("A", 0, 3), ("B", 1, 2), ("C", 1, 4), ("D", 6, 1)]);
+        let config = SchedulerConfig {
+            algorithm: Algorithm::Random,
+            seed: 7,
+            ..Default::default()
+        };
+        let schedule = simulate(&jobs, &config);
+        assert_eq!(schedule, simulate(&jobs, &config));
+        // one uninterrupted segment per job, never before it arrives
+        assert_eq!(schedule.segments.len(), jobs.len());
+        for segment in &schedule.segments {
+            let job = jobs
+                .iter()
+                .find(|job| job.job_name == segment.job_name)
+                .unwrap();
+            assert!(segment.start >= job.arrival_time);
+            assert_eq!(segment.end - segment.start, job.needed_cpu_cycle);
+        }
+    }
+
+    #[test]
+    fn job_builder_keeps_names_and_ids() {
+        let mut jobs = job_builder(&[], 3);
+        jobs.swap(0, 2);
+        jobs[0].job_name = "backup".to_string();
+        let jobs = job_builder(&jobs, 2);
+        let jobs = job_builder(&jobs, 4);
+        assert_eq!(
+            jobs.iter()
+                .map(|job| (job.id, job.job_name.as_str()))
+                .collect::<Vec<_>>(),
+            [(3, "backup"), (2, "B"), (4, "A"), (5, "C")]
+        );
+    }
+
+    #[test]
+    fn context_switch_cost_delays_next_job() {
+        let jobs = jobs_from(&[("A", 0, 3), ("B", 0, 2)]);
+        let config = SchedulerConfig {
+            context_switch_cost: 2,
+            ..Default::default()
+        };
+        let schedule = simulate(&jobs, &config);
+        assert_eq!(schedule.context_switches, 1);
+        assert_eq!(
+            schedule
+                .segments
+                .iter()
+                .map(|segment| (segment.job_name.as_str(), segment.start, segment.end))
+                .collect::<Vec<_>>(),
+            [("A", 0, 3), ("B", 5, 7)]
+        );
+    }
+
+    #[test]
+    fn cores_share_one_queue() {
+        let jobs = jobs_from(&[("A", 0, 4), ("B", 0, 2), ("C", 1, 3)]);
+        let config = SchedulerConfig {
+            algorithm: Algorithm::Fcfs,
+            cores: 2,
+            ..Default::default()
+        };
+        let schedule = simulate(&jobs, &config);
+        assert_eq!(
+            schedule.segments,
+            [
+                Segment {
+                    job_id: 2,
+                    job_name: "B".to_string(),
+                    core: 1,
+                    start: 0,
+                    end: 2
+                },
+                Segment {
+                    job_id: 1,
+                    job_name: "A".to_string(),
+                    core: 0,
+                    start: 0,
+                    end: 4
+                },
+                Segment {
+                    job_id: 3,
+                    job_name: "C".to_string(),
+                    core: 1,
+                    start: 2,
+                    end: 5
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn tie_breaking_rules() {
+        // A and B arrive together and need the same time, C is a second later
+        let mut jobs = job_builder(&[], 3);
+        for job in &mut jobs {
+            job.needed_cpu_cycle = 3;
+        }
+        jobs[0].job_name = "Z".to_string();
+        jobs[2].arrival_time = 1;
+        let order = |jobs: &[Job], algorithm, rule, preempted_first| {
+            let config = SchedulerConfig {
+                algorithm,
+                time_quantum: 2,
+                tie_breaking: TieBreaking {
+                    rule,
+                    preempted_first,
+                },
+                ..Default::default()
+            };
+            simulate(jobs, &config)
+                .segments
+                .iter()
+                .map(|segment| segment.job_name.clone())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        assert_eq!(order(&jobs, Algorithm::Sjn, TieBreak::Fifo, false), "Z B C");
+        assert_eq!(order(&jobs, Algorithm::Sjn, TieBreak::Lifo, false), "B C Z");
+        assert_eq!(
+            order(&jobs, Algorithm::Fcfs, TieBreak::JobName, false),
+            "B Z C"
+        );
+        assert_eq!(
+            order(&jobs, Algorithm::Fcfs, TieBreak::JobId, false),
+            "Z B C"
+        );
+
+        // Z's quantum is up at 2, C arrived at 1 and B is still waiting
+        assert_eq!(
+            order(&jobs, Algorithm::RoundRobin, TieBreak::Fifo, false),
+            "Z B C Z B C"
+        );
+        jobs[2].arrival_time = 2;
+        assert_eq!(
+            order(&jobs, Algorithm::RoundRobin, TieBreak::Fifo, false),
+            "Z B C Z B C"
+        );
+        assert_eq!(
+            order(&jobs, Algorithm::RoundRobin, TieBreak::Fifo, true),
+            "Z B Z C B C"
+        );
+        assert_eq!(
+            TieBreaking::default().describe(Algorithm::RoundRobin),
+            "Tie-break: First queued (FIFO); new arrivals queue ahead of preempted jobs"
+        );
+    }
+
+    #[test]
+    fn dependents_wait_for_predecessors() {
+        // B is shorter but needs A, C needs no CPU but waits for B
+        let mut jobs = jobs_from(&[("A", 0, 3), ("B", 0, 1), ("C", 0, 0), ("D", 1, 2)]);
+        ensure_unique_ids(&mut jobs);
+        jobs[1].depends_on = vec![1];
+        jobs[2].depends_on = vec![2];
+        let config = SchedulerConfig {
+            algorithm: Algorithm::Sjn,
+            ..Default::default()
+        };
+        let schedule = simulate(&jobs, &config);
+        let timeline: Vec<(&str, u32, u32)> = schedule
+            .segments
+            .iter()
+            .map(|segment| (segment.job_name.as_str(), segment.start, segment.end))
+            .collect();
+        assert_eq!(timeline, [("A", 0, 3), ("B", 3, 4), ("D", 4, 6)]);
+        let c = schedule
+            .jobs
+            .iter()
+            .find(|job| job.job_name == "C")
+            .unwrap();
+        assert_eq!(c.completion_time, 4);
+        assert_eq!(
+            crate::lifecycle::state_at(&schedule.state_changes, 2, 2).0,
+            ProcessState::New
+        );
+
+        // a cycle runs the jobs as if they had no dependencies
+        jobs[0].depends_on = vec![3];
+        assert_eq!(simulate(&jobs, &config).segments[0].job_name, "B");
+    }
+
+    #[test]
+    fn aging_prevents_starvation() {
+        // a long job behind a steady stream of short ones
+        let mut specs = vec![("S", 0, 2), ("L", 0, 10)];
+        specs.extend(
+            (1..20)
+                .step_by(2)
+                .map(|arrival_time| ("S", arrival_time, 2)),
+        );
+        let jobs = jobs_from(&specs);
+        let run = |aging| {
+            let config = SchedulerConfig {
+                algorithm: Algorithm::Sjn,
+                aging,
+                ..Default::default()
+            };
+            simulate(&jobs, &config)
+        };
+        let starvation = crate::metrics::Starvation {
+            max_wait: None,
+            burst_multiple: Some(2.0),
+        };
+        let long_start = |schedule: &Schedule| {
+            schedule
+                .segments
+                .iter()
+                .find(|segment| segment.job_name == "L")
+                .map(|segment| segment.start)
+        };
+
+        let starved = run(None);
+        assert_eq!(long_start(&starved), Some(22));
+        assert_eq!(starvation.starved_jobs(&starved), [2]);
+
+        // the short jobs age too, but by 10 L has waited off its whole burst
+        let aged = run(Some(Aging { rate: 1.0, cap: 10 }));
+        assert_eq!(long_start(&aged), Some(10));
+        // the short jobs stuck behind it now wait longer instead
+        assert!(!starvation.starved_jobs(&aged).contains(&2));
+    }
+
+    /// Jobs with memory and a class, which only count when the config has a memory model
+    /// or multilevel queues, and sometimes critical sections on three resources.
+    fn random_jobs() -> impl Strategy<Value = Vec<Job>> {
+        let section = (0u8..3, 0u32..8, 0u32..5);
+        (
+            prop::collection::vec(
+                (
+                    0u32..20,
+                    0u32..10,
+                    0u32..=100,
+                    prop::sample::select(JobClass::ALL.to_vec()),
+                    prop::collection::vec(section, 0..3),
+                ),
+                1..12,
+            ),
+            any::<bool>(),
+        )
+            .prop_map(|(specs, locking)| {
+                let mut jobs = job_builder(&[], specs.len() as u32);
+                for (job, (arrival_time, needed_cpu_cycle, memory, class, sections)) in
+                    jobs.iter_mut().zip(specs)
+                {
+                    job.arrival_time = arrival_time;
+                    job.needed_cpu_cycle = needed_cpu_cycle;
+                    job.memory = memory;
+                    job.class = class;
+                    if locking {
+                        job.critical_sections = sections
+                            .into_iter()
+                            .map(|(resource, offset, duration)| CriticalSection {
+                                resource: format!("R{resource}"),
+                                offset,
+                                duration,
+                            })
+                            .collect();
+                    }
+                }
+                jobs
+            })
+    }
+
+    fn random_memory() -> impl Strategy<Value = MemoryModel> {
+        (
+            prop::sample::select(Admission::ALL.to_vec()),
+            prop::option::of(0u32..3),
+        )
+            .prop_map(|(admission, swap_cost)| MemoryModel {
+                size: 100,
+                admission,
+                swap_cost,
+            })
+    }
+
+    fn random_multilevel() -> impl Strategy<Value = MultilevelQueues> {
+        (
+            prop::array::uniform3((
+                prop::sample::select(Algorithm::ALL.to_vec()),
+                1u32..4,
+                0u32..5,
+            )),
+            prop::sample::select(QueueSharing::ALL.to_vec()),
+        )
+            .prop_map(|(queues, sharing)| {
+                let [system, interactive, batch] =
+                    queues.map(|(algorithm, time_quantum, slice)| ClassQueue {
+                        algorithm,
+                        time_quantum,
+                        slice,
+                    });
+                MultilevelQueues {
+                    system,
+                    interactive,
+                    batch,
+                    sharing,
+                }
+            })
+    }
+
+    fn random_config() -> impl Strategy<Value = SchedulerConfig> {
+        (
+            1u32..6,
+            1u32..4,
+            0u32..3,
+            any::<u64>(),
+            prop::sample::select(TieBreak::ALL.to_vec()),
+            any::<bool>(),
+            prop::option::of((0.0..3.0f64, 0u32..10)),
+            prop::sample::select(LockProtocol::ALL.to_vec()),
+            prop::option::of(random_memory()),
+            prop::option::of(random_multilevel()),
+        )
+            .prop_map(
+                |(
+                    time_quantum,
+                    cores,
+                    context_switch_cost,
+                    seed,
+                    rule,
+                    preempted_first,
+                    aging,
+                    locking,
+                    memory,
+                    multilevel,
+                )| {
+                    SchedulerConfig {
+                        time_quantum,
+                        cores,
+                        context_switch_cost,
+                        seed,
+                        tie_breaking: TieBreaking {
+                            rule,
+                            preempted_first,
+                        },
+                        aging: aging.map(|(rate, cap)| Aging { rate, cap }),
+                        locking,
+                        memory,
+                        multilevel,
+                        ..Default::default()
+                    }
+                },
+            )
+    }
+
+    /// Checks `schedule` against what any correct run of `jobs` looks like.
+    fn check_invariants(
+        jobs: &[Job],
+        config: &SchedulerConfig,
+        schedule: &Schedule,
+    ) -> Result<(), TestCaseError> {
+        prop_assert_eq!(schedule.jobs.len(), jobs.len());
+        for job in jobs {
+            let finished: Vec<&Job> = schedule
+                .jobs
+                .iter()
+                .filter(|done| done.id == job.id)
+                .collect();
+            prop_assert_eq!(finished.len(), 1, "job {} finished once", job.id);
+            let segments: Vec<&Segment> = schedule
+                .segments
+                .iter()
+                .filter(|segment| segment.job_id == job.id)
+                .collect();
+            let run_time: u32 = segments
+                .iter()
+                .map(|segment| segment.end - segment.start)
+                .sum();
+            prop_assert_eq!(
+                run_time,
+                job.needed_cpu_cycle,
+                "job {} ran its burst",
+                job.id
+            );
+            prop_assert!(
+                segments
+                    .iter()
+                    .all(|segment| segment.start >= job.arrival_time),
+                "job {} ran before it arrived",
+                job.id
+            );
+            let completion_time = segments
+                .iter()
+                .map(|segment| segment.end)
+                .max()
+                .unwrap_or(job.arrival_time);
+            prop_assert_eq!(finished[0].completion_time, completion_time);
+            prop_assert_eq!(
+                finished[0].turnaround_time,
+                completion_time - job.arrival_time
+            );
+        }
+
+        for (i, a) in schedule.segments.iter().enumerate() {
+            prop_assert!(a.start < a.end && a.core < config.cores);
+            for b in &schedule.segments[i + 1..] {
+                let overlap = a.start < b.end && b.start < a.end;
+                prop_assert!(
+                    !(overlap && (a.core == b.core || a.job_id == b.job_id)),
+                    "{:?} overlaps {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+
+        // no two jobs hold a resource at once
+        for (i, a) in schedule.holds.iter().enumerate() {
+            for b in &schedule.holds[i + 1..] {
+                prop_assert!(
+                    a.resource != b.resource || a.end <= b.start || b.end <= a.start,
+                    "{:?} overlaps {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+        if let Some(memory) = &config.memory {
+            prop_assert!(memory_used(schedule)
+                .iter()
+                .all(|&used| used <= memory.size));
+        }
+        if config.multilevel.is_some() {
+            let ticks: u32 = class_ticks(schedule).iter().map(|(_, ticks)| ticks).sum();
+            prop_assert_eq!(
+                ticks,
+                jobs.iter().map(|job| job.needed_cpu_cycle).sum::<u32>()
+            );
+        }
+
+        // every algorithm here is work conserving: without switching time, a core only
+        // sits idle when every unfinished job that has arrived is running elsewhere,
+        // unless it is blocked on a resource or kept out of memory
+        let blocking =
+            config.memory.is_some() || jobs.iter().any(|job| !job.critical_sections.is_empty());
+        if config.context_switch_cost == 0 && !blocking {
+            let makespan = schedule
+                .jobs
+                .iter()
+                .map(|job| job.completion_time)
+                .max()
+                .unwrap_or(0);
+            for tick in 0..makespan {
+                let running = schedule
+                    .segments
+                    .iter()
+                    .filter(|segment| segment.start <= tick && tick < segment.end)
+                    .count() as u32;
+                let unfinished = schedule
+                    .jobs
+                    .iter()
+                    .filter(|job| job.arrival_time <= tick && tick < job.completion_time)
+                    .count() as u32;
+                prop_assert_eq!(running, unfinished.min(config.cores), "tick {}", tick);
+            }
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn scheduler_invariants(jobs in random_jobs(), config in random_config()) {
+            for algorithm in Algorithm::ALL {
+                let config = SchedulerConfig { algorithm, ..config.clone() };
+                check_invariants(&jobs, &config, &simulate(&jobs, &config))?;
+            }
+        }
+    }
 }
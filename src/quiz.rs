@@ -0,0 +1,364 @@
+use std::path::Path;
+
+use crate::process_scheduler::{
+    ensure_unique_ids, job_builder, randomize_jobs, simulate, Algorithm, Schedule, SchedulerConfig,
+};
+use crate::workload::Workload;
+
+/// A workload whose schedule is worked out by hand and then graded.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Quiz {
+    pub name: String,
+    pub workload: Workload,
+}
+
+impl Quiz {
+    /// Random jobs to be scheduled with `config` on a single core.
+    pub fn generate(name: &str, job_count: u32, config: SchedulerConfig) -> Quiz {
+        Quiz {
+            name: name.to_string(),
+            workload: Workload {
+                jobs: randomize_jobs(job_builder(&[], job_count.max(1))),
+                config: SchedulerConfig { cores: 1, ..config },
+            },
+        }
+    }
+
+    /// The schedule the answer is graded against.
+    pub fn solution(&self) -> Schedule {
+        simulate(&self.workload.jobs, &self.workload.config)
+    }
+}
+
+/// Quizzes handed out together, stored as one JSON file.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct QuizSet {
+    pub quizzes: Vec<Quiz>,
+}
+
+impl QuizSet {
+    pub fn from_json(json: &str) -> Result<QuizSet, String> {
+        let mut set: QuizSet =
+            serde_json::from_str(json).map_err(|error| format!("invalid quiz set: {error}"))?;
+        for quiz in &mut set.quizzes {
+            ensure_unique_ids(&mut quiz.workload.jobs);
+        }
+        Ok(set)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("quiz sets always serialize")
+    }
+
+    pub fn load(path: &Path) -> Result<QuizSet, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|error| format!("cannot read {}: {error}", path.display()))?;
+        QuizSet::from_json(&json)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_json())
+            .map_err(|error| format!("cannot write {}: {error}", path.display()))
+    }
+}
+
+/// One bar of the Gantt chart as the learner drew it.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct AnswerSegment {
+    pub job_id: u32,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// The learner's completion and turnaround time of one job.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct AnswerJob {
+    pub job_id: u32,
+    pub completion_time: u32,
+    pub turnaround_time: u32,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Answer {
+    pub segments: Vec<AnswerSegment>,
+    pub jobs: Vec<AnswerJob>,
+}
+
+impl Answer {
+    /// An answer with a row for every job of `quiz` and no segments yet.
+    pub fn blank(quiz: &Quiz) -> Answer {
+        Answer {
+            segments: Vec::new(),
+            jobs: quiz
+                .workload
+                .jobs
+                .iter()
+                .map(|job| AnswerJob {
+                    job_id: job.id,
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Grade {
+    /// Row of the first segment that differs from the solution, the number of rows
+    /// when the answer stops short. `None` when the chart is right.
+    pub first_wrong_segment: Option<usize>,
+    pub correct_segments: usize,
+    pub solution_segments: usize,
+    /// Completion and turnaround times that are right, two per job.
+    pub correct_times: usize,
+    pub solution_times: usize,
+    /// Share of segments and times that are right, extra segments count as wrong.
+    pub score: f64,
+}
+
+/// Back to back segments of the same job as one, each with the row it started at.
+fn merged(segments: impl IntoIterator<Item = AnswerSegment>) -> Vec<(usize, AnswerSegment)> {
+    let mut merged: Vec<(usize, AnswerSegment)> = Vec::new();
+    for (row, segment) in segments.into_iter().enumerate() {
+        match merged.last_mut() {
+            Some((_, last)) if last.job_id == segment.job_id && last.end == segment.start => {
+                last.end = segment.end
+            }
+            _ => merged.push((row, segment)),
+        }
+    }
+    merged
+}
+
+fn solution_segments(solution: &Schedule) -> Vec<AnswerSegment> {
+    merged(solution.segments.iter().map(|segment| AnswerSegment {
+        job_id: segment.job_id,
+        start: segment.start,
+        end: segment.end,
+    }))
+    .into_iter()
+    .map(|(_, segment)| segment)
+    .collect()
+}
+
+/// Checks `answer` against the schedule the quiz's algorithm produces.
+/// Splitting one run of a job into several back to back segments is not a mistake.
+pub fn grade(quiz: &Quiz, answer: &Answer) -> Grade {
+    let solution = quiz.solution();
+    let expected = solution_segments(&solution);
+    let given = merged(answer.segments.iter().copied());
+
+    let first_wrong_segment = (0..expected.len().max(given.len()))
+        .find(|&i| given.get(i).map(|(_, segment)| segment) != expected.get(i))
+        .map(|i| given.get(i).map_or(answer.segments.len(), |(row, _)| *row));
+    let correct_segments = expected
+        .iter()
+        .filter(|segment| given.iter().any(|(_, given)| given == *segment))
+        .count();
+
+    let correct_times = solution
+        .jobs
+        .iter()
+        .map(|job| {
+            answer
+                .jobs
+                .iter()
+                .find(|answer| answer.job_id == job.id)
+                .map_or(0, |answer| {
+                    (answer.completion_time == job.completion_time) as usize
+                        + (answer.turnaround_time == job.turnaround_time) as usize
+                })
+        })
+        .sum();
+    let solution_times = 2 * solution.jobs.len();
+
+    let total = expected.len().max(given.len()) + solution_times;
+    Grade {
+        first_wrong_segment,
+        correct_segments,
+        solution_segments: expected.len(),
+        correct_times,
+        solution_times,
+        score: if total == 0 {
+            1.0
+        } else {
+            (correct_segments + correct_times) as f64 / total as f64
+        },
+    }
+}
+
+/// How `algorithm` picks the next job, in a sentence.
+fn rule(config: &SchedulerConfig) -> String {
+    match config.algorithm {
+        Algorithm::Random => "Random picks any ready job, only the seed decides which.".to_string(),
+        Algorithm::Fcfs => "FCFS runs the job that arrived first until it finishes.".to_string(),
+        Algorithm::Sjn => {
+            "SJN runs the job needing the fewest CPU cycles until it finishes.".to_string()
+        }
+        Algorithm::Srt => "SRT runs the job with the least time left, and switches as soon as \
+            a job with less time left arrives."
+            .to_string(),
+        Algorithm::RoundRobin => format!(
+            "Round Robin runs the job at the front of the queue for up to {} ticks, then puts it \
+            at the back, behind jobs that arrived in the meantime.",
+            config.time_quantum.max(1)
+        ),
+    }
+}
+
+/// A nudge towards fixing the first mistake in `answer`, `None` when there is none.
+pub fn hint(quiz: &Quiz, answer: &Answer) -> Option<String> {
+    let solution = quiz.solution();
+    let expected = solution_segments(&solution);
+    let given = merged(answer.segments.iter().copied());
+    let name = |job_id: u32| {
+        quiz.workload
+            .jobs
+            .iter()
+            .find(|job| job.id == job_id)
+            .map_or_else(|| format!("#{job_id}"), |job| job.job_name.clone())
+    };
+
+    let Some(wrong) = (0..expected.len().max(given.len()))
+        .find(|&i| given.get(i).map(|(_, segment)| segment) != expected.get(i))
+    else {
+        let grade = grade(quiz, answer);
+        return (grade.correct_times < grade.solution_times).then(|| {
+            "The chart is right. A job's completion time is where its last segment ends, \
+                and its turnaround time is completion time minus arrival time."
+                .to_string()
+        });
+    };
+    let Some(segment) = expected.get(wrong) else {
+        let makespan = expected.last().map_or(0, |segment| segment.end);
+        return Some(format!(
+            "Every job has finished by {makespan}, remove the segments after that."
+        ));
+    };
+
+    let time = segment.start;
+    let ready: Vec<String> = quiz
+        .workload
+        .jobs
+        .iter()
+        .filter(|job| job.arrival_time <= time)
+        .filter_map(|job| {
+            let done: u32 = expected
+                .iter()
+                .filter(|segment| segment.job_id == job.id && segment.start < time)
+                .map(|segment| segment.end.min(time) - segment.start)
+                .sum();
+            let left = job.needed_cpu_cycle - done.min(job.needed_cpu_cycle);
+            (left > 0).then(|| format!("{} ({left} left)", job.job_name))
+        })
+        .collect();
+    let mut hint = match given.get(wrong) {
+        Some((_, given)) if given.job_id == segment.job_id && given.start == segment.start => {
+            format!(
+                "{} is the right job at {time}, but check when it stops. ",
+                name(segment.job_id)
+            )
+        }
+        _ if wrong > 0 && expected[wrong - 1].end < time => {
+            format!("The CPU is idle until {time}. ")
+        }
+        _ => String::new(),
+    };
+    hint.push_str(&format!(
+        "At {time} the ready queue holds {}. {}",
+        ready.join(", "),
+        rule(&quiz.workload.config)
+    ));
+    Some(hint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::textbook_scenarios;
+
+    fn srt_quiz() -> Quiz {
+        let scenario = textbook_scenarios()
+            .into_iter()
+            .find(|scenario| scenario.name == "McHoes & Flynn: SRT")
+            .unwrap();
+        Quiz {
+            name: scenario.name,
+            workload: scenario.workload,
+        }
+    }
+
+    #[test]
+    fn grades_and_hints() {
+        let quiz = srt_quiz(); // arrivals 0..3, cycles 6, 3, 1, 4
+        let mut answer = Answer::blank(&quiz);
+        let segment = |job_id, start, end| AnswerSegment { job_id, start, end };
+        // B should be preempted by C at 2
+        answer.segments = vec![segment(1, 0, 1), segment(2, 1, 4), segment(3, 4, 5)];
+        let grade = grade(&quiz, &answer);
+        assert_eq!(grade.first_wrong_segment, Some(1));
+        assert_eq!(grade.correct_segments, 1);
+        assert_eq!(grade.solution_segments, 6);
+        assert_eq!(
+            hint(&quiz, &answer).unwrap(),
+            "B is the right job at 1, but check when it stops. At 1 the ready queue holds \
+            A (5 left), B (3 left). SRT runs the job with the least time left, and switches \
+            as soon as a job with less time left arrives."
+        );
+
+        // a run split in two is still one segment
+        answer.segments = vec![
+            segment(1, 0, 1),
+            segment(2, 1, 2),
+            segment(3, 2, 3),
+            segment(2, 3, 4),
+            segment(2, 4, 5),
+            segment(4, 5, 9),
+            segment(1, 9, 14),
+        ];
+        assert_eq!(super::grade(&quiz, &answer).first_wrong_segment, None);
+        assert!(hint(&quiz, &answer)
+            .unwrap()
+            .starts_with("The chart is right"));
+
+        for (answer, job) in answer.jobs.iter_mut().zip(&quiz.solution().jobs) {
+            answer.job_id = job.id;
+            answer.completion_time = job.completion_time;
+            answer.turnaround_time = job.turnaround_time;
+        }
+        assert_eq!(super::grade(&quiz, &answer).score, 1.0);
+        assert_eq!(hint(&quiz, &answer), None);
+
+        answer.segments.push(segment(2, 14, 15));
+        let grade = super::grade(&quiz, &answer);
+        assert_eq!(grade.first_wrong_segment, Some(7));
+        assert!(grade.score < 1.0);
+    }
+
+    #[test]
+    fn quiz_sets_round_trip() {
+        let set = QuizSet {
+            quizzes: vec![
+                srt_quiz(),
+                Quiz::generate(
+                    "RR",
+                    5,
+                    SchedulerConfig {
+                        algorithm: Algorithm::RoundRobin,
+                        cores: 4,
+                        ..Default::default()
+                    },
+                ),
+            ],
+        };
+        assert_eq!(set.quizzes[1].workload.jobs.len(), 5);
+        assert_eq!(set.quizzes[1].workload.config.cores, 1);
+        assert_eq!(QuizSet::from_json(&set.to_json()), Ok(set));
+        assert!(QuizSet::from_json(r#"{"quizzes": 3}"#).is_err());
+    }
+}
@@ -0,0 +1,429 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::dependencies::{predecessors, topological_order};
+use crate::lifecycle::{ProcessState::*, StateChange};
+use crate::process_scheduler::{
+    ensure_unique_ids, Algorithm, Job, Schedule, SchedulerConfig, Segment,
+};
+
+#[derive(Clone, Default)]
+struct Core {
+    running: Option<usize>,
+    last_job: Option<usize>,
+    switching: u32,
+    quantum_used: u32,
+    // aging bonus of the running job when it was dispatched
+    earned: u32,
+}
+
+/// A deliberately plain second implementation of [`simulate`](crate::process_scheduler::simulate)
+/// to check the real one against.
+///
+/// Job state is kept in flat vectors indexed by job and the queue is rescanned every tick,
+/// and rather than tracking segments as jobs start and stop, it records which job each
+/// core ran in every tick and joins runs of the same job afterwards. It is slow and
+/// makes no attempt to be anything else. Critical sections are not modelled, so it only
+/// stands in for [`simulate`](crate::process_scheduler::simulate) on jobs without any,
+/// and neither are power or memory models or multilevel queues.
+pub fn reference_schedule(jobs: &[Job], config: &SchedulerConfig) -> Schedule {
+    let mut jobs = jobs.to_vec();
+    ensure_unique_ids(&mut jobs);
+    jobs.sort_by_key(|job| job.arrival_time);
+    let job_count = jobs.len();
+    let core_count = config.cores.max(1) as usize;
+    let time_quantum = config.time_quantum.max(1);
+    let tie_break = config.tie_breaking.rule;
+    let mut rng = StdRng::seed_from_u64(config.seed);
+
+    let predecessors = match topological_order(&jobs) {
+        Ok(_) => predecessors(&jobs),
+        Err(_) => vec![Vec::new(); job_count],
+    };
+
+    let mut remaining: Vec<u32> = jobs.iter().map(|job| job.needed_cpu_cycle).collect();
+    // tick each job arrived with its predecessors finished
+    let mut released: Vec<Option<u32>> = vec![None; job_count];
+    let mut queue: Vec<usize> = Vec::new();
+    // tick each job last joined the queue
+    let mut joined = vec![0; job_count];
+    let mut finished: Vec<(usize, u32)> = Vec::new();
+
+    let mut cores = vec![Core::default(); core_count];
+    let mut context_switches = 0;
+    // ran[tick][core]
+    let mut ran: Vec<Vec<Option<usize>>> = Vec::new();
+
+    let mut tick = 0;
+    while finished.len() < job_count {
+        let bonus = |joined: &[u32], job: usize| {
+            config
+                .aging
+                .map_or(0, |aging| aging.bonus(tick - joined[job]))
+        };
+        // where a preempted job rejoins the queue
+        let rejoin_at = |queue: &[usize]| match config.tie_breaking.preempted_first {
+            true => queue
+                .iter()
+                .position(|&job| jobs[job].arrival_time == tick)
+                .unwrap_or(queue.len()),
+            false => queue.len(),
+        };
+
+        let mut arriving: Vec<usize> = Vec::new();
+        // a job needing no CPU finishes on release, which may release others
+        let mut any_released = true;
+        while any_released {
+            any_released = false;
+            for (job, spec) in jobs.iter().enumerate() {
+                let ready = predecessors[job]
+                    .iter()
+                    .all(|predecessor| finished.iter().any(|(done, _)| done == predecessor));
+                if released[job].is_none() && spec.arrival_time <= tick && ready {
+                    released[job] = Some(tick);
+                    any_released = true;
+                    if spec.needed_cpu_cycle == 0 {
+                        finished.push((job, tick));
+                    } else {
+                        arriving.push(job);
+                    }
+                }
+            }
+        }
+        arriving.sort();
+        if config.algorithm == Algorithm::RoundRobin {
+            arriving.sort_by(|&a, &b| tie_break.compare(&jobs[a], a, &jobs[b], b));
+        }
+        for job in arriving {
+            joined[job] = tick;
+            queue.push(job);
+        }
+
+        // position in the queue of the job to run next
+        let pick = |queue: &[usize], joined: &[u32], rng: &mut StdRng| -> usize {
+            let rank = |job: usize| {
+                let value = match config.algorithm {
+                    Algorithm::Fcfs => jobs[job].arrival_time,
+                    Algorithm::Sjn => jobs[job].needed_cpu_cycle,
+                    _ => remaining[job],
+                };
+                value.saturating_sub(bonus(joined, job))
+            };
+            match config.algorithm {
+                Algorithm::Random => rng.gen_range(0..queue.len()),
+                Algorithm::RoundRobin => 0,
+                Algorithm::Fcfs | Algorithm::Sjn | Algorithm::Srt => {
+                    let mut best = 0;
+                    for position in 1..queue.len() {
+                        let (job, best_job) = (queue[position], queue[best]);
+                        let better = rank(job).cmp(&rank(best_job)).then_with(|| {
+                            tie_break.compare(&jobs[job], position, &jobs[best_job], best)
+                        });
+                        if better.is_lt() {
+                            best = position;
+                        }
+                    }
+                    best
+                }
+            }
+        };
+        let mut dispatch = |core: &mut Core, job: usize, joined: &[u32]| {
+            core.switching = 0;
+            if core.last_job.is_some_and(|last_job| last_job != job) {
+                context_switches += 1;
+                core.switching = config.context_switch_cost;
+            }
+            core.running = Some(job);
+            core.last_job = Some(job);
+            core.quantum_used = 0;
+            core.earned = bonus(joined, job);
+        };
+
+        match config.algorithm {
+            Algorithm::Srt => {
+                while !queue.is_empty() {
+                    let position = pick(&queue, &joined, &mut rng);
+                    let candidate = queue[position];
+                    // the running job with the most time left, the first such core on a tie
+                    let mut victim: Option<(usize, u32)> = None;
+                    for (index, core) in cores.iter().enumerate() {
+                        if let (Some(job), 0) = (core.running, core.switching) {
+                            let rank = remaining[job].saturating_sub(core.earned);
+                            if victim.map_or(true, |(_, most)| rank > most) {
+                                victim = Some((index, rank));
+                            }
+                        }
+                    }
+                    let candidate_rank =
+                        remaining[candidate].saturating_sub(bonus(&joined, candidate));
+                    let Some((index, _)) = victim.filter(|&(_, rank)| candidate_rank < rank) else {
+                        break;
+                    };
+                    queue.remove(position);
+                    let preempted = cores[index].running.take().expect("victim is running");
+                    let at = rejoin_at(&queue);
+                    queue.insert(at, preempted);
+                    joined[preempted] = tick;
+                    dispatch(&mut cores[index], candidate, &joined);
+                }
+            }
+            Algorithm::RoundRobin => {
+                for core in &mut cores {
+                    if core.running.is_none()
+                        || core.switching > 0
+                        || core.quantum_used < time_quantum
+                    {
+                        continue;
+                    }
+                    let at = rejoin_at(&queue);
+                    if at == 0 {
+                        // nobody is waiting ahead of it
+                        core.quantum_used = 0;
+                        continue;
+                    }
+                    let preempted = core.running.take().expect("core is running");
+                    queue.insert(at, preempted);
+                    joined[preempted] = tick;
+                    let next = queue.remove(0);
+                    dispatch(core, next, &joined);
+                }
+            }
+            Algorithm::Random | Algorithm::Fcfs | Algorithm::Sjn => {}
+        }
+
+        for core in &mut cores {
+            if core.running.is_none() && !queue.is_empty() {
+                let position = pick(&queue, &joined, &mut rng);
+                let job = queue.remove(position);
+                dispatch(core, job, &joined);
+            }
+        }
+
+        let mut row = vec![None; core_count];
+        for (index, core) in cores.iter_mut().enumerate() {
+            if core.switching > 0 {
+                core.switching -= 1;
+                continue;
+            }
+            if let Some(job) = core.running {
+                row[index] = Some(job);
+                remaining[job] -= 1;
+                core.quantum_used += 1;
+                if remaining[job] == 0 {
+                    finished.push((job, tick + 1));
+                    core.running = None;
+                }
+            }
+        }
+        ran.push(row);
+        tick += 1;
+    }
+
+    let mut segments: Vec<Segment> = Vec::new();
+    for core in 0..core_count {
+        for (tick, row) in ran.iter().enumerate() {
+            let Some(job) = row[core] else {
+                continue;
+            };
+            let tick = tick as u32;
+            match segments.last_mut() {
+                Some(segment)
+                    if segment.core == core as u32
+                        && segment.job_id == jobs[job].id
+                        && segment.end == tick =>
+                {
+                    segment.end = tick + 1
+                }
+                _ => segments.push(Segment {
+                    job_id: jobs[job].id,
+                    job_name: jobs[job].job_name.clone(),
+                    core: core as u32,
+                    start: tick,
+                    end: tick + 1,
+                }),
+            }
+        }
+    }
+    segments.sort_by_key(|segment| (segment.start, segment.core));
+
+    // state changes follow from the timeline: every job is released, then each segment
+    // ends either in a preemption or, for the last one, in the job finishing
+    let mut state_changes = Vec::new();
+    for &(job, completion_time) in &finished {
+        let change = |time, from, to| StateChange {
+            time,
+            job_id: jobs[job].id,
+            from,
+            to,
+        };
+        let released = released[job].expect("finished jobs were released");
+        if jobs[job].needed_cpu_cycle == 0 {
+            state_changes.push(change(released, New, Terminated));
+            continue;
+        }
+        state_changes.push(change(released, New, Ready));
+        for segment in segments
+            .iter()
+            .filter(|segment| segment.job_id == jobs[job].id)
+        {
+            state_changes.push(change(segment.start, Ready, Running));
+            match segment.end == completion_time {
+                true => state_changes.push(change(segment.end, Running, Terminated)),
+                false => state_changes.push(change(segment.end, Running, Ready)),
+            }
+        }
+    }
+    state_changes.sort();
+
+    Schedule {
+        jobs: finished
+            .into_iter()
+            .map(|(job, completion_time)| Job {
+                remaining_cpu_cycle: 0,
+                completion_time,
+                turnaround_time: completion_time - jobs[job].arrival_time,
+                ..jobs[job].clone()
+            })
+            .collect(),
+        segments,
+        context_switches,
+        state_changes,
+        ..Default::default()
+    }
+}
+
+/// Explains how `schedule` differs from the reference schedule of the same jobs, if it does.
+/// Segments and state changes are compared regardless of the order they are listed in.
+pub fn compare_with_reference(
+    jobs: &[Job],
+    config: &SchedulerConfig,
+    schedule: &Schedule,
+) -> Result<(), String> {
+    let expected = reference_schedule(jobs, config);
+    let mut segments = schedule.segments.clone();
+    segments.sort_by_key(|segment| (segment.start, segment.core));
+    if segments != expected.segments {
+        return Err(format!(
+            "timelines differ\n  simulate:  {:?}\n  reference: {:?}",
+            spans(&segments),
+            spans(&expected.segments)
+        ));
+    }
+    let completions = |schedule: &Schedule| {
+        let mut completions: Vec<(u32, u32, u32)> = schedule
+            .jobs
+            .iter()
+            .map(|job| (job.id, job.completion_time, job.turnaround_time))
+            .collect();
+        completions.sort();
+        completions
+    };
+    if completions(schedule) != completions(&expected) {
+        return Err(format!(
+            "completion times differ\n  simulate:  {:?}\n  reference: {:?}",
+            completions(schedule),
+            completions(&expected)
+        ));
+    }
+    let mut state_changes = schedule.state_changes.clone();
+    state_changes.sort();
+    if state_changes != expected.state_changes {
+        return Err(format!(
+            "state changes differ\n  simulate:  {:?}\n  reference: {:?}",
+            state_changes, expected.state_changes
+        ));
+    }
+    if schedule.context_switches != expected.context_switches {
+        return Err(format!(
+            "context switches differ: simulate {}, reference {}",
+            schedule.context_switches, expected.context_switches
+        ));
+    }
+    Ok(())
+}
+
+fn spans(segments: &[Segment]) -> Vec<(u32, u32, u32, u32)> {
+    segments
+        .iter()
+        .map(|segment| (segment.job_id, segment.core, segment.start, segment.end))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locking::LockProtocol;
+    use crate::process_scheduler::{simulate, Aging, TieBreak, TieBreaking};
+    use crate::scenario::textbook_scenarios;
+    use proptest::prelude::*;
+
+    #[test]
+    fn textbook_examples_agree() {
+        for scenario in textbook_scenarios() {
+            let workload = &scenario.workload;
+            let schedule = reference_schedule(&workload.jobs, &workload.config);
+            assert_eq!(
+                compare_with_reference(&workload.jobs, &workload.config, &schedule),
+                Ok(())
+            );
+            assert_eq!(
+                compare_with_reference(
+                    &workload.jobs,
+                    &workload.config,
+                    &simulate(&workload.jobs, &workload.config)
+                ),
+                Ok(()),
+                "{}",
+                scenario.name
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn simulate_matches_reference(
+            specs in prop::collection::vec((0u32..15, 0u32..8, any::<u8>()), 1..10),
+            time_quantum in 1u32..5,
+            cores in 1u32..4,
+            context_switch_cost in 0u32..3,
+            seed in any::<u64>(),
+            rule in prop::sample::select(TieBreak::ALL.to_vec()),
+            preempted_first in any::<bool>(),
+            aging in prop::option::of((0.0..3.0f64, 0u32..10)),
+            locking in prop::sample::select(LockProtocol::ALL.to_vec()),
+        ) {
+            let jobs: Vec<Job> = specs
+                .into_iter()
+                .enumerate()
+                .map(|(i, (arrival_time, needed_cpu_cycle, earlier))| Job {
+                    id: i as u32 + 1,
+                    job_name: format!("J{i}"),
+                    arrival_time,
+                    needed_cpu_cycle,
+                    // any of the jobs listed before it, so never a cycle
+                    depends_on: (1..=i as u32).filter(|id| earlier & (1 << (id - 1)) != 0).collect(),
+                    ..Default::default()
+                })
+                .collect();
+            for algorithm in Algorithm::ALL {
+                let config = SchedulerConfig {
+                    algorithm,
+                    time_quantum,
+                    cores,
+                    context_switch_cost,
+                    seed,
+                    tie_breaking: TieBreaking { rule, preempted_first },
+                    aging: aging.map(|(rate, cap)| Aging { rate, cap }),
+                    // without critical sections no protocol changes anything
+                    locking,
+                    power: None,
+                    memory: None,
+                    multilevel: None,
+                };
+                let schedule = simulate(&jobs, &config);
+                if let Err(difference) = compare_with_reference(&jobs, &config, &schedule) {
+                    return Err(TestCaseError::fail(format!("{algorithm:?}: {difference}")));
+                }
+            }
+        }
+    }
+}
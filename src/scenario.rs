@@ -0,0 +1,196 @@
+use crate::process_scheduler::{Algorithm, Job, SchedulerConfig};
+use crate::workload::Workload;
+
+/// A workload saved under a name.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Scenario {
+    pub name: String,
+    pub workload: Workload,
+}
+
+/// The user's saved scenarios, kept sorted by name. Built-in examples are not part of it.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ScenarioLibrary {
+    pub scenarios: Vec<Scenario>,
+}
+
+impl ScenarioLibrary {
+    pub fn get(&self, name: &str) -> Option<&Scenario> {
+        self.scenarios.iter().find(|scenario| scenario.name == name)
+    }
+
+    /// Saves `workload` as `name`, replacing a scenario of the same name.
+    pub fn save_as(&mut self, name: &str, workload: Workload) -> Result<(), String> {
+        let name = valid_name(name)?;
+        self.scenarios.retain(|scenario| scenario.name != name);
+        self.scenarios.push(Scenario { name, workload });
+        self.sort();
+        Ok(())
+    }
+
+    pub fn rename(&mut self, name: &str, new_name: &str) -> Result<(), String> {
+        let new_name = valid_name(new_name)?;
+        if new_name != name && self.get(&new_name).is_some() {
+            return Err(format!("a scenario called `{new_name}` already exists"));
+        }
+        let scenario = self
+            .scenarios
+            .iter_mut()
+            .find(|scenario| scenario.name == name)
+            .ok_or_else(|| format!("no scenario called `{name}`"))?;
+        scenario.name = new_name;
+        self.sort();
+        Ok(())
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<Scenario, String> {
+        let index = self
+            .scenarios
+            .iter()
+            .position(|scenario| scenario.name == name)
+            .ok_or_else(|| format!("no scenario called `{name}`"))?;
+        Ok(self.scenarios.remove(index))
+    }
+
+    /// Copies `scenario` into the library under the first free `<name> (copy n)`, returning that name.
+    /// Works for built-in examples too.
+    pub fn duplicate(&mut self, scenario: &Scenario) -> String {
+        let mut name = format!("{} (copy)", scenario.name);
+        let mut copy = 2;
+        while self.get(&name).is_some() {
+            name = format!("{} (copy {copy})", scenario.name);
+            copy += 1;
+        }
+        self.scenarios.push(Scenario {
+            name: name.clone(),
+            workload: scenario.workload.clone(),
+        });
+        self.sort();
+        name
+    }
+
+    fn sort(&mut self) {
+        self.scenarios.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+}
+
+fn valid_name(name: &str) -> Result<String, String> {
+    match name.trim() {
+        "" => Err("scenario names cannot be empty".to_string()),
+        name => Ok(name.to_string()),
+    }
+}
+
+/// Worked examples from Understanding Operating Systems (6th Edition)
+/// by Ann McIver McHoes & Ida M. Flynn, chapter 4.
+pub fn textbook_scenarios() -> Vec<Scenario> {
+    let example = |name: &str, algorithm, jobs: &[(u32, u32)]| Scenario {
+        name: name.to_string(),
+        workload: Workload {
+            jobs: jobs
+                .iter()
+                .zip('A'..)
+                .zip(1..)
+                .map(|((&(arrival_time, needed_cpu_cycle), job_name), id)| Job {
+                    id,
+                    job_name: job_name.to_string(),
+                    arrival_time,
+                    needed_cpu_cycle,
+                    remaining_cpu_cycle: needed_cpu_cycle,
+                    ..Default::default()
+                })
+                .collect(),
+            config: SchedulerConfig {
+                algorithm,
+                time_quantum: 4,
+                ..Default::default()
+            },
+        },
+    };
+    vec![
+        example(
+            "McHoes & Flynn: FCFS (A, B, C)",
+            Algorithm::Fcfs,
+            &[(0, 15), (0, 2), (0, 1)],
+        ),
+        example(
+            "McHoes & Flynn: FCFS (C, B, A)",
+            Algorithm::Fcfs,
+            &[(0, 1), (0, 2), (0, 15)],
+        ),
+        example(
+            "McHoes & Flynn: SJN",
+            Algorithm::Sjn,
+            &[(0, 5), (0, 2), (0, 6), (0, 4)],
+        ),
+        example(
+            "McHoes & Flynn: SRT",
+            Algorithm::Srt,
+            &[(0, 6), (1, 3), (2, 1), (3, 4)],
+        ),
+        example(
+            "McHoes & Flynn: Round Robin",
+            Algorithm::RoundRobin,
+            &[(0, 8), (1, 4), (2, 9), (3, 5)],
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::run_metrics;
+    use crate::process_scheduler::simulate;
+
+    #[test]
+    fn textbook_averages() {
+        // average turnaround times given in the book
+        let expected = [50.0 / 3.0, 22.0 / 3.0, 9.0, 6.25, 18.25];
+        for (scenario, expected) in textbook_scenarios().iter().zip(expected) {
+            let workload = &scenario.workload;
+            let metrics = run_metrics(&simulate(&workload.jobs, &workload.config), 1);
+            assert!(
+                (metrics.average_turnaround_time - expected).abs() < 1e-9,
+                "{}: {}",
+                scenario.name,
+                metrics.average_turnaround_time
+            );
+        }
+    }
+
+    #[test]
+    fn save_rename_duplicate_delete() {
+        let mut library = ScenarioLibrary::default();
+        let example = &textbook_scenarios()[0];
+        library
+            .save_as(" lab 1 ", example.workload.clone())
+            .unwrap();
+        assert_eq!(library.scenarios[0].name, "lab 1");
+        assert!(library.save_as("", Workload::default()).is_err());
+
+        assert_eq!(
+            library.duplicate(&library.scenarios[0].clone()),
+            "lab 1 (copy)"
+        );
+        assert_eq!(
+            library.duplicate(&library.scenarios[0].clone()),
+            "lab 1 (copy 2)"
+        );
+        assert!(library.rename("lab 1 (copy)", "lab 1").is_err());
+        library.rename("lab 1 (copy)", "lab 2").unwrap();
+        assert_eq!(
+            library
+                .scenarios
+                .iter()
+                .map(|scenario| scenario.name.as_str())
+                .collect::<Vec<_>>(),
+            ["lab 1", "lab 1 (copy 2)", "lab 2"]
+        );
+
+        library.delete("lab 1").unwrap();
+        assert!(library.delete("lab 1").is_err());
+        assert_eq!(library.get("lab 2").unwrap().workload, example.workload);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::process_scheduler::Job;
+
+/// One job of a Standard Workload Format trace, as used by the Parallel Workloads Archive.
+/// Fields the trace does not know are -1.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SwfRecord {
+    pub job_number: i64,
+    /// Seconds since the start of the log.
+    pub submit_time: i64,
+    pub wait_time: i64,
+    pub run_time: i64,
+    pub allocated_processors: i64,
+    pub requested_processors: i64,
+    pub requested_time: i64,
+    /// 1 completed, 0 failed, 5 cancelled.
+    pub status: i64,
+    pub user_id: i64,
+    pub group_id: i64,
+    pub queue: i64,
+    pub partition: i64,
+}
+
+/// How trace records become jobs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwfOptions {
+    /// Seconds per simulator tick.
+    pub time_unit: u32,
+    /// Keep only the first jobs (after filtering).
+    pub max_jobs: Option<usize>,
+    /// Keep only jobs of these users, all users if empty.
+    pub users: Vec<i64>,
+    /// Keep only jobs from these queues, all queues if empty.
+    pub queues: Vec<i64>,
+    /// Treat a job on n processors as n times the CPU cycles on our single queue.
+    pub scale_by_processors: bool,
+}
+
+impl Default for SwfOptions {
+    fn default() -> Self {
+        Self {
+            time_unit: 1,
+            max_jobs: None,
+            users: Vec::new(),
+            queues: Vec::new(),
+            scale_by_processors: false,
+        }
+    }
+}
+
+/// Parses the records of an SWF file, skipping `;` header comments and blank lines.
+pub fn parse_swf(text: &str) -> Result<Vec<SwfRecord>, String> {
+    let mut records = Vec::new();
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        let fields = line
+            .split_whitespace()
+            .map(|field| {
+                // a few archive logs write fractional seconds
+                field
+                    .parse::<f64>()
+                    .map(|value| value as i64)
+                    .map_err(|_| format!("line {}: `{field}` is not a number", line_number + 1))
+            })
+            .collect::<Result<Vec<i64>, String>>()?;
+        if fields.len() < 18 {
+            return Err(format!(
+                "line {}: expected 18 fields, found {}",
+                line_number + 1,
+                fields.len()
+            ));
+        }
+        records.push(SwfRecord {
+            job_number: fields[0],
+            submit_time: fields[1],
+            wait_time: fields[2],
+            run_time: fields[3],
+            allocated_processors: fields[4],
+            requested_processors: fields[7],
+            requested_time: fields[8],
+            status: fields[10],
+            user_id: fields[11],
+            group_id: fields[12],
+            queue: fields[14],
+            partition: fields[15],
+        });
+    }
+    Ok(records)
+}
+
+/// Turns records into jobs. Records without a run time are skipped and
+/// arrivals are shifted so the first kept job arrives at 0.
+pub fn swf_jobs(records: &[SwfRecord], options: &SwfOptions) -> Vec<Job> {
+    let time_unit = options.time_unit.max(1) as i64;
+    let kept: Vec<&SwfRecord> = records
+        .iter()
+        .filter(|record| record.run_time > 0 && record.submit_time >= 0)
+        .filter(|record| options.users.is_empty() || options.users.contains(&record.user_id))
+        .filter(|record| options.queues.is_empty() || options.queues.contains(&record.queue))
+        .take(options.max_jobs.unwrap_or(usize::MAX))
+        .collect();
+    let first_submit = kept
+        .iter()
+        .map(|record| record.submit_time)
+        .min()
+        .unwrap_or(0);
+
+    kept.into_iter()
+        .zip(1..)
+        .map(|(record, id)| {
+            let processors = match options.scale_by_processors {
+                true => record
+                    .allocated_processors
+                    .max(record.requested_processors)
+                    .max(1),
+                false => 1,
+            };
+            // round up so short jobs still need at least one tick
+            let cycles = (record.run_time * processors + time_unit - 1) / time_unit;
+            let arrival = (record.submit_time - first_submit) / time_unit;
+            Job {
+                id,
+                job_name: record.job_number.to_string(),
+                arrival_time: arrival.clamp(0, u32::MAX as i64) as u32,
+                needed_cpu_cycle: cycles.clamp(1, u32::MAX as i64) as u32,
+                remaining_cpu_cycle: cycles.clamp(1, u32::MAX as i64) as u32,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+pub fn import_swf(text: &str, options: &SwfOptions) -> Result<Vec<Job>, String> {
+    Ok(swf_jobs(&parse_swf(text)?, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACE: &str = "\
+; Version: 2.2
+; Computer: Example cluster
+; MaxJobs: 4
+    1   100   5   120   4  -1  -1   4   300  -1  1  7  1  -1  1  1  -1  -1
+    2   160   0    30   1  -1  -1   1    60  -1  1  8  1  -1  2  1  -1  -1
+    3   200   0    -1   2  -1  -1   2    60  -1  5  7  1  -1  1  1  -1  -1
+    4   260  10    61   2  -1  -1   2   120  -1  1  7  1  -1  1  1  -1  -1
+";
+
+    #[test]
+    fn records_become_jobs() {
+        let jobs = import_swf(TRACE, &SwfOptions::default()).unwrap();
+        // job 3 was cancelled before it ran
+        assert_eq!(
+            jobs.iter()
+                .map(|job| (
+                    job.job_name.as_str(),
+                    job.arrival_time,
+                    job.needed_cpu_cycle
+                ))
+                .collect::<Vec<_>>(),
+            [("1", 0, 120), ("2", 60, 30), ("4", 160, 61)]
+        );
+    }
+
+    #[test]
+    fn filter_scale_and_cap() {
+        let options = SwfOptions {
+            time_unit: 60,
+            max_jobs: Some(1),
+            users: vec![7],
+            scale_by_processors: true,
+            ..Default::default()
+        };
+        let jobs = import_swf(TRACE, &options).unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].job_name, "1");
+        assert_eq!(jobs[0].needed_cpu_cycle, 8); // 120 s on 4 processors
+    }
+
+    #[test]
+    fn short_records_are_rejected() {
+        assert_eq!(
+            parse_swf("1 0 0 10\n"),
+            Err("line 1: expected 18 fields, found 4".to_string())
+        );
+    }
+}
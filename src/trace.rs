@@ -0,0 +1,484 @@
+use std::collections::HashMap;
+
+use crate::process_scheduler::{Job, Schedule, Segment};
+
+/// One stretch of a task running on a CPU, times in seconds as printed in the trace.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceRun {
+    pub cpu: u32,
+    pub start: f64,
+    pub end: f64,
+}
+
+/// Everything a task ran between becoming runnable and blocking again, preemptions included.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpuBurst {
+    /// When the task was woken up, or first seen running if the wakeup is not in the trace.
+    pub ready: f64,
+    pub runs: Vec<TraceRun>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceTask {
+    pub pid: i64,
+    pub comm: String,
+    pub bursts: Vec<CpuBurst>,
+}
+
+impl TraceTask {
+    /// `comm-pid`, the way ftrace prints tasks.
+    pub fn name(&self) -> String {
+        format!("{}-{}", self.comm, self.pid)
+    }
+
+    /// Time spent blocked between consecutive CPU bursts.
+    pub fn io_bursts(&self) -> Vec<f64> {
+        self.bursts
+            .windows(2)
+            .map(|pair| {
+                let blocked_at = pair[0].runs.last().map_or(pair[0].ready, |run| run.end);
+                (pair[1].ready - blocked_at).max(0.0)
+            })
+            .collect()
+    }
+}
+
+/// Tasks seen in a trace, in order of first appearance.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trace {
+    pub tasks: Vec<TraceTask>,
+    /// Timestamp of the first event.
+    pub start: f64,
+    /// Highest CPU number seen plus one.
+    pub cpus: u32,
+}
+
+#[derive(Default)]
+struct TaskState {
+    task: usize,
+    woken_at: Option<f64>,
+    running: Option<(u32, f64)>,
+    // index of the burst the task is in, None while blocked
+    burst: Option<usize>,
+}
+
+/// Reads `perf sched script` or ftrace output. Only `sched_switch`, `sched_wakeup`,
+/// `sched_wakeup_new` and `sched_waking` events are used, other lines are skipped.
+pub fn parse_trace(text: &str) -> Result<Trace, String> {
+    let mut trace = Trace::default();
+    let mut states: HashMap<i64, TaskState> = HashMap::new();
+    let mut first_event = None;
+    let mut last_event = 0.0;
+
+    for (line_number, line) in text.lines().enumerate() {
+        let Some(event) = parse_event(line) else {
+            continue;
+        };
+        let error = |what: &str| format!("line {}: {what}", line_number + 1);
+        first_event.get_or_insert(event.timestamp);
+        last_event = event.timestamp;
+        trace.cpus = trace.cpus.max(event.cpu + 1);
+
+        match event.name {
+            "sched_wakeup" | "sched_wakeup_new" | "sched_waking" => {
+                let (comm, pid) =
+                    wakee(event.fields).ok_or_else(|| error("cannot read the woken task"))?;
+                if pid == 0 {
+                    continue;
+                }
+                let task_state = task_state(&mut states, &mut trace, pid, &comm);
+                if task_state.running.is_none() && task_state.woken_at.is_none() {
+                    task_state.woken_at = Some(event.timestamp);
+                    task_state.burst = None;
+                }
+            }
+            "sched_switch" => {
+                let switch =
+                    parse_switch(event.fields).ok_or_else(|| error("cannot read sched_switch"))?;
+                if switch.prev_pid != 0 {
+                    let task_state =
+                        task_state(&mut states, &mut trace, switch.prev_pid, &switch.prev_comm);
+                    let task = task_state.task;
+                    if let (Some((cpu, start)), Some(burst)) =
+                        (task_state.running.take(), task_state.burst)
+                    {
+                        trace.tasks[task].bursts[burst].runs.push(TraceRun {
+                            cpu,
+                            start,
+                            end: event.timestamp,
+                        });
+                    }
+                    // Anything but runnable means the task blocked and its CPU burst is over
+                    if !switch.prev_state.starts_with('R') {
+                        task_state.burst = None;
+                    }
+                }
+                if switch.next_pid != 0 {
+                    let task_state =
+                        task_state(&mut states, &mut trace, switch.next_pid, &switch.next_comm);
+                    let task = task_state.task;
+                    if task_state.burst.is_none() {
+                        let ready = task_state.woken_at.unwrap_or(event.timestamp);
+                        trace.tasks[task].bursts.push(CpuBurst {
+                            ready,
+                            runs: Vec::new(),
+                        });
+                        task_state.burst = Some(trace.tasks[task].bursts.len() - 1);
+                    }
+                    task_state.woken_at = None;
+                    task_state.running = Some((event.cpu, event.timestamp));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Tasks still on a CPU when the trace ends ran until the last event
+    for state in states.values() {
+        if let (Some((cpu, start)), Some(burst)) = (state.running, state.burst) {
+            trace.tasks[state.task].bursts[burst].runs.push(TraceRun {
+                cpu,
+                start,
+                end: last_event,
+            });
+        }
+    }
+    trace.start = first_event.ok_or("no sched_switch or sched_wakeup events found")?;
+    Ok(trace)
+}
+
+fn task_state<'a>(
+    states: &'a mut HashMap<i64, TaskState>,
+    trace: &mut Trace,
+    pid: i64,
+    comm: &str,
+) -> &'a mut TaskState {
+    states.entry(pid).or_insert_with(|| {
+        trace.tasks.push(TraceTask {
+            pid,
+            comm: comm.to_string(),
+            bursts: Vec::new(),
+        });
+        TaskState {
+            task: trace.tasks.len() - 1,
+            ..Default::default()
+        }
+    })
+}
+
+struct Event<'a> {
+    cpu: u32,
+    timestamp: f64,
+    name: &'a str,
+    fields: &'a str,
+}
+
+/// Finds `[cpu]`, the `timestamp:` after it and the event name in a trace line.
+/// ftrace: `bash-4321 [000] d..3 1234.567890: sched_switch: ...`
+/// perf:   `bash 4321 [000] 1234.567890: sched:sched_switch: ...`
+fn parse_event(line: &str) -> Option<Event<'_>> {
+    let open = line.find('[')?;
+    let close = open + line[open..].find(']')?;
+    let cpu = line[open + 1..close].trim().parse().ok()?;
+    let mut rest = &line[close + 1..];
+    let timestamp = loop {
+        let token = rest.split_whitespace().next()?;
+        let position = rest.find(token)? + token.len();
+        rest = &rest[position..];
+        if let Some(timestamp) = token.strip_suffix(':').and_then(|token| token.parse().ok()) {
+            break timestamp;
+        }
+    };
+    let rest = rest.trim_start();
+    let (name, fields) = rest
+        .split_once(": ")
+        .unwrap_or((rest.trim_end_matches(':'), ""));
+    let name = name.rsplit(':').next()?;
+    Some(Event {
+        cpu,
+        timestamp,
+        name,
+        fields: fields.trim(),
+    })
+}
+
+/// `key=value` pairs, where values may contain spaces (task names can).
+fn key_values(fields: &str) -> HashMap<&str, String> {
+    let mut pairs: HashMap<&str, String> = HashMap::new();
+    let mut current: Option<&str> = None;
+    for token in fields.split_whitespace() {
+        match token.split_once('=') {
+            Some((key, value)) if !key.is_empty() && token != "==>" => {
+                pairs.insert(key, value.to_string());
+                current = Some(key);
+            }
+            _ if token == "==>" => current = None,
+            _ => {
+                if let Some(value) = current.and_then(|key| pairs.get_mut(key)) {
+                    value.push(' ');
+                    value.push_str(token);
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// `comm=foo pid=99 ...`, or the older `foo:99 [120] ...`.
+fn wakee(fields: &str) -> Option<(String, i64)> {
+    let pairs = key_values(fields);
+    if let (Some(comm), Some(pid)) = (pairs.get("comm"), pairs.get("pid")) {
+        return Some((comm.clone(), pid.parse().ok()?));
+    }
+    let task = fields.split(" [").next()?;
+    let (comm, pid) = task.rsplit_once(':')?;
+    Some((comm.to_string(), pid.trim().parse().ok()?))
+}
+
+struct Switch {
+    prev_comm: String,
+    prev_pid: i64,
+    prev_state: String,
+    next_comm: String,
+    next_pid: i64,
+}
+
+/// `prev_comm=a prev_pid=1 prev_prio=120 prev_state=S ==> next_comm=b next_pid=2 next_prio=120`,
+/// or the compact `a:1 [120] S ==> b:2 [120]`.
+fn parse_switch(fields: &str) -> Option<Switch> {
+    let pairs = key_values(fields);
+    if let (Some(prev_pid), Some(next_pid)) = (pairs.get("prev_pid"), pairs.get("next_pid")) {
+        return Some(Switch {
+            prev_comm: pairs.get("prev_comm").cloned().unwrap_or_default(),
+            prev_pid: prev_pid.parse().ok()?,
+            prev_state: pairs.get("prev_state").cloned().unwrap_or_default(),
+            next_comm: pairs.get("next_comm").cloned().unwrap_or_default(),
+            next_pid: next_pid.parse().ok()?,
+        });
+    }
+    let (prev, next) = fields.split_once("==>")?;
+    let task = |text: &str| -> Option<(String, i64)> {
+        let (comm, pid) = text.split(" [").next()?.trim().rsplit_once(':')?;
+        Some((comm.to_string(), pid.parse().ok()?))
+    };
+    let (prev_comm, prev_pid) = task(prev)?;
+    let (next_comm, next_pid) = task(next)?;
+    let prev_state = prev.rsplit(']').next()?.trim().to_string();
+    Some(Switch {
+        prev_comm,
+        prev_pid,
+        prev_state,
+        next_comm,
+        next_pid,
+    })
+}
+
+/// How a trace becomes a workload.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceOptions {
+    /// Microseconds per simulator tick.
+    pub time_unit_us: u32,
+    /// One job per CPU burst instead of one job per task. The time a task spends
+    /// blocked then shows up as the gap between the arrivals of its bursts.
+    pub per_burst: bool,
+    /// Keep only tasks with these names (`comm`), all tasks if empty.
+    pub comms: Vec<String>,
+    /// Keep only the first jobs to arrive.
+    pub max_jobs: Option<usize>,
+}
+
+impl Default for TraceOptions {
+    fn default() -> Self {
+        Self {
+            time_unit_us: 1000,
+            per_burst: false,
+            comms: Vec::new(),
+            max_jobs: None,
+        }
+    }
+}
+
+/// The jobs to replay and the schedule that was actually observed for them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportedTrace {
+    pub jobs: Vec<Job>,
+    pub observed: Schedule,
+    pub cpus: u32,
+}
+
+pub fn import_trace(text: &str, options: &TraceOptions) -> Result<ImportedTrace, String> {
+    let trace = parse_trace(text)?;
+    // whole microseconds first, trace timestamps are printed with six decimals
+    let micros = |seconds: f64| (seconds * 1_000_000.0).round().max(0.0);
+    let unit = options.time_unit_us.max(1) as f64;
+    let tick = |time: f64| (micros(time - trace.start) / unit).floor() as u32;
+
+    // (job, runs) pairs, one per task or per burst
+    let mut jobs: Vec<(Job, Vec<&TraceRun>)> = Vec::new();
+    for task in &trace.tasks {
+        if !options.comms.is_empty() && !options.comms.contains(&task.comm) {
+            continue;
+        }
+        let groups: Vec<(String, &[CpuBurst])> = match options.per_burst {
+            true => (0..task.bursts.len())
+                .map(|i| (format!("{}#{}", task.name(), i + 1), &task.bursts[i..=i]))
+                .collect(),
+            false => vec![(task.name(), &task.bursts[..])],
+        };
+        for (job_name, bursts) in groups {
+            let runs: Vec<&TraceRun> = bursts.iter().flat_map(|burst| &burst.runs).collect();
+            let Some(ready) = bursts.first().map(|burst| burst.ready) else {
+                continue;
+            };
+            let cpu_time: f64 = runs.iter().map(|run| run.end - run.start).sum();
+            if runs.is_empty() {
+                continue;
+            }
+            let needed_cpu_cycle = ((micros(cpu_time) / unit).ceil() as u32).max(1);
+            jobs.push((
+                Job {
+                    job_name,
+                    arrival_time: tick(ready),
+                    needed_cpu_cycle,
+                    remaining_cpu_cycle: needed_cpu_cycle,
+                    ..Default::default()
+                },
+                runs,
+            ));
+        }
+    }
+    jobs.sort_by_key(|(job, _)| job.arrival_time);
+    jobs.truncate(options.max_jobs.unwrap_or(usize::MAX));
+    for ((job, _), id) in jobs.iter_mut().zip(1..) {
+        job.id = id;
+    }
+
+    let mut observed = Schedule::default();
+    for (job, runs) in &jobs {
+        let mut last_end = job.arrival_time;
+        for run in runs {
+            let (start, end) = (tick(run.start), tick(run.end));
+            last_end = last_end.max(end);
+            if start < end {
+                observed.segments.push(Segment {
+                    job_id: job.id,
+                    job_name: job.job_name.clone(),
+                    core: run.cpu,
+                    start,
+                    end,
+                });
+            }
+        }
+        observed.jobs.push(Job {
+            completion_time: last_end,
+            turnaround_time: last_end - job.arrival_time,
+            ..job.clone()
+        });
+    }
+    observed
+        .segments
+        .sort_by_key(|segment| (segment.start, segment.core));
+    observed.jobs.sort_by_key(|job| job.completion_time);
+    for cpu in 0..trace.cpus {
+        let on_cpu: Vec<&Segment> = observed
+            .segments
+            .iter()
+            .filter(|segment| segment.core == cpu)
+            .collect();
+        observed.context_switches += on_cpu
+            .windows(2)
+            .filter(|pair| pair[0].job_id != pair[1].job_id)
+            .count() as u32;
+    }
+
+    Ok(ImportedTrace {
+        jobs: jobs.into_iter().map(|(job, _)| job).collect(),
+        observed,
+        cpus: trace.cpus.max(1),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // bash runs, blocks on IO, gets woken up again while worker holds the CPU
+    const FTRACE: &str = "\
+# tracer: nop
+          <idle>-0     [000] d..3  100.000000: sched_switch: prev_comm=swapper/0 prev_pid=0 prev_prio=120 prev_state=R ==> next_comm=bash next_pid=10 next_prio=120
+            bash-10    [000] d..3  100.003000: sched_wakeup: comm=Web Content pid=20 prio=120 target_cpu=000
+            bash-10    [000] d..3  100.004000: sched_switch: prev_comm=bash prev_pid=10 prev_prio=120 prev_state=S ==> next_comm=Web Content next_pid=20 next_prio=120
+     Web Content-20    [000] d..3  100.006000: sched_wakeup: comm=bash pid=10 prio=120 target_cpu=000
+     Web Content-20    [000] d..3  100.009000: sched_switch: prev_comm=Web Content prev_pid=20 prev_prio=120 prev_state=R ==> next_comm=bash next_pid=10 next_prio=120
+            bash-10    [000] d..3  100.011000: sched_switch: prev_comm=bash prev_pid=10 prev_prio=120 prev_state=S ==> next_comm=Web Content next_pid=20 next_prio=120
+     Web Content-20    [000] d..3  100.012000: sched_switch: prev_comm=Web Content prev_pid=20 prev_prio=120 prev_state=S ==> next_comm=swapper/0 next_pid=0 next_prio=120
+";
+
+    #[test]
+    fn bursts_and_io() {
+        let trace = parse_trace(FTRACE).unwrap();
+        let bash = &trace.tasks[0];
+        assert_eq!(bash.name(), "bash-10");
+        assert_eq!(bash.bursts.len(), 2);
+        assert_eq!(bash.bursts[1].ready, 100.006);
+        assert!((bash.io_bursts()[0] - 0.002).abs() < 1e-9);
+        // preempted while runnable, so still one CPU burst
+        assert_eq!(trace.tasks[1].name(), "Web Content-20");
+        assert_eq!(trace.tasks[1].bursts.len(), 1);
+        assert_eq!(trace.tasks[1].bursts[0].runs.len(), 2);
+    }
+
+    #[test]
+    fn per_task_and_per_burst_jobs() {
+        let per_task = import_trace(FTRACE, &TraceOptions::default()).unwrap();
+        assert_eq!(
+            per_task
+                .jobs
+                .iter()
+                .map(|job| (
+                    job.job_name.as_str(),
+                    job.arrival_time,
+                    job.needed_cpu_cycle
+                ))
+                .collect::<Vec<_>>(),
+            [("bash-10", 0, 6), ("Web Content-20", 3, 6)]
+        );
+        assert_eq!(per_task.observed.segments.len(), 4);
+        assert_eq!(per_task.observed.context_switches, 3);
+        assert_eq!(per_task.observed.jobs[1].completion_time, 12);
+
+        let options = TraceOptions {
+            per_burst: true,
+            ..Default::default()
+        };
+        let per_burst = import_trace(FTRACE, &options).unwrap();
+        assert_eq!(
+            per_burst
+                .jobs
+                .iter()
+                .map(|job| (
+                    job.job_name.as_str(),
+                    job.arrival_time,
+                    job.needed_cpu_cycle
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("bash-10#1", 0, 4),
+                ("Web Content-20#1", 3, 6),
+                ("bash-10#2", 6, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn perf_sched_script() {
+        let perf = "\
+            perf    10 [001]  5.000100: sched:sched_switch: perf:10 [120] S ==> kworker/1:1:30 [120]
+   kworker/1:1    30 [001]  5.000400: sched:sched_switch: kworker/1:1:30 [120] S ==> swapper/1:0 [120]
+";
+        let trace = parse_trace(perf).unwrap();
+        assert_eq!(trace.cpus, 2);
+        assert_eq!(trace.tasks[1].name(), "kworker/1:1-30");
+        assert_eq!(trace.tasks[1].bursts[0].runs[0].end, 5.0004);
+    }
+}
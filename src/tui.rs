@@ -0,0 +1,454 @@
+use std::path::PathBuf;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Row, Table};
+use ratatui::Frame;
+
+use crate::gantt::{text_gantt, GanttCell};
+use crate::metrics::{job_metrics, run_metrics};
+use crate::process_scheduler::{
+    job_builder, job_color, randomize_jobs, simulate, Algorithm, Job, Schedule, SchedulerConfig,
+    TieBreak,
+};
+use crate::workload::Workload;
+
+const HELP: &str = "↑↓←→ select  0-9 +/- edit  J/K move  n/N jobs  a/A algorithm  [ ] quantum  t/T ties  r randomize  o open  w write  q quit";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Column {
+    CpuCycle,
+    ArrivalTime,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Prompt {
+    Open,
+    Write,
+}
+
+/// Terminal front end with the same job table as the simulator window.
+struct Tui {
+    jobs: Vec<Job>,
+    config: SchedulerConfig,
+    selected_job: usize,
+    selected_column: Column,
+    // true while digits are being typed into the selected cell
+    typing: bool,
+    prompt: Option<(Prompt, String)>,
+    path: Option<PathBuf>,
+    status: String,
+    quit: bool,
+}
+
+/// Opens the terminal UI, optionally with a workload file loaded.
+pub fn run(path: Option<PathBuf>) -> Result<(), String> {
+    let mut tui = Tui::new(job_builder(&[], 1), SchedulerConfig::default());
+    if let Some(path) = path {
+        tui.open(path);
+    }
+
+    let mut terminal = ratatui::init();
+    let result = loop {
+        if let Err(error) = terminal.draw(|frame| tui.draw(frame)) {
+            break Err(error.to_string());
+        }
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => tui.handle_key(key),
+            Ok(_) => {}
+            Err(error) => break Err(error.to_string()),
+        }
+        if tui.quit {
+            break Ok(());
+        }
+    };
+    ratatui::restore();
+    result
+}
+
+impl Tui {
+    fn new(jobs: Vec<Job>, config: SchedulerConfig) -> Self {
+        Self {
+            jobs,
+            config,
+            selected_job: 0,
+            selected_column: Column::CpuCycle,
+            typing: false,
+            prompt: None,
+            path: None,
+            status: HELP.to_string(),
+            quit: false,
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if let Some((prompt, mut text)) = self.prompt.take() {
+            match key.code {
+                KeyCode::Enter => match prompt {
+                    Prompt::Open => self.open(PathBuf::from(text)),
+                    Prompt::Write => self.write(PathBuf::from(text)),
+                },
+                KeyCode::Esc => self.status = HELP.to_string(),
+                KeyCode::Backspace => {
+                    text.pop();
+                    self.prompt = Some((prompt, text));
+                }
+                KeyCode::Char(c) => {
+                    text.push(c);
+                    self.prompt = Some((prompt, text));
+                }
+                _ => self.prompt = Some((prompt, text)),
+            }
+            return;
+        }
+
+        let was_typing = self.typing;
+        self.typing = false;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected_job = self.selected_job.saturating_sub(1)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected_job = (self.selected_job + 1).min(self.jobs.len() - 1)
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.selected_column = Column::CpuCycle,
+            KeyCode::Right | KeyCode::Char('l') => self.selected_column = Column::ArrivalTime,
+            KeyCode::Char('+') | KeyCode::Char('=') => self.edit(|value| value.saturating_add(1)),
+            KeyCode::Char('-') => self.edit(|value| value.saturating_sub(1)),
+            KeyCode::Char(digit @ '0'..='9') => {
+                let digit = digit.to_digit(10).expect("matched a digit");
+                self.edit(|value| {
+                    if was_typing {
+                        value.saturating_mul(10).saturating_add(digit)
+                    } else {
+                        digit
+                    }
+                });
+                self.typing = true;
+            }
+            KeyCode::Backspace => {
+                self.edit(|value| value / 10);
+                self.typing = true;
+            }
+            // move up [a][b] swap with previous
+            KeyCode::Char('K') => {
+                if self.selected_job != 0 {
+                    self.jobs.swap(self.selected_job, self.selected_job - 1);
+                    self.selected_job -= 1;
+                }
+            }
+            // move down [b][a] swap with next
+            KeyCode::Char('J') => {
+                if self.selected_job + 1 != self.jobs.len() {
+                    self.jobs.swap(self.selected_job, self.selected_job + 1);
+                    self.selected_job += 1;
+                }
+            }
+            KeyCode::Char('n') => self.set_job_count(self.jobs.len() as u32 + 1),
+            KeyCode::Char('N') => self.set_job_count(self.jobs.len() as u32 - 1),
+            KeyCode::Char('a') => self.cycle_algorithm(1),
+            KeyCode::Char('A') => self.cycle_algorithm(Algorithm::ALL.len() - 1),
+            KeyCode::Char(']') => {
+                self.config.time_quantum = (self.config.time_quantum + 1).min(u16::MAX as u32)
+            }
+            KeyCode::Char('[') => {
+                self.config.time_quantum = self.config.time_quantum.saturating_sub(1).max(1)
+            }
+            KeyCode::Char('t') => {
+                let rule = &mut self.config.tie_breaking.rule;
+                let index = TieBreak::ALL.iter().position(|r| r == rule).unwrap_or(0);
+                *rule = TieBreak::ALL[(index + 1) % TieBreak::ALL.len()];
+            }
+            KeyCode::Char('T') => {
+                let tie_breaking = &mut self.config.tie_breaking;
+                tie_breaking.preempted_first = !tie_breaking.preempted_first;
+            }
+            KeyCode::Char('r') => self.jobs = randomize_jobs(self.jobs.clone()),
+            KeyCode::Char('o') => self.prompt = Some((Prompt::Open, self.path_text())),
+            KeyCode::Char('w') => self.prompt = Some((Prompt::Write, self.path_text())),
+            _ => {}
+        }
+    }
+
+    /// Applies `change` to the selected cell, keeping it in the range the job table allows.
+    fn edit(&mut self, change: impl Fn(u32) -> u32) {
+        let job = &mut self.jobs[self.selected_job];
+        match self.selected_column {
+            Column::CpuCycle => {
+                job.needed_cpu_cycle = change(job.needed_cpu_cycle).clamp(1, u16::MAX as u32)
+            }
+            Column::ArrivalTime => job.arrival_time = change(job.arrival_time).min(u16::MAX as u32),
+        }
+    }
+
+    fn set_job_count(&mut self, job_count: u32) {
+        if (1..=u16::MAX as u32).contains(&job_count) {
+            self.jobs = job_builder(&self.jobs, job_count);
+            self.selected_job = self.selected_job.min(self.jobs.len() - 1);
+        }
+    }
+
+    fn cycle_algorithm(&mut self, step: usize) {
+        let index = Algorithm::ALL
+            .iter()
+            .position(|&algorithm| algorithm == self.config.algorithm)
+            .unwrap_or(0);
+        self.config.algorithm = Algorithm::ALL[(index + step) % Algorithm::ALL.len()];
+    }
+
+    fn path_text(&self) -> String {
+        self.path
+            .as_ref()
+            .map_or("workload.json".to_string(), |path| {
+                path.display().to_string()
+            })
+    }
+
+    fn open(&mut self, path: PathBuf) {
+        match Workload::load(&path) {
+            Ok(workload) if workload.jobs.is_empty() => {
+                self.status = format!("{} has no jobs", path.display());
+            }
+            Ok(workload) => {
+                self.jobs = workload.jobs;
+                self.config = workload.config;
+                self.selected_job = 0;
+                self.status = format!("Opened {}", path.display());
+                self.path = Some(path);
+            }
+            Err(error) => self.status = error,
+        }
+    }
+
+    fn write(&mut self, path: PathBuf) {
+        let workload = Workload {
+            jobs: self.jobs.clone(),
+            config: self.config.clone(),
+        };
+        match workload.save(&path) {
+            Ok(()) => {
+                self.status = format!("Wrote {}", path.display());
+                self.path = Some(path);
+            }
+            Err(error) => self.status = error,
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame<'_>) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [jobs, results] =
+            Layout::horizontal([Constraint::Length(38), Constraint::Min(0)]).areas(body);
+
+        let mut settings = vec![
+            Span::raw("Algorithm: "),
+            Span::styled(
+                self.config.algorithm.name(),
+                Style::new().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!("   Number of Jobs: {}", self.jobs.len())),
+        ];
+        if self.config.algorithm == Algorithm::RoundRobin {
+            settings.push(Span::raw(format!(
+                "   Time Quantum: {}",
+                self.config.time_quantum
+            )));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(settings))
+                .block(Block::bordered().title("Process Scheduling Simulator")),
+            header,
+        );
+
+        self.draw_jobs(frame, jobs);
+
+        let schedule = simulate(&self.jobs, &self.config);
+        let gantt_height = 2 * self.config.cores.max(1) as u16 + 3;
+        let [gantt, table] =
+            Layout::vertical([Constraint::Length(gantt_height), Constraint::Min(0)]).areas(results);
+        let width = gantt.width.saturating_sub(10) as usize;
+        frame.render_widget(
+            Paragraph::new(gantt_lines(&schedule, self.config.cores.max(1), width))
+                .block(Block::bordered().title("Gantt Chart")),
+            gantt,
+        );
+        draw_results(frame, table, &schedule, &self.config);
+
+        let footer_text = match &self.prompt {
+            Some((Prompt::Open, text)) => format!("Open workload: {text}_"),
+            Some((Prompt::Write, text)) => format!("Write workload: {text}_"),
+            None => self.status.clone(),
+        };
+        frame.render_widget(Paragraph::new(footer_text), footer);
+    }
+
+    fn draw_jobs(&self, frame: &mut Frame<'_>, area: Rect) {
+        let selected = Style::new().add_modifier(Modifier::REVERSED);
+        let rows = self.jobs.iter().enumerate().map(|(i, job)| {
+            let cell = |column: Column, value: u32| {
+                let style = if i == self.selected_job && column == self.selected_column {
+                    selected
+                } else {
+                    Style::new()
+                };
+                Span::styled(value.to_string(), style)
+            };
+            Row::new(vec![
+                Line::from(job_span(job.id, &format!("JOB {}", job.job_name))),
+                Line::from(cell(Column::CpuCycle, job.needed_cpu_cycle)),
+                Line::from(cell(Column::ArrivalTime, job.arrival_time)),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Length(10),
+                Constraint::Length(12),
+            ],
+        )
+        .header(Row::new(["Job Details", "CPU Cycle", "Arrival Time"]).add_modifier(Modifier::BOLD))
+        .block(Block::bordered().title("Jobs"));
+        frame.render_widget(table, area);
+    }
+}
+
+fn draw_results(frame: &mut Frame<'_>, area: Rect, schedule: &Schedule, config: &SchedulerConfig) {
+    let metrics = run_metrics(schedule, config.cores);
+    let mut rows: Vec<Row<'_>> = job_metrics(schedule)
+        .into_iter()
+        .map(|job| {
+            Row::new(vec![
+                Line::from(job_span(job.job_id, &format!("JOB {}", job.job_name))),
+                Line::from(job.completion_time.to_string()),
+                Line::from(job.turnaround_time.to_string()),
+                Line::from(job.waiting_time.to_string()),
+                Line::from(job.response_time.to_string()),
+            ])
+        })
+        .collect();
+    rows.push(
+        Row::new(vec![
+            "Average".to_string(),
+            String::new(),
+            format!("{:.2}", metrics.average_turnaround_time),
+            format!("{:.2}", metrics.average_waiting_time),
+            format!("{:.2}", metrics.average_response_time),
+        ])
+        .add_modifier(Modifier::BOLD),
+    );
+    let table = Table::new(rows, [Constraint::Length(12); 5])
+        .header(
+            Row::new([
+                "Job Name",
+                "Completion",
+                "Turn Around",
+                "Waiting",
+                "Response",
+            ])
+            .add_modifier(Modifier::BOLD),
+        )
+        .block(
+            Block::bordered()
+                .title("Results")
+                .title_bottom(config.tie_breaking.describe(config.algorithm)),
+        );
+    frame.render_widget(table, area);
+}
+
+/// `text` on the background colour of the job, like the job labels in the window.
+fn job_span<'a>(job_id: u32, text: &str) -> Span<'a> {
+    let [r, g, b] = job_color(job_id);
+    let foreground = if 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32 > 128.0 {
+        Color::Black
+    } else {
+        Color::White
+    };
+    Span::styled(
+        text.to_string(),
+        Style::new()
+            .bg(Color::Rgb(r, g, b))
+            .fg(foreground)
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+/// One row per core plus a time axis, scaled to fit in `width` characters.
+fn gantt_lines(schedule: &Schedule, cores: u32, width: usize) -> Vec<Line<'static>> {
+    let makespan = schedule
+        .segments
+        .iter()
+        .map(|segment| segment.end)
+        .max()
+        .unwrap_or(0);
+    let Some(gantt) = text_gantt(&schedule.segments, cores, makespan, width) else {
+        return vec![Line::from("No jobs scheduled")];
+    };
+
+    let mut lines = Vec::new();
+    for (core, row) in gantt.rows.iter().enumerate() {
+        let mut spans = vec![Span::raw(format!("core {core:<3}|"))];
+        for cell in row {
+            match *cell {
+                GanttCell::Start(index) | GanttCell::Continue(index) => {
+                    let segment = &schedule.segments[index];
+                    // Label the first column of every segment
+                    let label = match cell {
+                        GanttCell::Start(_) => segment.job_name.chars().next().unwrap_or(' '),
+                        _ => ' ',
+                    };
+                    let mut span = job_span(segment.job_id, &label.to_string());
+                    span.style = span.style.remove_modifier(Modifier::BOLD);
+                    spans.push(span);
+                }
+                GanttCell::Idle => spans.push(Span::styled("·", Style::new().fg(Color::DarkGray))),
+            }
+        }
+        spans.push(Span::raw("|"));
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(format!("         {}", gantt.axis)));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(tui: &mut Tui, code: KeyCode) {
+        tui.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn typing_digits_edits_selected_cell() {
+        let mut tui = Tui::new(job_builder(&[], 2), SchedulerConfig::default());
+        press(&mut tui, KeyCode::Down);
+        press(&mut tui, KeyCode::Right);
+        press(&mut tui, KeyCode::Char('1'));
+        press(&mut tui, KeyCode::Char('2'));
+        assert_eq!(tui.jobs[1].arrival_time, 12);
+        press(&mut tui, KeyCode::Left);
+        press(&mut tui, KeyCode::Char('0'));
+        assert_eq!(
+            tui.jobs[1].needed_cpu_cycle, 1,
+            "CPU cycle never drops below 1"
+        );
+    }
+
+    #[test]
+    fn move_job_keeps_it_selected() {
+        let mut tui = Tui::new(job_builder(&[], 3), SchedulerConfig::default());
+        press(&mut tui, KeyCode::Char('J'));
+        press(&mut tui, KeyCode::Char('J'));
+        assert_eq!(tui.selected_job, 2);
+        assert_eq!(tui.jobs[2].job_name, "A");
+    }
+}
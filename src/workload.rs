@@ -0,0 +1,101 @@
+use std::path::Path;
+
+use crate::dependencies::topological_order;
+use crate::locking::check_critical_sections;
+use crate::memory::check_memory;
+use crate::process_scheduler::{ensure_unique_ids, Job, SchedulerConfig};
+
+/// Jobs together with the scheduler settings they should run under, as stored in workload files.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Workload {
+    pub jobs: Vec<Job>,
+    pub config: SchedulerConfig,
+}
+
+impl Workload {
+    /// Jobs without an id are given one. Jobs depending on each other in a cycle are rejected.
+    pub fn from_json(json: &str) -> Result<Workload, String> {
+        let mut workload: Workload =
+            serde_json::from_str(json).map_err(|error| format!("invalid workload: {error}"))?;
+        ensure_unique_ids(&mut workload.jobs);
+        topological_order(&workload.jobs)?;
+        check_critical_sections(&workload.jobs)?;
+        if let Some(memory) = &workload.config.memory {
+            check_memory(&workload.jobs, memory)?;
+        }
+        if let Some(power) = &workload.config.power {
+            power.check()?;
+        }
+        if let Some(multilevel) = &workload.config.multilevel {
+            multilevel.check()?;
+        }
+        Ok(workload)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("workloads always serialize")
+    }
+
+    pub fn load(path: &Path) -> Result<Workload, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|error| format!("cannot read {}: {error}", path.display()))?;
+        Workload::from_json(&json)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_json())
+            .map_err(|error| format!("cannot write {}: {error}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_scheduler::Algorithm;
+
+    #[test]
+    fn json_round_trip() {
+        let workload = Workload {
+            jobs: vec![Job {
+                id: 1,
+                job_name: "A".to_string(),
+                arrival_time: 2,
+                needed_cpu_cycle: 7,
+                ..Default::default()
+            }],
+            config: SchedulerConfig {
+                algorithm: Algorithm::RoundRobin,
+                time_quantum: 3,
+                ..Default::default()
+            },
+        };
+        assert_eq!(Workload::from_json(&workload.to_json()), Ok(workload));
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let workload =
+            Workload::from_json(r#"{"jobs": [{"job_name": "A", "needed_cpu_cycle": 4}]}"#).unwrap();
+        assert_eq!(workload.jobs[0].arrival_time, 0);
+        assert_eq!(workload.jobs[0].id, 1);
+        assert_eq!(workload.config, SchedulerConfig::default());
+    }
+
+    #[test]
+    fn dependency_cycles_are_rejected() {
+        let json = r#"{"jobs": [
+            {"id": 1, "job_name": "A", "needed_cpu_cycle": 1, "depends_on": [2]},
+            {"id": 2, "job_name": "B", "needed_cpu_cycle": 1, "depends_on": [1]}
+        ]}"#;
+        assert_eq!(
+            Workload::from_json(json),
+            Err("job dependencies form a cycle: B → A → B".to_string())
+        );
+        let acyclic = json.replace(r#""depends_on": [1]"#, r#""depends_on": []"#);
+        assert_eq!(
+            Workload::from_json(&acyclic).unwrap().jobs[0].depends_on,
+            [2]
+        );
+    }
+}